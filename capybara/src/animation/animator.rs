@@ -0,0 +1,365 @@
+use super::AnimationClip;
+use super::AnimationEvent;
+use super::AnimationFrame;
+use super::AnimationMode;
+use crate::assets::RawAnimation;
+use crate::renderer::sprite::Sprite;
+use anyhow::anyhow;
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use std::mem;
+
+#[derive(Clone, Debug)]
+pub struct Animator {
+    pub clips: Vec<AnimationClip>,
+    pub states: Vec<AnimatorState>,
+    pub transitions: Vec<AnimatorTransition>,
+    pub parameters: FxHashMap<String, AnimatorParameter>,
+    pub events: Vec<AnimationEvent>,
+    pub speed: f32,
+
+    state_index: usize,
+    clip_indices: Vec<usize>,
+    frame: usize,
+    elapsed: f32,
+    forward: bool,
+    loops: usize,
+    finished: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimatorState {
+    pub name: String,
+    pub clip: String,
+    pub speed: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimatorTransition {
+    pub from: Option<String>,
+    pub to: String,
+    pub conditions: Vec<AnimatorCondition>,
+    pub wait_for_end: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimatorCondition {
+    pub parameter: String,
+    pub comparison: AnimatorComparison,
+    pub value: AnimatorParameter,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AnimatorComparison {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AnimatorParameter {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Trigger(bool),
+}
+
+impl Animator {
+    pub fn new(animation: &RawAnimation) -> Result<Self> {
+        let mut animator = Self {
+            clips: animation.clips.clone(),
+            states: animation.states.clone(),
+            transitions: animation.transitions.clone(),
+            parameters: animation.parameters.iter().cloned().collect(),
+            events: Vec::default(),
+            speed: 1.0,
+
+            state_index: 0,
+            clip_indices: Vec::default(),
+            frame: 0,
+            elapsed: 0.0,
+            forward: true,
+            loops: 0,
+            finished: false,
+        };
+
+        // Every clip without an explicit state gets one with the same name, so simple files can omit the state machine
+        for clip in &animation.clips {
+            if !animator.states.iter().any(|p| p.name == clip.name) {
+                animator.states.push(AnimatorState::new(&clip.name, &clip.name, 1.0));
+            }
+        }
+
+        for state in &animator.states {
+            match animator.clips.iter().position(|p| p.name == state.clip) {
+                Some(index) => animator.clip_indices.push(index),
+                None => return Err(anyhow!("Clip {} used by state {} not found", state.clip, state.name)),
+            }
+        }
+
+        for transition in &animator.transitions {
+            if !animator.states.iter().any(|p| p.name == transition.to) {
+                return Err(anyhow!("Transition target {} not found", transition.to));
+            }
+        }
+
+        let initial_state = match &animation.initial_state {
+            Some(initial_state) => animator
+                .states
+                .iter()
+                .position(|p| p.name == *initial_state)
+                .ok_or_else(|| anyhow!("Initial state {} not found", initial_state))?,
+            None => 0,
+        };
+
+        if !animator.states.is_empty() {
+            animator.enter_state(initial_state);
+        }
+
+        Ok(animator)
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.states.is_empty() {
+            return;
+        }
+
+        self.process_transitions();
+
+        let clip = &self.clips[self.clip_indices[self.state_index]];
+        if self.finished || clip.frames.is_empty() {
+            return;
+        }
+
+        self.elapsed += delta * 1000.0 * self.states[self.state_index].speed * self.speed;
+
+        loop {
+            let clip = &self.clips[self.clip_indices[self.state_index]];
+            let duration = clip.frames[self.frame].duration;
+
+            if duration <= 0.0 || self.elapsed < duration {
+                break;
+            }
+
+            self.elapsed -= duration;
+
+            if !self.advance() {
+                self.elapsed = 0.0;
+                self.finished = true;
+                break;
+            }
+
+            self.emit_events();
+        }
+    }
+
+    pub fn drain_events(&mut self) -> Vec<AnimationEvent> {
+        // Events are kept until consumed, so the ones emitted by set_state outside of update aren't lost
+        mem::take(&mut self.events)
+    }
+
+    pub fn apply(&self, sprite: &mut Sprite) {
+        if let Some(frame) = self.get_frame() {
+            frame.source.apply(sprite);
+        }
+    }
+
+    pub fn set_state(&mut self, name: &str) -> Result<()> {
+        let index = self.states.iter().position(|p| p.name == name).ok_or_else(|| anyhow!("State {} not found", name))?;
+        self.enter_state(index);
+
+        Ok(())
+    }
+
+    pub fn get_state(&self) -> Option<&AnimatorState> {
+        self.states.get(self.state_index)
+    }
+
+    pub fn get_clip(&self) -> Option<&AnimationClip> {
+        self.clip_indices.get(self.state_index).map(|p| &self.clips[*p])
+    }
+
+    pub fn get_frame(&self) -> Option<&AnimationFrame> {
+        self.get_clip().and_then(|p| p.frames.get(self.frame))
+    }
+
+    pub fn get_frame_index(&self) -> usize {
+        self.frame
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: AnimatorParameter) {
+        self.parameters.insert(name.to_string(), value);
+    }
+
+    pub fn get_parameter(&self, name: &str) -> Option<AnimatorParameter> {
+        self.parameters.get(name).copied()
+    }
+
+    pub fn set_trigger(&mut self, name: &str) {
+        self.set_parameter(name, AnimatorParameter::Trigger(true));
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn enter_state(&mut self, index: usize) {
+        self.state_index = index;
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.forward = true;
+        self.loops = 0;
+        self.finished = false;
+
+        self.emit_events();
+    }
+
+    fn process_transitions(&mut self) {
+        let current_state = &self.states[self.state_index].name;
+        let completed = self.finished || self.loops > 0;
+
+        let transition = self.transitions.iter().find(|transition| {
+            let source_valid = match &transition.from {
+                Some(from) => from == current_state,
+                None => transition.to != *current_state,
+            };
+
+            source_valid && (!transition.wait_for_end || completed) && transition.conditions.iter().all(|p| p.evaluate(&self.parameters))
+        });
+
+        if let Some(transition) = transition.cloned() {
+            for condition in &transition.conditions {
+                if let Some(AnimatorParameter::Trigger(value)) = self.parameters.get_mut(&condition.parameter) {
+                    *value = false;
+                }
+            }
+
+            if let Some(index) = self.states.iter().position(|p| p.name == transition.to) {
+                self.enter_state(index);
+            }
+        }
+    }
+
+    fn advance(&mut self) -> bool {
+        let clip = &self.clips[self.clip_indices[self.state_index]];
+        let frames_count = clip.frames.len();
+
+        match clip.mode {
+            AnimationMode::Once => {
+                if self.frame + 1 >= frames_count {
+                    return false;
+                }
+
+                self.frame += 1;
+            }
+            AnimationMode::Loop => {
+                self.frame = (self.frame + 1) % frames_count;
+                if self.frame == 0 {
+                    self.loops += 1;
+                }
+            }
+            AnimationMode::PingPong => {
+                if frames_count == 1 {
+                    self.loops += 1;
+                    return true;
+                }
+
+                if self.forward && self.frame + 1 >= frames_count {
+                    self.forward = false;
+                } else if !self.forward && self.frame == 0 {
+                    self.forward = true;
+                }
+
+                if self.forward {
+                    self.frame += 1;
+                } else {
+                    self.frame -= 1;
+                    if self.frame == 0 {
+                        self.loops += 1;
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    fn emit_events(&mut self) {
+        let clip = &self.clips[self.clip_indices[self.state_index]];
+        if let Some(frame) = clip.frames.get(self.frame) {
+            for event in &frame.events {
+                self.events.push(AnimationEvent::new(event, &clip.name, self.frame));
+            }
+        }
+    }
+}
+
+impl AnimatorState {
+    pub fn new(name: &str, clip: &str, speed: f32) -> Self {
+        Self { name: name.to_string(), clip: clip.to_string(), speed }
+    }
+}
+
+impl AnimatorTransition {
+    pub fn new(from: Option<&str>, to: &str, conditions: Vec<AnimatorCondition>, wait_for_end: bool) -> Self {
+        Self { from: from.map(|p| p.to_string()), to: to.to_string(), conditions, wait_for_end }
+    }
+}
+
+impl AnimatorCondition {
+    pub fn new(parameter: &str, comparison: AnimatorComparison, value: AnimatorParameter) -> Self {
+        Self { parameter: parameter.to_string(), comparison, value }
+    }
+
+    pub fn evaluate(&self, parameters: &FxHashMap<String, AnimatorParameter>) -> bool {
+        let parameter = match parameters.get(&self.parameter) {
+            Some(parameter) => parameter,
+            None => return false,
+        };
+
+        match parameter {
+            AnimatorParameter::Trigger(value) => *value,
+            AnimatorParameter::Bool(value) => match self.comparison {
+                AnimatorComparison::Equal => *value == self.value.as_bool(),
+                AnimatorComparison::NotEqual => *value != self.value.as_bool(),
+                _ => false,
+            },
+            _ => {
+                let a = parameter.as_f32();
+                let b = self.value.as_f32();
+
+                match self.comparison {
+                    AnimatorComparison::Equal => a == b,
+                    AnimatorComparison::NotEqual => a != b,
+                    AnimatorComparison::Greater => a > b,
+                    AnimatorComparison::GreaterOrEqual => a >= b,
+                    AnimatorComparison::Less => a < b,
+                    AnimatorComparison::LessOrEqual => a <= b,
+                }
+            }
+        }
+    }
+}
+
+impl AnimatorParameter {
+    pub fn as_bool(&self) -> bool {
+        match self {
+            AnimatorParameter::Bool(value) => *value,
+            AnimatorParameter::Int(value) => *value != 0,
+            AnimatorParameter::Float(value) => *value != 0.0,
+            AnimatorParameter::Trigger(value) => *value,
+        }
+    }
+
+    pub fn as_f32(&self) -> f32 {
+        match self {
+            AnimatorParameter::Bool(value) => *value as i32 as f32,
+            AnimatorParameter::Int(value) => *value as f32,
+            AnimatorParameter::Float(value) => *value,
+            AnimatorParameter::Trigger(value) => *value as i32 as f32,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::renderer::sprite::Sprite;
+use crate::renderer::sprite::TextureType;
+use glam::Vec2;
+
+pub mod animator;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnimationClip {
+    pub name: String,
+    pub mode: AnimationMode,
+    pub frames: Vec<AnimationFrame>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationFrame {
+    pub source: AnimationFrameSource,
+    pub duration: f32,
+    pub events: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationFrameSource {
+    Tile { size: Vec2, index: usize },
+    AtlasEntity { name: String },
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum AnimationMode {
+    Once,
+    #[default]
+    Loop,
+    PingPong,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationEvent {
+    pub name: String,
+    pub clip: String,
+    pub frame: usize,
+}
+
+impl AnimationClip {
+    pub fn new(name: &str, mode: AnimationMode, frames: Vec<AnimationFrame>) -> Self {
+        Self { name: name.to_string(), mode, frames }
+    }
+
+    pub fn get_duration(&self) -> f32 {
+        self.frames.iter().map(|p| p.duration).sum()
+    }
+
+    pub fn get_texture_type(&self) -> Option<TextureType> {
+        let entities = self
+            .frames
+            .iter()
+            .map(|p| match &p.source {
+                AnimationFrameSource::AtlasEntity { name } => Some(name.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<String>>>()?;
+
+        Some(TextureType::AtlasAnimation { entities })
+    }
+}
+
+impl AnimationFrame {
+    pub fn new(source: AnimationFrameSource, duration: f32) -> Self {
+        Self { source, duration, events: Vec::default() }
+    }
+}
+
+impl AnimationFrameSource {
+    pub fn apply(&self, sprite: &mut Sprite) {
+        match self {
+            AnimationFrameSource::Tile { size, index } => {
+                sprite.texture_type = TextureType::Tilemap { size: *size };
+                sprite.animation_frame = *index as i32;
+            }
+            AnimationFrameSource::AtlasEntity { name } => {
+                if !matches!(&sprite.texture_type, TextureType::AtlasEntity { name: current } if current == name) {
+                    sprite.texture_type = TextureType::AtlasEntity { name: name.clone() };
+                }
+            }
+        }
+    }
+}
+
+impl AnimationEvent {
+    pub fn new(name: &str, clip: &str, frame: usize) -> Self {
+        Self { name: name.to_string(), clip: clip.to_string(), frame }
+    }
+}
@@ -1,19 +1,34 @@
+use crate::assets::preloader::AssetsPreloader;
+use crate::clock::Clock;
+use crate::ecs::systems;
+use crate::ecs::systems::System;
+use crate::ecs::systems::SystemDefinition;
+use crate::ecs::systems::SystemPhase;
+use crate::ecs::world::World;
 use crate::error_break;
 use crate::error_continue;
+use crate::error_return;
 use crate::renderer::context::RendererContext;
 use crate::scene::FrameCommand;
 use crate::scene::Scene;
+use crate::scene::SceneFlags;
+use crate::transition::TransitionContext;
 use crate::ui::context::UiContext;
 use crate::utils::storage::Storage;
 use crate::window::InputEvent;
 use crate::window::WindowContext;
 use crate::window::WindowStyle;
+use anyhow::anyhow;
 use anyhow::Result;
+use egui::FullOutput;
+use egui::PointerState;
+use egui::RawInput;
+use glam::IVec2;
 use glam::Vec2;
-use instant::Instant;
 use log::error;
 use log::info;
 use std::cell::RefCell;
+use std::mem;
 use std::rc::Rc;
 
 #[cfg(feature = "audio")]
@@ -31,6 +46,15 @@ where
     pub ui: UiContext,
     pub scenes: Storage<Box<dyn Scene<G>>>,
     pub global: G,
+    pub clock: Clock,
+    pub transition: TransitionContext,
+    pub preloader: AssetsPreloader,
+    pub world: World,
+    pub systems: Vec<SystemDefinition<G>>,
+
+    // Interval of polling loose asset directories for changes, hot reload is disabled if not set
+    #[cfg(any(windows, unix))]
+    pub hot_reload: Option<f32>,
 
     #[cfg(feature = "audio")]
     pub audio: AudioContext,
@@ -38,12 +62,12 @@ where
     #[cfg(feature = "physics")]
     pub physics: PhysicsContext,
 
-    current_scene: String,
-    next_scene: Option<String>,
-    frame_timestamp: Instant,
+    scene_stack: Vec<String>,
+    scene_commands: Vec<FrameCommand>,
+    loading_scene: Option<String>,
+    preloading_command: Option<FrameCommand>,
     running: bool,
-    timestep: f32,
-    accumulator: f32,
+    frames_left: Option<u32>,
 }
 
 pub struct ApplicationState<'a, G> {
@@ -51,6 +75,9 @@ pub struct ApplicationState<'a, G> {
     pub renderer: &'a mut RendererContext,
     pub ui: &'a mut UiContext,
     pub global: &'a mut G,
+    pub clock: &'a mut Clock,
+    pub preloader: &'a mut AssetsPreloader,
+    pub world: &'a mut World,
 
     #[cfg(feature = "audio")]
     pub audio: &'a mut AudioContext,
@@ -66,6 +93,9 @@ macro_rules! state {
             renderer: &mut $self.renderer,
             ui: &mut $self.ui,
             global: &mut $self.global,
+            clock: &mut $self.clock,
+            preloader: &mut $self.preloader,
+            world: &mut $self.world,
 
             #[cfg(feature = "audio")]
             audio: &mut $self.audio,
@@ -84,6 +114,7 @@ where
         let window = WindowContext::new(title, style, msaa)?;
         let mut renderer = RendererContext::new(window.load_gl_pointers(), msaa)?;
         let ui = UiContext::new(&mut renderer);
+        let transition = TransitionContext::new(&mut renderer)?;
 
         #[cfg(feature = "audio")]
         let audio = AudioContext::new()?;
@@ -97,6 +128,14 @@ where
             ui,
             scenes: Storage::default(),
             global: G::default(),
+            clock: Clock::default(),
+            transition,
+            preloader: AssetsPreloader::default(),
+            world: World::default(),
+            systems: Vec::default(),
+
+            #[cfg(any(windows, unix))]
+            hot_reload: None,
 
             #[cfg(feature = "audio")]
             audio,
@@ -104,12 +143,12 @@ where
             #[cfg(feature = "physics")]
             physics,
 
-            current_scene: String::default(),
-            next_scene: None,
-            frame_timestamp: Instant::now(),
+            scene_stack: Vec::default(),
+            scene_commands: Vec::default(),
+            loading_scene: None,
+            preloading_command: None,
             running: true,
-            timestep: 1.0 / 60.0,
-            accumulator: 0.0,
+            frames_left: None,
         })
     }
 
@@ -121,6 +160,32 @@ where
         self
     }
 
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    #[cfg(any(windows, unix))]
+    pub fn with_hot_reload(mut self, interval: f32) -> Self {
+        self.hot_reload = Some(interval);
+        self
+    }
+
+    pub fn with_system(mut self, name: &str, phase: SystemPhase, system: System<G>) -> Self {
+        self.systems.push(SystemDefinition::new(name, phase, system));
+        self
+    }
+
+    pub fn with_default_systems(mut self) -> Self {
+        self.systems.extend(systems::get_default_systems());
+        self
+    }
+
+    pub fn with_loading_scene(mut self, name: &str) -> Self {
+        self.loading_scene = Some(name.to_string());
+        self
+    }
+
     pub fn run(self, scene: &str) {
         let app = Rc::new(RefCell::new(self));
         let mut app_borrow = app.borrow_mut();
@@ -132,48 +197,44 @@ where
 
         info!("Engine initialization completed, going to main loop");
 
-        app_borrow.next_scene = Some(scene.to_string());
+        app_borrow.scene_commands.push(FrameCommand::ChangeScene { name: scene.to_string() });
         app_borrow.window.set_swap_interval(1);
         app_borrow.run_internal();
     }
 
-    pub fn run_internal(&mut self) {
-        while self.running {
-            self.renderer.begin_frame();
-
-            if let Some(next_scene) = &self.next_scene {
-                info!("Changing scene from {} to {}", self.current_scene, next_scene);
+    pub fn run_frames(&mut self, scene: Option<&str>, count: u32) {
+        if let Some(scene) = scene {
+            self.scene_commands.push(FrameCommand::ChangeScene { name: scene.to_string() });
+        }
 
-                if !self.current_scene.is_empty() {
-                    let scene = match self.scenes.get_by_name_mut(&self.current_scene) {
-                        Ok(scene) => scene,
-                        Err(err) => error_break!("Failed to get scene {} ({})", self.current_scene, err),
-                    };
+        self.frames_left = Some(count);
+        self.run_internal();
+        self.frames_left = None;
+    }
 
-                    if let Err(err) = scene.deactivation(state!(self)) {
-                        error_break!("Failed to deactivate scene {} ({})", self.current_scene, err);
-                    };
+    pub fn run_internal(&mut self) {
+        while self.running {
+            if let Some(frames_left) = &mut self.frames_left {
+                if *frames_left == 0 {
+                    break;
+                }
 
-                    let current_scene_reset = scene.reset();
-                    self.scenes.remove_by_name(&self.current_scene);
+                *frames_left -= 1;
+            }
 
-                    if let Err(err) = self.scenes.store_with_name(&self.current_scene, current_scene_reset) {
-                        error!("Failed to reset scene {} ({})", self.current_scene, err)
-                    }
-                }
+            self.renderer.begin_frame();
+            self.process_preloading();
 
-                if let Err(err) = self.scenes.get_by_name_mut(next_scene).and_then(|p| p.activation(state!(self))) {
-                    error_break!("Failed to activate scene {} ({})", next_scene, err);
-                };
+            #[cfg(any(windows, unix))]
+            self.process_reloading();
 
-                self.current_scene = next_scene.clone();
-                self.next_scene = None;
+            if let Err(err) = self.process_scene_commands() {
+                error_break!("Failed to process scene commands ({})", err);
             }
 
-            let scene = match self.scenes.get_by_name_mut(&self.current_scene) {
-                Ok(scene) => scene,
-                Err(err) => error_break!("Failed to get scene {} ({})", self.current_scene, err),
-            };
+            if self.scene_stack.is_empty() && !self.preloader.is_loading() {
+                error_break!("Scene stack is empty");
+            }
 
             while let Some(event) = self.window.poll_event() {
                 match event {
@@ -182,67 +243,395 @@ where
                     _ => {}
                 }
 
+                let event = self.map_virtual_event(event);
                 self.ui.collect_event(&event);
-
-                if let Err(err) = scene.input(state!(self), event) {
-                    error_continue!("Failed to process input event {:?} ({})", event, err);
-                }
+                self.process_input(event);
             }
 
+            // Interface is rendered into the same target as the scene, so with virtual resolution it has to use its size too
+            self.ui.screen_size = self.renderer.viewport_size;
+
             let ui_input = self.ui.get_input();
-            let (ui_output, command) = match scene.ui(state!(self), ui_input) {
-                Ok((ui_output, command)) => (ui_output, command),
-                Err(err) => error_continue!("Failed to process UI ({})", err),
-            };
-            self.process_frame_command(command);
+            let ui_output = self.process_ui(ui_input);
+            let delta = self.clock.tick();
 
-            let now = Instant::now();
-            let mut delta = (now - self.frame_timestamp).as_secs_f32();
+            while self.clock.step() {
+                #[cfg(feature = "physics")]
+                self.physics.step(self.clock.timestep);
 
-            if delta > 0.1 {
-                delta = 0.1;
+                self.process_fixed();
             }
 
-            self.frame_timestamp = now;
-            self.accumulator += delta;
+            if self.transition.is_active() {
+                self.transition.set_target(&mut self.renderer, self.transition.incoming_texture_id);
+            }
 
-            while self.accumulator >= self.timestep {
-                #[cfg(feature = "physics")]
-                self.physics.step(self.timestep);
+            self.process_frame(ui_output, self.clock.get_alpha(), delta);
 
-                let command = match self.scenes.get_by_name_mut(&self.current_scene).and_then(|p| p.fixed(state!(self))) {
-                    Ok(command) => command,
-                    Err(err) => error_continue!("Failed to process fixed frame ({})", err),
-                };
+            if self.transition.is_active() {
+                self.transition.draw(&mut self.renderer);
 
-                self.process_frame_command(command);
-                self.accumulator -= self.timestep;
+                // Transition isn't a part of the simulation, so it has to finish even if the clock is paused or scaled down
+                self.transition.update(self.clock.real_delta);
             }
 
-            let command = match self.scenes.get_by_name_mut(&self.current_scene).and_then(|p| p.frame(state!(self), self.accumulator, delta)) {
+            self.renderer.end_frame();
+            self.window.swap_buffers();
+
+            #[cfg(web)]
+            break;
+        }
+    }
+
+    pub fn get_current_scene(&self) -> &str {
+        self.scene_stack.last().map(|p| p.as_str()).unwrap_or_default()
+    }
+
+    pub fn get_scene_stack(&self) -> &[String] {
+        &self.scene_stack
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn process_input(&mut self, event: InputEvent) {
+        let input_from = self.get_stack_bottom(|flags| flags.input_passthrough);
+
+        for index in (input_from..self.scene_stack.len()).rev() {
+            let name = &self.scene_stack[index];
+            if let Err(err) = self.scenes.get_by_name_mut(name).and_then(|p| p.input(state!(self), event)) {
+                error_continue!("Failed to process input event {:?} in scene {} ({})", event, name, err);
+            }
+        }
+    }
+
+    fn process_ui(&mut self, input: RawInput) -> FullOutput {
+        let input_from = self.get_stack_bottom(|flags| flags.input_passthrough);
+        let render_from = self.get_stack_bottom(|flags| flags.render_below);
+        let context = self.ui.inner.read().clone();
+        let mut commands = Vec::default();
+
+        // All visible scenes share a single pass, so egui can resolve overlapping windows and keep its state consistent between frames
+        let output = context.run(input, |context| {
+            let input = context.input(|input| input.clone());
+
+            for index in render_from..self.scene_stack.len() {
+                // Scenes below the input bottom are built without pointer and keyboard, so their widgets are visible but can't be interacted with
+                if index < input_from {
+                    context.input_mut(|input| {
+                        input.pointer = PointerState::default();
+                        input.scroll_delta = Default::default();
+                        input.keys_down.clear();
+                        input.events.clear();
+                    });
+                } else if index == input_from {
+                    context.input_mut(|current| *current = input.clone());
+                }
+
+                let name = &self.scene_stack[index];
+                match self.scenes.get_by_name_mut(name).and_then(|p| p.ui(state!(self), context)) {
+                    Ok(command) => commands.push(command),
+                    Err(err) => error!("Failed to process UI in scene {} ({})", name, err),
+                }
+            }
+        });
+
+        for command in commands {
+            self.process_frame_command(command);
+        }
+
+        output
+    }
+
+    fn process_fixed(&mut self) {
+        let update_from = self.get_stack_bottom(|flags| flags.update_below);
+
+        for index in update_from..self.scene_stack.len() {
+            let name = &self.scene_stack[index];
+            let command = match self.scenes.get_by_name_mut(name).and_then(|p| p.fixed(state!(self))) {
                 Ok(command) => command,
-                Err(err) => error_continue!("Failed to process frame ({})", err),
+                Err(err) => error_continue!("Failed to process fixed frame in scene {} ({})", name, err),
             };
 
             self.process_frame_command(command);
-            self.renderer.flush_buffer();
+        }
 
-            self.ui.draw(&mut self.renderer, ui_output);
+        self.process_systems(SystemPhase::Fixed);
+    }
 
-            self.renderer.end_frame();
-            self.window.swap_buffers();
+    fn process_frame(&mut self, ui_output: FullOutput, alpha: f32, delta: f32) {
+        let update_from = self.get_stack_bottom(|flags| flags.update_below);
+        let render_from = self.get_stack_bottom(|flags| flags.render_below);
 
-            #[cfg(web)]
-            break;
+        for index in render_from..self.scene_stack.len() {
+            if self.renderer.active_camera_id != self.renderer.default_camera_id {
+                self.renderer.set_camera(self.renderer.default_camera_id);
+            }
+
+            let name = &self.scene_stack[index];
+            let scene_delta = if index >= update_from { delta } else { 0.0 };
+
+            match self.scenes.get_by_name_mut(name).and_then(|p| p.frame(state!(self), alpha, scene_delta)) {
+                Ok(command) => self.process_frame_command(command),
+                Err(err) => error!("Failed to process frame in scene {} ({})", name, err),
+            };
+
+            // Entities belong to the bottom visible scene, so overlays are always drawn on top of them
+            if index == render_from {
+                self.process_systems(SystemPhase::Frame);
+            }
+
+            self.renderer.flush_buffer();
+        }
+
+        self.ui.draw(&mut self.renderer, ui_output);
+    }
+
+    fn process_systems(&mut self, phase: SystemPhase) {
+        for definition in &self.systems {
+            if definition.enabled && definition.phase == phase {
+                if let Err(err) = (definition.system)(state!(self)) {
+                    error!("Failed to process system {} ({})", definition.name, err);
+                }
+            }
         }
     }
 
     fn process_frame_command(&mut self, command: Option<FrameCommand>) {
         match command {
-            Some(FrameCommand::ChangeScene { name }) => self.next_scene = Some(name),
-            Some(FrameCommand::ResetScene) => self.next_scene = Some(self.current_scene.clone()),
             Some(FrameCommand::Exit) => self.running = false,
+            Some(command) => self.scene_commands.push(command),
             None => {}
         }
     }
+
+    fn process_scene_commands(&mut self) -> Result<()> {
+        for command in mem::take(&mut self.scene_commands) {
+            if self.preload_scene_assets(&command)? {
+                continue;
+            }
+
+            match command {
+                FrameCommand::ChangeScene { name } => {
+                    info!("Changing scene from {} to {}", self.get_current_scene(), name);
+
+                    while !self.scene_stack.is_empty() {
+                        self.deactivate_scene()?;
+                    }
+
+                    self.activate_scene(&name)?;
+                }
+                FrameCommand::TransitionScene { name, transition } => {
+                    info!("Changing scene from {} to {} ({:?})", self.get_current_scene(), name, transition.kind);
+
+                    // Outgoing scenes are rendered once more into the texture, which is then blended with the incoming ones
+                    let mut ui_input = self.ui.get_input();
+                    ui_input.events.clear();
+
+                    self.transition.set_target(&mut self.renderer, self.transition.outgoing_texture_id);
+                    let ui_output = self.process_ui(ui_input);
+                    self.process_frame(ui_output, self.clock.get_alpha(), 0.0);
+                    self.renderer.set_target_texture(None);
+                    self.scene_commands.clear();
+
+                    while !self.scene_stack.is_empty() {
+                        self.deactivate_scene()?;
+                    }
+
+                    self.activate_scene(&name)?;
+                    self.transition.begin(transition);
+                }
+                FrameCommand::PushScene { name } => {
+                    info!("Pushing scene {} on top of {}", name, self.get_current_scene());
+
+                    if self.scene_stack.contains(&name) {
+                        error_continue!("Scene {} is already on the stack", name);
+                    }
+
+                    self.activate_scene(&name)?;
+                }
+                FrameCommand::PopScene => {
+                    info!("Popping scene {}", self.get_current_scene());
+
+                    if self.scene_stack.len() <= 1 {
+                        error_continue!("There is no scene to return to");
+                    }
+
+                    self.deactivate_scene()?;
+                }
+                FrameCommand::ResetScene => {
+                    if self.scene_stack.is_empty() {
+                        error_continue!("There is no scene to reset");
+                    }
+
+                    let name = self.get_current_scene().to_string();
+                    info!("Resetting scene {}", name);
+
+                    self.deactivate_scene()?;
+                    self.activate_scene(&name)?;
+                }
+                FrameCommand::Exit => self.running = false,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn preload_scene_assets(&mut self, command: &FrameCommand) -> Result<bool> {
+        let name = match get_command_scene(command) {
+            Some(name) => name,
+            None => return Ok(false),
+        };
+
+        if Some(name) == self.loading_scene.as_deref() {
+            return Ok(false);
+        }
+
+        let scene = self.scenes.get_by_name(name).map_err(|err| anyhow!("Failed to get scene {} ({})", name, err))?;
+        let mut missing = false;
+
+        for path in scene.assets() {
+            missing |= self.preloader.enqueue(&path);
+        }
+
+        if !missing {
+            return Ok(false);
+        }
+
+        info!("Scene {} is waiting for its assets", name);
+        self.preloading_command = Some(command.clone());
+
+        // Pushed scenes are waiting in the background, everything else replaces the stack with the loading scene
+        if let Some(loading_scene) = self.loading_scene.clone() {
+            if !matches!(command, FrameCommand::PushScene { name: _ }) && self.get_current_scene() != loading_scene {
+                while !self.scene_stack.is_empty() {
+                    self.deactivate_scene()?;
+                }
+
+                self.activate_scene(&loading_scene)?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn process_preloading(&mut self) {
+        if let Some(id) = self.preloader.process() {
+            let assets = self.preloader.packages.get_unchecked(id);
+            self.renderer.instantiate_assets(assets, None);
+            self.ui.instantiate_assets(assets, None);
+
+            #[cfg(feature = "audio")]
+            self.audio.instantiate_assets(assets, None, None);
+        }
+
+        if !self.preloader.is_loading() {
+            if let Some(command) = self.preloading_command.take() {
+                // Failed packages would be enqueued again by the command, so it's dropped instead of looping forever
+                if let Some(name) = get_command_scene(&command) {
+                    if let Ok(scene) = self.scenes.get_by_name(name) {
+                        if let Some(path) = scene.assets().iter().find(|p| self.preloader.is_failed(p)) {
+                            error_return!(
+                                "Failed to load package {} required by scene {}, staying on scene {}",
+                                path,
+                                name,
+                                self.get_current_scene()
+                            );
+                        }
+                    }
+                }
+
+                self.scene_commands.insert(0, command);
+            }
+        }
+    }
+
+    // Only packages mounted from loose directories have watchers, archives are never reloaded
+    #[cfg(any(windows, unix))]
+    fn process_reloading(&mut self) {
+        let interval = match self.hot_reload {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        for assets in self.preloader.packages.iter_mut() {
+            let paths = assets.reload_changed(interval);
+            if paths.is_empty() {
+                continue;
+            }
+
+            self.renderer.reload_assets(assets, &paths);
+            self.ui.reload_assets(assets, &paths);
+
+            #[cfg(feature = "audio")]
+            self.audio.reload_assets(assets, &paths, None);
+        }
+    }
+
+    fn activate_scene(&mut self, name: &str) -> Result<()> {
+        let scene = self.scenes.get_by_name_mut(name).map_err(|err| anyhow!("Failed to get scene {} ({})", name, err))?;
+        scene.activation(state!(self)).map_err(|err| anyhow!("Failed to activate scene {} ({})", name, err))?;
+        self.scene_stack.push(name.to_string());
+
+        Ok(())
+    }
+
+    fn deactivate_scene(&mut self) -> Result<()> {
+        let name = self.scene_stack.pop().ok_or_else(|| anyhow!("Scene stack is empty"))?;
+        let scene = self.scenes.get_by_name_mut(&name).map_err(|err| anyhow!("Failed to get scene {} ({})", name, err))?;
+        scene.deactivation(state!(self)).map_err(|err| anyhow!("Failed to deactivate scene {} ({})", name, err))?;
+
+        let scene_reset = scene.reset();
+        self.scenes.remove_by_name(&name);
+
+        if let Err(err) = self.scenes.store_with_name(&name, scene_reset) {
+            error!("Failed to reset scene {} ({})", name, err)
+        }
+
+        Ok(())
+    }
+
+    fn map_virtual_event(&self, event: InputEvent) -> InputEvent {
+        if self.renderer.get_virtual_resolution().is_none() {
+            return event;
+        }
+
+        let map = |position: IVec2| self.renderer.from_window_to_virtual_coordinates(position.as_vec2()).floor().as_ivec2();
+        match event {
+            InputEvent::MouseMove { position, modifiers } => InputEvent::MouseMove { position: map(position), modifiers },
+            InputEvent::MouseEnter { position, modifiers } => InputEvent::MouseEnter { position: map(position), modifiers },
+            InputEvent::MouseButtonPress { button, position, modifiers } => {
+                InputEvent::MouseButtonPress { button, position: map(position), modifiers }
+            }
+            InputEvent::MouseButtonRelease { button, position, modifiers } => {
+                InputEvent::MouseButtonRelease { button, position: map(position), modifiers }
+            }
+            InputEvent::TouchStart { id, position } => InputEvent::TouchStart { id, position: map(position) },
+            InputEvent::TouchMove { id, position } => InputEvent::TouchMove { id, position: map(position) },
+            InputEvent::TouchEnd { id, position } => InputEvent::TouchEnd { id, position: map(position) },
+            _ => event,
+        }
+    }
+
+    fn get_stack_bottom(&self, passthrough: fn(SceneFlags) -> bool) -> usize {
+        let mut index = self.scene_stack.len().saturating_sub(1);
+        while index > 0 {
+            match self.scenes.get_by_name(&self.scene_stack[index]) {
+                Ok(scene) if passthrough(scene.flags()) => index -= 1,
+                _ => break,
+            }
+        }
+
+        index
+    }
+}
+
+fn get_command_scene(command: &FrameCommand) -> Option<&str> {
+    match command {
+        FrameCommand::ChangeScene { name } => Some(name),
+        FrameCommand::TransitionScene { name, transition: _ } => Some(name),
+        FrameCommand::PushScene { name } => Some(name),
+        _ => None,
+    }
 }
@@ -0,0 +1,125 @@
+use super::RawAnimation;
+use crate::animation::animator::AnimatorComparison;
+use crate::animation::animator::AnimatorCondition;
+use crate::animation::animator::AnimatorParameter;
+use crate::animation::animator::AnimatorState;
+use crate::animation::animator::AnimatorTransition;
+use crate::animation::AnimationClip;
+use crate::animation::AnimationFrame;
+use crate::animation::AnimationFrameSource;
+use crate::animation::AnimationMode;
+use crate::utils::json;
+use anyhow::bail;
+use anyhow::Result;
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+
+pub fn load_animation(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<RawAnimation> {
+    let mut animation = RawAnimation {
+        name: name.to_string(),
+        path: path.to_string(),
+        initial_state: json::read_value_optional::<String>(data, "initialState")?,
+        ..Default::default()
+    };
+
+    for clip in json::read_array(data, "clips")? {
+        animation.clips.push(load_clip(clip)?);
+    }
+
+    for parameter in json::read_array_optional(data, "parameters")? {
+        let name = json::read_value::<String>(parameter, "name")?;
+        let kind = json::read_value::<String>(parameter, "type")?;
+        let value = parameter.get("value").unwrap_or(&JsonValue::Null);
+
+        let value = match kind.as_str() {
+            "Bool" => AnimatorParameter::Bool(value.get::<bool>().copied().unwrap_or(false)),
+            "Int" => AnimatorParameter::Int(value.get::<f64>().copied().unwrap_or(0.0) as i32),
+            "Float" => AnimatorParameter::Float(value.get::<f64>().copied().unwrap_or(0.0) as f32),
+            "Trigger" => AnimatorParameter::Trigger(false),
+            _ => bail!("Invalid parameter type {}", kind),
+        };
+
+        animation.parameters.push((name, value));
+    }
+
+    for state in json::read_array_optional(data, "states")? {
+        let name = json::read_value::<String>(state, "name")?;
+        let clip = json::read_value_optional::<String>(state, "clip")?.unwrap_or_else(|| name.clone());
+        let speed = json::read_value_optional::<f64>(state, "speed")?.unwrap_or(1.0) as f32;
+
+        animation.states.push(AnimatorState::new(&name, &clip, speed));
+    }
+
+    for transition in json::read_array_optional(data, "transitions")? {
+        let from = json::read_value_optional::<String>(transition, "from")?;
+        let to = json::read_value::<String>(transition, "to")?;
+        let wait_for_end = json::read_value_optional::<bool>(transition, "waitForEnd")?.unwrap_or(false);
+        let mut conditions = Vec::default();
+
+        for condition in json::read_array_optional(transition, "conditions")? {
+            let parameter = json::read_value::<String>(condition, "parameter")?;
+            let comparison = json::read_value_optional::<String>(condition, "comparison")?.unwrap_or_else(|| "==".to_string());
+            let comparison = match comparison.as_str() {
+                "==" => AnimatorComparison::Equal,
+                "!=" => AnimatorComparison::NotEqual,
+                ">" => AnimatorComparison::Greater,
+                ">=" => AnimatorComparison::GreaterOrEqual,
+                "<" => AnimatorComparison::Less,
+                "<=" => AnimatorComparison::LessOrEqual,
+                _ => bail!("Invalid comparison {}", comparison),
+            };
+            let value = match condition.get("value") {
+                Some(JsonValue::Boolean(value)) => AnimatorParameter::Bool(*value),
+                Some(JsonValue::Number(value)) => AnimatorParameter::Float(*value as f32),
+                None | Some(JsonValue::Null) => AnimatorParameter::Bool(true),
+                _ => bail!("Invalid value of condition {}", parameter),
+            };
+
+            conditions.push(AnimatorCondition::new(&parameter, comparison, value));
+        }
+
+        animation.transitions.push(AnimatorTransition::new(from.as_deref(), &to, conditions, wait_for_end));
+    }
+
+    Ok(animation)
+}
+
+fn load_clip(data: &HashMap<String, JsonValue>) -> Result<AnimationClip> {
+    let name = json::read_value::<String>(data, "name")?;
+    let mode = json::read_value_optional::<String>(data, "mode")?.unwrap_or_else(|| "Loop".to_string());
+    let tile_size = if data.contains_key("tileSize") { Some(json::read_vec2(data, "tileSize")?) } else { None };
+    let default_duration = json::read_value_optional::<f64>(data, "duration")?.unwrap_or(100.0) as f32;
+
+    let mode = match mode.as_str() {
+        "Once" => AnimationMode::Once,
+        "Loop" => AnimationMode::Loop,
+        "PingPong" => AnimationMode::PingPong,
+        _ => bail!("Invalid animation mode {}", mode),
+    };
+
+    let mut frames = Vec::default();
+    for frame in json::read_array(data, "frames")? {
+        let source = if let Some(entity) = json::read_value_optional::<String>(frame, "entity")? {
+            AnimationFrameSource::AtlasEntity { name: entity }
+        } else if let Some(tile) = json::read_value_optional::<f64>(frame, "tile")? {
+            let size = match tile_size {
+                Some(tile_size) => tile_size,
+                None => bail!("Clip {} has tile frames but no tileSize", name),
+            };
+
+            AnimationFrameSource::Tile { size, index: tile as usize }
+        } else {
+            bail!("Frame of clip {} has neither entity nor tile", name);
+        };
+
+        let duration = json::read_value_optional::<f64>(frame, "duration")?.map(|p| p as f32).unwrap_or(default_duration);
+        let events = match frame.get("events") {
+            Some(JsonValue::Array(events)) => events.iter().filter_map(|p| p.get::<String>().cloned()).collect(),
+            _ => Vec::default(),
+        };
+
+        frames.push(AnimationFrame { source, duration, events });
+    }
+
+    Ok(AnimationClip::new(&name, mode, frames))
+}
@@ -0,0 +1,145 @@
+use super::RawAnimation;
+use super::RawAtlas;
+use super::RawAtlasEntity;
+use super::RawAtlasEntityCenter;
+use crate::animation::AnimationClip;
+use crate::animation::AnimationFrame;
+use crate::animation::AnimationFrameSource;
+use crate::animation::AnimationMode;
+use crate::utils::json;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use glam::Vec2;
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+
+pub fn is_sheet(data: &HashMap<String, JsonValue>) -> bool {
+    match data.get("meta") {
+        Some(JsonValue::Object(meta)) => data.contains_key("frames") && meta.contains_key("image"),
+        _ => false,
+    }
+}
+
+pub fn load_sheet(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<(RawAtlas, RawAnimation)> {
+    let meta = json::read_object(data, "meta")?;
+    let image = json::read_value::<String>(meta, "image")?;
+    let mut frames = Vec::default();
+
+    match data.get("frames") {
+        Some(JsonValue::Object(hash)) => {
+            for (frame_name, frame) in hash {
+                let frame = frame.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read frame {}", frame_name))?;
+                frames.push((frame_name.clone(), frame));
+            }
+
+            // JSON objects are unordered, so the frame number embedded in the name is the only way to restore the timeline
+            frames.sort_by_key(|(frame_name, _)| get_sorting_key(frame_name));
+        }
+        Some(JsonValue::Array(array)) => {
+            for frame in array {
+                let frame = frame.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read frame"))?;
+                frames.push((json::read_value::<String>(frame, "filename")?, frame));
+            }
+        }
+        _ => bail!("Failed to read frames"),
+    }
+
+    let mut entities = Vec::default();
+    let mut durations = Vec::default();
+
+    for (frame_name, frame) in &frames {
+        let (position, size) = read_rect(json::read_object(frame, "frame")?)?;
+
+        entities.push(RawAtlasEntity::new(frame_name, path, position, size));
+        durations.push(json::read_value_optional::<f64>(frame, "duration")?.unwrap_or(100.0) as f32);
+    }
+
+    for slice in json::read_array_optional(meta, "slices")? {
+        let slice_name = json::read_value::<String>(slice, "name")?;
+        let key = match json::read_array(slice, "keys")?.first() {
+            Some(key) => *key,
+            None => bail!("Slice {} has no keys", slice_name),
+        };
+
+        let frame_index = json::read_value::<f64>(key, "frame")? as usize;
+        let frame = match frames.get(frame_index) {
+            Some((_, frame)) => frame,
+            None => bail!("Slice {} refers to invalid frame {}", slice_name, frame_index),
+        };
+
+        let (frame_position, _) = read_rect(json::read_object(frame, "frame")?)?;
+        let source_offset = match json::read_object_nullable(frame, "spriteSourceSize") {
+            Ok(Some(source)) => read_rect(source)?.0,
+            _ => Vec2::ZERO,
+        };
+        let (bounds_position, bounds_size) = read_rect(json::read_object(key, "bounds")?)?;
+
+        let mut entity = RawAtlasEntity::new(&slice_name, path, frame_position + bounds_position - source_offset, bounds_size);
+        if let Ok(Some(center)) = json::read_object_nullable(key, "center") {
+            let (position, size) = read_rect(center)?;
+            entity.center = Some(RawAtlasEntityCenter { position, size });
+        }
+
+        entities.push(entity);
+    }
+
+    let mut clips = Vec::default();
+    for tag in json::read_array_optional(meta, "frameTags")? {
+        let tag_name = json::read_value::<String>(tag, "name")?;
+        let from = json::read_value::<f64>(tag, "from")? as usize;
+        let to = json::read_value::<f64>(tag, "to")? as usize;
+        let direction = json::read_value_optional::<String>(tag, "direction")?.unwrap_or_else(|| "forward".to_string());
+        let repeat = json::read_value_optional::<String>(tag, "repeat")?;
+
+        if from > to || to >= frames.len() {
+            bail!("Tag {} has invalid range {}-{}", tag_name, from, to);
+        }
+
+        let mut indices = (from..=to).collect::<Vec<usize>>();
+        let mode = match direction.as_str() {
+            "forward" => AnimationMode::Loop,
+            "reverse" => {
+                indices.reverse();
+                AnimationMode::Loop
+            }
+            "pingpong" => AnimationMode::PingPong,
+            "pingpong_reverse" => {
+                indices.reverse();
+                AnimationMode::PingPong
+            }
+            _ => bail!("Invalid direction {} of tag {}", direction, tag_name),
+        };
+        let mode = if repeat.as_deref() == Some("1") && mode == AnimationMode::Loop { AnimationMode::Once } else { mode };
+
+        clips.push(AnimationClip::new(&tag_name, mode, indices.iter().map(|p| create_frame(&entities[*p], durations[*p])).collect()));
+    }
+
+    if clips.is_empty() && !frames.is_empty() {
+        clips.push(AnimationClip::new(name, AnimationMode::Loop, (0..frames.len()).map(|p| create_frame(&entities[p], durations[p])).collect()));
+    }
+
+    let atlas = RawAtlas::new(name, path, &image, entities);
+    let animation = RawAnimation { name: name.to_string(), path: path.to_string(), clips, ..Default::default() };
+
+    Ok((atlas, animation))
+}
+
+fn read_rect(data: &HashMap<String, JsonValue>) -> Result<(Vec2, Vec2)> {
+    Ok((json::read_vec2_dissected(data, "x", "y")?, json::read_vec2_dissected(data, "w", "h")?))
+}
+
+fn create_frame(entity: &RawAtlasEntity, duration: f32) -> AnimationFrame {
+    AnimationFrame::new(AnimationFrameSource::AtlasEntity { name: entity.name.clone() }, duration)
+}
+
+fn get_sorting_key(name: &str) -> (String, usize) {
+    let end = match name.rfind(|p: char| p.is_ascii_digit()) {
+        Some(end) => end + 1,
+        None => return (name.to_string(), 0),
+    };
+    let start = name[..end].rfind(|p: char| !p.is_ascii_digit()).map(|p| p + 1).unwrap_or(0);
+    let number = name[start..end].parse().unwrap_or(0);
+
+    (format!("{}{}", &name[..start], &name[end..]), number)
+}
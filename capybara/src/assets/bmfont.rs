@@ -0,0 +1,143 @@
+use super::vfs;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use glam::Vec2;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use rustc_hash::FxHashMap;
+use std::mem;
+
+#[derive(Clone, Debug, Default)]
+pub struct BitmapFont {
+    pub name: String,
+    pub path: String,
+    pub size: f32,
+    pub line_height: f32,
+    pub base: f32,
+    pub pages: Vec<String>,
+    pub glyphs: Vec<BitmapFontGlyph>,
+    pub kerning: Vec<(char, char, f32)>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BitmapFontGlyph {
+    pub id: char,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub offset: Vec2,
+    pub advance: f32,
+    pub page: usize,
+}
+
+type FntTag = (String, FxHashMap<String, String>);
+
+pub fn load_fnt(name: &str, path: &str, data: &str) -> Result<BitmapFont> {
+    // Text and XML variants have the same tags and attributes, so both are read into one list and processed together
+    let tags = if data.trim_start().starts_with('<') { read_xml_tags(data)? } else { read_text_tags(data) };
+    let mut font = BitmapFont { name: name.to_string(), path: path.to_string(), ..Default::default() };
+
+    for (tag, attributes) in &tags {
+        let read = |key: &str| -> Result<f32> {
+            let value = attributes.get(key).ok_or_else(|| anyhow!("Attribute {} not found in tag {}", key, tag))?;
+            Ok(value.parse::<f32>()?)
+        };
+
+        match tag.as_str() {
+            "info" => font.size = read("size")?.abs(),
+            "common" => {
+                font.line_height = read("lineHeight")?;
+                font.base = read("base")?;
+            }
+            "page" => {
+                let id = read("id")? as usize;
+                let file = attributes.get("file").ok_or_else(|| anyhow!("Page {} has no file", id))?;
+
+                if font.pages.len() <= id {
+                    font.pages.resize(id + 1, String::default());
+                }
+                font.pages[id] = vfs::resolve_path(path, file);
+            }
+            "char" => font.glyphs.push(BitmapFontGlyph {
+                id: read_char(read("id")?)?,
+                position: Vec2::new(read("x")?, read("y")?),
+                size: Vec2::new(read("width")?, read("height")?),
+                offset: Vec2::new(read("xoffset")?, read("yoffset")?),
+                advance: read("xadvance")?,
+                page: attributes.get("page").and_then(|p| p.parse().ok()).unwrap_or(0),
+            }),
+            "kerning" => font.kerning.push((read_char(read("first")?)?, read_char(read("second")?)?, read("amount")?)),
+            _ => {}
+        }
+    }
+
+    if font.pages.is_empty() {
+        bail!("Font {} has no pages", name);
+    }
+
+    Ok(font)
+}
+
+fn read_text_tags(data: &str) -> Vec<FntTag> {
+    let mut tags = Vec::default();
+
+    for line in data.lines() {
+        let mut tokens = Vec::default();
+        let mut token = String::default();
+        let mut quoted = false;
+
+        for char in line.chars() {
+            match char {
+                '"' => quoted = !quoted,
+                ' ' | '\t' if !quoted => {
+                    if !token.is_empty() {
+                        tokens.push(mem::take(&mut token));
+                    }
+                }
+                _ => token.push(char),
+            }
+        }
+
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+
+        if let Some((tag, attributes)) = tokens.split_first() {
+            let attributes = attributes.iter().filter_map(|p| p.split_once('=')).map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            tags.push((tag.to_string(), attributes));
+        }
+    }
+
+    tags
+}
+
+fn read_xml_tags(data: &str) -> Result<Vec<FntTag>> {
+    let mut tags = Vec::default();
+    let mut reader = Reader::from_str(data);
+
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => {
+                let mut attributes = FxHashMap::default();
+                for attribute in element.attributes() {
+                    let attribute = attribute?;
+                    let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                    attributes.insert(key, attribute.decode_and_unescape_value(&reader)?.to_string());
+                }
+
+                tags.push((String::from_utf8_lossy(element.name().as_ref()).to_string(), attributes));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(tags)
+}
+
+fn read_char(id: f32) -> Result<char> {
+    char::from_u32(id as u32).ok_or_else(|| anyhow!("Invalid character {}", id))
+}
@@ -0,0 +1,424 @@
+use super::RawTexture;
+use super::RawTextureFormat;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use flate2::read::ZlibDecoder;
+use glam::Vec2;
+use std::io::Read;
+
+const QOI_MAGIC: &[u8] = b"qoif";
+const KTX2_MAGIC: &[u8] = &[0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+
+// Same limit as in the QOI reference decoder, it keeps allocations for malformed headers reasonable for every format
+const MAX_PIXELS: usize = 400_000_000;
+
+pub fn load_qoi(name: &str, path: &str, data: &[u8]) -> Result<RawTexture> {
+    if data.len() < 22 || &data[0..4] != QOI_MAGIC {
+        bail!("Invalid QOI header");
+    }
+
+    let width = u32::from_be_bytes(data[4..8].try_into()?) as usize;
+    let height = u32::from_be_bytes(data[8..12].try_into()?) as usize;
+    let length = get_pixels_count(width, height)? * 4;
+
+    let mut pixels = Vec::with_capacity(length);
+    let mut index = [[0u8; 4]; 64];
+    let mut pixel = [0, 0, 0, 255];
+    let mut position = 14;
+
+    // Stream ends with 8 bytes of padding, so every chunk started before it can be read without further checks
+    let end = data.len() - 8;
+
+    while pixels.len() < length {
+        if position >= end {
+            bail!("Unexpected end of QOI data");
+        }
+
+        let tag = data[position];
+        let mut count = 1;
+        position += 1;
+
+        match tag {
+            0xfe => {
+                pixel[0..3].copy_from_slice(&data[position..position + 3]);
+                position += 3;
+            }
+            0xff => {
+                pixel.copy_from_slice(&data[position..position + 4]);
+                position += 4;
+            }
+            _ => match tag >> 6 {
+                0 => pixel = index[tag as usize],
+                1 => {
+                    pixel[0] = pixel[0].wrapping_add((tag >> 4) & 3).wrapping_sub(2);
+                    pixel[1] = pixel[1].wrapping_add((tag >> 2) & 3).wrapping_sub(2);
+                    pixel[2] = pixel[2].wrapping_add(tag & 3).wrapping_sub(2);
+                }
+                2 => {
+                    let dg = (tag & 63).wrapping_sub(32);
+                    let dr = (data[position] >> 4).wrapping_sub(8).wrapping_add(dg);
+                    let db = (data[position] & 15).wrapping_sub(8).wrapping_add(dg);
+                    position += 1;
+
+                    pixel[0] = pixel[0].wrapping_add(dr);
+                    pixel[1] = pixel[1].wrapping_add(dg);
+                    pixel[2] = pixel[2].wrapping_add(db);
+                }
+                _ => count = (tag & 63) as usize + 1,
+            },
+        }
+
+        let hash = (pixel[0] as usize * 3 + pixel[1] as usize * 5 + pixel[2] as usize * 7 + pixel[3] as usize * 11) % 64;
+        index[hash] = pixel;
+
+        for _ in 0..count.min((length - pixels.len()) / 4) {
+            pixels.extend_from_slice(&pixel);
+        }
+    }
+
+    premultiply(&mut pixels);
+    Ok(RawTexture::new(name, path, Vec2::new(width as f32, height as f32), &pixels))
+}
+
+pub fn load_tga(name: &str, path: &str, data: &[u8]) -> Result<RawTexture> {
+    if data.len() < 18 {
+        bail!("Invalid TGA header");
+    }
+
+    let id_length = data[0] as usize;
+    let colormap_type = data[1];
+    let image_type = data[2];
+    let colormap_first = u16::from_le_bytes([data[3], data[4]]) as usize;
+    let colormap_length = u16::from_le_bytes([data[5], data[6]]) as usize;
+    let colormap_depth = data[7] as usize;
+    let width = u16::from_le_bytes([data[12], data[13]]) as usize;
+    let height = u16::from_le_bytes([data[14], data[15]]) as usize;
+    let depth = data[16] as usize;
+    let descriptor = data[17];
+    let alpha = descriptor & 15 != 0;
+
+    let mut position = 18 + id_length;
+    let colormap = if colormap_type == 1 {
+        let length = colormap_length * colormap_depth.div_ceil(8);
+        position += length;
+        read_bytes(data, position - length, length)?
+    } else {
+        &[]
+    };
+
+    let bytes_per_pixel = depth.div_ceil(8);
+    let count = get_pixels_count(width, height)?;
+    let mut indices = Vec::with_capacity(count * bytes_per_pixel);
+
+    if image_type & 8 != 0 {
+        while indices.len() < count * bytes_per_pixel {
+            let header = *data.get(position).ok_or_else(|| anyhow!("Unexpected end of TGA data"))?;
+            let length = (header & 127) as usize + 1;
+            position += 1;
+
+            if header & 128 != 0 {
+                let pixel = read_bytes(data, position, bytes_per_pixel)?;
+                for _ in 0..length {
+                    indices.extend_from_slice(pixel);
+                }
+                position += bytes_per_pixel;
+            } else {
+                indices.extend_from_slice(read_bytes(data, position, length * bytes_per_pixel)?);
+                position += length * bytes_per_pixel;
+            }
+        }
+
+        indices.truncate(count * bytes_per_pixel);
+    } else {
+        indices.extend_from_slice(read_bytes(data, position, count * bytes_per_pixel)?);
+    }
+
+    let (format, mut pixels) = match (image_type & 7, depth) {
+        (1, 8) => {
+            let entry_size = colormap_depth.div_ceil(8);
+            let mut pixels = Vec::with_capacity(count * 4);
+
+            for index in indices {
+                let entry = (index as usize).checked_sub(colormap_first).ok_or_else(|| anyhow!("Invalid TGA color map index {}", index))?;
+                pixels.extend_from_slice(&read_tga_color(read_bytes(colormap, entry * entry_size, entry_size)?, alpha)?);
+            }
+
+            (RawTextureFormat::Rgba8, pixels)
+        }
+        (2, 15 | 16 | 24 | 32) => {
+            (RawTextureFormat::Rgba8, indices.chunks(bytes_per_pixel).map(|p| read_tga_color(p, alpha)).collect::<Result<Vec<_>>>()?.concat())
+        }
+        (3, 8) => (RawTextureFormat::R8, indices),
+        (3, 16) => (RawTextureFormat::Rgba8, indices.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect()),
+        (kind, depth) => bail!("Unsupported TGA image type {} with {} bits per pixel", kind, depth),
+    };
+
+    // Textures are stored from the top row like PNG, but TGA starts from the bottom unless the descriptor says otherwise
+    let channels = if format == RawTextureFormat::R8 { 1 } else { 4 };
+    if descriptor & 32 == 0 {
+        let rows = pixels.chunks(width * channels).rev().flatten().copied().collect();
+        pixels = rows;
+    }
+
+    if descriptor & 16 != 0 {
+        for row in pixels.chunks_mut(width * channels) {
+            let mut columns = row.chunks(channels).rev().flatten().copied().collect::<Vec<u8>>();
+            row.swap_with_slice(&mut columns);
+        }
+    }
+
+    if format == RawTextureFormat::Rgba8 {
+        premultiply(&mut pixels);
+    }
+
+    Ok(RawTexture::new(name, path, Vec2::new(width as f32, height as f32), &pixels).with_format(format))
+}
+
+pub fn load_ktx2(name: &str, path: &str, data: &[u8]) -> Result<RawTexture> {
+    if data.len() < 80 || &data[0..12] != KTX2_MAGIC {
+        bail!("Invalid KTX2 header");
+    }
+
+    let vk_format = read_u32(data, 12)?;
+    let width = read_u32(data, 20)?;
+    let height = read_u32(data, 24)?;
+    let depth = read_u32(data, 28)?;
+    let layers = read_u32(data, 32)?;
+    let faces = read_u32(data, 36)?;
+    let levels = read_u32(data, 40)?.max(1) as usize;
+    let supercompression = read_u32(data, 44)?;
+    let dfd_offset = read_u32(data, 48)? as usize;
+
+    if depth > 1 || layers > 1 || faces != 1 {
+        bail!("Only 2D textures are supported in KTX2 files");
+    }
+
+    get_pixels_count(width as usize, height as usize)?;
+
+    // GL uploads are always sRGB like for PNG files, so both variants of every format are treated the same way
+    let format = match vk_format {
+        9 | 15 => RawTextureFormat::R8,
+        37 | 43 => RawTextureFormat::Rgba8,
+        131..=134 => RawTextureFormat::Bc1,
+        137 | 138 => RawTextureFormat::Bc3,
+        145 | 146 => RawTextureFormat::Bc7,
+        147 | 148 => RawTextureFormat::Etc2Rgb8,
+        151 | 152 => RawTextureFormat::Etc2Rgba8,
+        157 | 158 => RawTextureFormat::Astc4x4,
+        _ => bail!("Unsupported KTX2 format {}", vk_format),
+    };
+
+    let mut images = Vec::default();
+    for level in 0..levels {
+        let offset = read_u64(data, 80 + level * 24)? as usize;
+        let length = read_u64(data, 80 + level * 24 + 8)? as usize;
+        let image = read_bytes(data, offset, length)?;
+
+        let mut image = match supercompression {
+            0 => image.to_vec(),
+            3 => {
+                let mut decompressed = Vec::default();
+                ZlibDecoder::new(image).read_to_end(&mut decompressed)?;
+                decompressed
+            }
+            _ => bail!("Unsupported KTX2 supercompression scheme {}", supercompression),
+        };
+
+        // Compressed uploads have to match the level size exactly, so the padding is cut off as well
+        let expected = get_level_size(format, width as usize, height as usize, level);
+        if image.len() < expected {
+            bail!("KTX2 level {} has {} bytes, expected {}", level, image.len(), expected);
+        }
+
+        image.truncate(expected);
+        images.push(image);
+
+        // Uncompressed textures have mipmaps generated on the GPU, so only the base level is needed
+        if !format.is_compressed() {
+            break;
+        }
+    }
+
+    let mut pixels = images.remove(0);
+    let premultiplied = data.get(dfd_offset + 15).map(|p| p & 1 != 0).unwrap_or(false);
+
+    if format == RawTextureFormat::Rgba8 && !premultiplied {
+        premultiply(&mut pixels);
+    }
+
+    Ok(RawTexture::new(name, path, Vec2::new(width as f32, height as f32), &pixels).with_format(format).with_mipmaps(images))
+}
+
+pub fn premultiply(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        let a = pixel[3] as f32 / 255.0;
+
+        pixel[0] = (pixel[0] as f32 * a) as u8;
+        pixel[1] = (pixel[1] as f32 * a) as u8;
+        pixel[2] = (pixel[2] as f32 * a) as u8;
+    }
+}
+
+fn read_tga_color(data: &[u8], alpha: bool) -> Result<[u8; 4]> {
+    Ok(match *data {
+        [low, high] => {
+            let value = u16::from_le_bytes([low, high]);
+            let expand = |p: u16| ((p & 31) * 255 / 31) as u8;
+
+            [expand(value >> 10), expand(value >> 5), expand(value), if alpha && value & 0x8000 == 0 { 0 } else { 255 }]
+        }
+        [b, g, r] => [r, g, b, 255],
+        [b, g, r, a] => [r, g, b, if alpha { a } else { 255 }],
+        _ => bail!("Unsupported TGA color size {}", data.len()),
+    })
+}
+
+// All supported compressed formats use 4x4 blocks, they differ only by the number of bytes per block
+fn get_level_size(format: RawTextureFormat, width: usize, height: usize, level: usize) -> usize {
+    let width = (width >> level).max(1);
+    let height = (height >> level).max(1);
+
+    match format {
+        RawTextureFormat::R8 => width * height,
+        RawTextureFormat::Rgba8 => width * height * 4,
+        RawTextureFormat::Bc1 | RawTextureFormat::Etc2Rgb8 => width.div_ceil(4) * height.div_ceil(4) * 8,
+        _ => width.div_ceil(4) * height.div_ceil(4) * 16,
+    }
+}
+
+fn get_pixels_count(width: usize, height: usize) -> Result<usize> {
+    match width.checked_mul(height) {
+        Some(count) if count > 0 && count <= MAX_PIXELS => Ok(count),
+        _ => bail!("Invalid image size {}x{}", width, height),
+    }
+}
+
+fn read_bytes(data: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    let end = offset.checked_add(length).ok_or_else(|| anyhow!("Invalid data range at {}", offset))?;
+    data.get(offset..end).ok_or_else(|| anyhow!("Unexpected end of data at {}", offset))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(data, offset, 4)?.try_into()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset, 8)?.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qoi(width: u32, height: u32, chunks: &[u8]) -> Vec<u8> {
+        let mut data = QOI_MAGIC.to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[4, 0]);
+        data.extend_from_slice(chunks);
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        data
+    }
+
+    fn tga(image_type: u8, depth: u8, descriptor: u8, width: u16, height: u16, pixels: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(&[depth, descriptor]);
+        data.extend_from_slice(pixels);
+        data
+    }
+
+    fn ktx2(vk_format: u32, width: u32, height: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut data = KTX2_MAGIC.to_vec();
+        for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0, 0, 0, 0, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[0; 16]);
+
+        let mut offset = 80 + levels.len() * 24;
+        for level in levels {
+            data.extend_from_slice(&(offset as u64).to_le_bytes());
+            data.extend_from_slice(&(level.len() as u64).to_le_bytes());
+            data.extend_from_slice(&(level.len() as u64).to_le_bytes());
+            offset += level.len();
+        }
+
+        for level in levels {
+            data.extend_from_slice(level);
+        }
+        data
+    }
+
+    #[test]
+    fn qoi_valid() {
+        // Opaque red pixel, run of two, then a small diff to green-ish
+        let data = qoi(4, 1, &[0xfe, 255, 0, 0, 0xc1, 0x40 | (1 << 4) | (3 << 2) | 2]);
+        let texture = load_qoi("test", "/test.qoi", &data).unwrap();
+
+        assert_eq!(texture.size, Vec2::new(4.0, 1.0));
+        assert_eq!(texture.format, RawTextureFormat::Rgba8);
+        assert_eq!(texture.data, [255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 254, 1, 0, 255]);
+    }
+
+    #[test]
+    fn qoi_invalid() {
+        assert!(load_qoi("test", "/test.qoi", &qoi(0, 1, &[0xfe, 255, 0, 0])).is_err());
+        assert!(load_qoi("test", "/test.qoi", &qoi(2, 1, &[0xfe, 255, 0, 0])).is_err());
+        assert!(load_qoi("test", "/test.qoi", &qoi(u32::MAX, u32::MAX, &[0xfe, 255, 0, 0])).is_err());
+        assert!(load_qoi("test", "/test.qoi", &qoi(1, 1, &[])[..20]).is_err());
+    }
+
+    #[test]
+    fn tga_valid() {
+        let data = tga(2, 32, 8, 2, 1, &[0, 0, 255, 255, 255, 0, 0, 255]);
+        let texture = load_tga("test", "/test.tga", &data).unwrap();
+
+        assert_eq!(texture.format, RawTextureFormat::Rgba8);
+        assert_eq!(texture.data, [255, 0, 0, 255, 0, 0, 255, 255]);
+
+        // Bottom-up grayscale with one RLE packet per row
+        let data = tga(11, 8, 0, 2, 2, &[0x81, 10, 0x81, 20]);
+        let texture = load_tga("test", "/test.tga", &data).unwrap();
+
+        assert_eq!(texture.format, RawTextureFormat::R8);
+        assert_eq!(texture.data, [20, 20, 10, 10]);
+    }
+
+    #[test]
+    fn tga_invalid() {
+        assert!(load_tga("test", "/test.tga", &tga(3, 8, 0, 0, 1, &[])).is_err());
+        assert!(load_tga("test", "/test.tga", &tga(3, 8, 0, 2, 2, &[1, 2, 3])).is_err());
+        assert!(load_tga("test", "/test.tga", &tga(11, 8, 0, 2, 2, &[0x83])).is_err());
+        assert!(load_tga("test", "/test.tga", &tga(3, 8, 0, 2, 2, &[])[..17]).is_err());
+    }
+
+    #[test]
+    fn ktx2_valid() {
+        let texture = load_ktx2("test", "/test.ktx2", &ktx2(9, 2, 2, &[&[1, 2, 3, 4]])).unwrap();
+
+        assert_eq!(texture.format, RawTextureFormat::R8);
+        assert_eq!(texture.data, [1, 2, 3, 4]);
+
+        // 5x5 texture needs 2x2 blocks at the base level, the second level fits in a single one
+        let texture = load_ktx2("test", "/test.ktx2", &ktx2(131, 5, 5, &[&[0; 32], &[0; 8], &[0; 8]])).unwrap();
+
+        assert_eq!(texture.format, RawTextureFormat::Bc1);
+        assert_eq!(texture.data.len(), 32);
+        assert_eq!(texture.mipmaps.len(), 2);
+    }
+
+    #[test]
+    fn ktx2_invalid() {
+        assert!(load_ktx2("test", "/test.ktx2", &ktx2(9, 0, 2, &[&[1, 2]])).is_err());
+        assert!(load_ktx2("test", "/test.ktx2", &ktx2(9, 2, 2, &[&[1, 2, 3]])).is_err());
+        assert!(load_ktx2("test", "/test.ktx2", &ktx2(131, 5, 5, &[&[0; 16]])).is_err());
+        assert!(load_ktx2("test", "/test.ktx2", &ktx2(145, 4, 4, &[&[0; 16], &[0; 8]])).is_err());
+        assert!(load_ktx2("test", "/test.ktx2", &ktx2(9, 2, 2, &[&[1, 2, 3, 4]])[..90]).is_err());
+
+        let mut data = ktx2(9, 2, 2, &[&[1, 2, 3, 4]]);
+        data[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(load_ktx2("test", "/test.ktx2", &data).is_err());
+    }
+}
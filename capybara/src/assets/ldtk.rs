@@ -16,10 +16,21 @@ use tinyjson::JsonValue;
 pub struct LdtkWorld {
     pub name: String,
     pub path: String,
+    pub layout: LdtkWorldLayout,
+    pub grid_size: Vec2,
     pub tilemaps: Vec<LdtkTilemap>,
     pub levels: Vec<LdtkLevel>,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum LdtkWorldLayout {
+    #[default]
+    Free,
+    GridVania,
+    LinearHorizontal,
+    LinearVertical,
+}
+
 #[derive(Debug, Default)]
 pub struct LdtkTilemap {
     pub id: usize,
@@ -32,40 +43,91 @@ pub struct LdtkTilemap {
 #[derive(Debug, Default)]
 pub struct LdtkLevel {
     pub id: usize,
+    pub iid: String,
     pub name: String,
     pub size: Vec2,
+    pub world_position: Vec2,
+    pub world_depth: i32,
     pub background: Vec4,
+    pub neighbours: Vec<LdtkNeighbour>,
+    pub fields: FxHashMap<String, LdtkEntityField>,
     pub layers: Vec<LdtkLayer>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct LdtkNeighbour {
+    pub level_iid: String,
+    pub direction: LdtkNeighbourDirection,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LdtkNeighbourDirection {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    Lower,
+    Higher,
+    Overlap,
+}
+
 #[derive(Debug, Default)]
 pub struct LdtkLayer {
     pub id: usize,
     pub name: String,
     pub grid_size: Vec2,
     pub tilemap_id: Option<usize>,
+    pub opacity: f32,
+    pub offset: Vec2,
+    pub visible: bool,
+    pub columns: usize,
+    pub rows: usize,
+    pub int_grid: Vec<i32>,
+    pub int_grid_values: Vec<LdtkIntGridValue>,
     pub tiles: Vec<LdtkTile>,
     pub entities: Vec<LdtkEntity>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LdtkIntGridValue {
+    pub value: i32,
+    pub name: String,
+    pub color: Vec4,
+}
+
 #[derive(Debug, Default)]
 pub struct LdtkTile {
     pub id: usize,
     pub position: Vec2,
     pub source: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
 }
 
 #[derive(Debug, Default)]
 pub struct LdtkEntity {
+    pub iid: String,
     pub name: String,
     pub position: Vec2,
     pub size: Vec2,
     pub pivot: Vec2,
     pub source: Vec2,
-    pub tilemap_id: usize,
+    pub tilemap_id: Option<usize>,
     pub fields: FxHashMap<String, LdtkEntityField>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LdtkEntityRef {
+    pub entity_iid: String,
+    pub layer_iid: String,
+    pub level_iid: String,
+    pub world_iid: String,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum LdtkEntityField {
     Bool(bool),
@@ -73,11 +135,27 @@ pub enum LdtkEntityField {
     Float(f32),
     String(String),
     Color(Vec4),
+    Point(Vec2),
+    Enum(String),
+    EntityRef(LdtkEntityRef),
     BoolArray(Vec<bool>),
     IntArray(Vec<i32>),
     FloatArray(Vec<f32>),
     StringArray(Vec<String>),
     ColorArray(Vec<Vec4>),
+    PointArray(Vec<Vec2>),
+    EnumArray(Vec<String>),
+    EntityRefArray(Vec<LdtkEntityRef>),
+}
+
+impl LdtkLayer {
+    pub fn get_int_grid_value(&self, x: usize, y: usize) -> i32 {
+        if x >= self.columns || y >= self.rows {
+            return 0;
+        }
+
+        self.int_grid[y * self.columns + x]
+    }
 }
 
 pub fn load_world(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<LdtkWorld> {
@@ -86,17 +164,57 @@ pub fn load_world(name: &str, path: &str, data: &HashMap<String, JsonValue>) ->
     let tilemap_definitions = json::read_array(definitions, "tilesets")?;
     let layer_definitions = json::read_array(definitions, "layers")?;
     let entity_definitions = json::read_array(definitions, "entities")?;
-    let levels = json::read_array(data, "levels")?;
+    let level_field_definitions = json::read_array_optional(definitions, "levelFields")?;
+    let mut levels = json::read_array(data, "levels")?;
+    let mut layout_data = data;
+
+    // Projects with multiple worlds keep levels in the separate array, only the first world is loaded then
+    if levels.is_empty() {
+        if let Some(first_world) = json::read_array_optional(data, "worlds")?.first() {
+            levels = json::read_array(first_world, "levels")?;
+            layout_data = first_world;
+        }
+    }
 
     world.name = name.to_string();
     world.path = path.to_string();
+    world.layout = match json::read_value_optional::<String>(layout_data, "worldLayout")?.as_deref() {
+        Some("GridVania") => LdtkWorldLayout::GridVania,
+        Some("LinearHorizontal") => LdtkWorldLayout::LinearHorizontal,
+        Some("LinearVertical") => LdtkWorldLayout::LinearVertical,
+        _ => LdtkWorldLayout::Free,
+    };
+    world.grid_size = Vec2::new(
+        json::read_value_optional::<f64>(layout_data, "worldGridWidth")?.unwrap_or(0.0) as f32,
+        json::read_value_optional::<f64>(layout_data, "worldGridHeight")?.unwrap_or(0.0) as f32,
+    );
+
+    let default_grid_size = json::read_value_optional::<f64>(data, "defaultGridSize")?.unwrap_or(16.0) as f32;
 
     for tilemap in tilemap_definitions {
         world.tilemaps.push(load_tilemap(tilemap)?);
     }
 
     for level in levels {
-        world.levels.push(load_level(level, &world.tilemaps, &layer_definitions, &entity_definitions)?);
+        world
+            .levels
+            .push(load_level(level, &world.tilemaps, &layer_definitions, &entity_definitions, &level_field_definitions, default_grid_size)?);
+    }
+
+    // Linear layouts don't store level coordinates, so they are rebuilt the same way as LDtk displays them
+    let mut offset = 0.0;
+    for level in &mut world.levels {
+        match world.layout {
+            LdtkWorldLayout::LinearHorizontal => {
+                level.world_position = Vec2::new(offset, -level.size.y);
+                offset += level.size.x;
+            }
+            LdtkWorldLayout::LinearVertical => {
+                offset += level.size.y;
+                level.world_position = Vec2::new(0.0, -offset);
+            }
+            _ => {}
+        }
     }
 
     Ok(world)
@@ -135,15 +253,49 @@ fn load_level(
     tilemaps: &[LdtkTilemap],
     layer_definitions: &[&HashMap<String, JsonValue>],
     entity_definitions: &[&HashMap<String, JsonValue>],
+    level_field_definitions: &[&HashMap<String, JsonValue>],
+    default_grid_size: f32,
 ) -> Result<LdtkLevel> {
+    let size = json::read_vec2_dissected(data, "pxWid", "pxHei")?;
+    let world_position = json::read_vec2_dissected(data, "worldX", "worldY")?;
+
     let mut level = LdtkLevel {
         id: json::read_value::<f64>(data, "uid")? as usize,
+        iid: json::read_value_optional::<String>(data, "iid")?.unwrap_or_default(),
         name: json::read_value::<String>(data, "identifier")?,
-        size: json::read_vec2_dissected(data, "pxWid", "pxHei")?,
+        size,
+        // LDtk stores top-left corner with Y pointing down, flip it so levels can be placed next to each other directly
+        world_position: Vec2::new(world_position.x, -world_position.y - size.y),
+        world_depth: json::read_value_optional::<f64>(data, "worldDepth")?.unwrap_or(0.0) as i32,
         background: json::read_color(data, "bgColor")?,
+        neighbours: Vec::default(),
+        fields: FxHashMap::default(),
         layers: Vec::default(),
     };
-    let layers = json::read_array(data, "layerInstances")?;
+
+    for neighbour in json::read_array_optional(data, "__neighbours")? {
+        let direction = match json::read_value::<String>(neighbour, "dir")?.as_str() {
+            "n" => LdtkNeighbourDirection::North,
+            "s" => LdtkNeighbourDirection::South,
+            "e" => LdtkNeighbourDirection::East,
+            "w" => LdtkNeighbourDirection::West,
+            "ne" => LdtkNeighbourDirection::NorthEast,
+            "nw" => LdtkNeighbourDirection::NorthWest,
+            "se" => LdtkNeighbourDirection::SouthEast,
+            "sw" => LdtkNeighbourDirection::SouthWest,
+            "<" => LdtkNeighbourDirection::Lower,
+            ">" => LdtkNeighbourDirection::Higher,
+            "o" => LdtkNeighbourDirection::Overlap,
+            _ => continue,
+        };
+
+        level.neighbours.push(LdtkNeighbour { level_iid: json::read_value::<String>(neighbour, "levelIid")?, direction });
+    }
+
+    let field_instances = json::read_array_optional(data, "fieldInstances")?;
+    level.fields = load_fields(&field_instances, level_field_definitions, default_grid_size, level.size)?;
+
+    let layers = json::read_array_optional(data, "layerInstances")?;
 
     for data in layers {
         let id = json::read_value::<f64>(data, "layerDefUid")? as usize;
@@ -156,34 +308,74 @@ fn load_level(
         let layer_grid_size = json::read_value::<f64>(layer_definition, "gridSize")? as f32;
         let layer_tilemap = json::read_value_nullable::<f64>(data, "overrideTilesetUid")?;
         let tilemap_id = layer_tilemap.or(layer_definition_tilemap);
+        let offset = Vec2::new(
+            json::read_value_optional::<f64>(data, "__pxTotalOffsetX")?.unwrap_or(0.0) as f32,
+            -json::read_value_optional::<f64>(data, "__pxTotalOffsetY")?.unwrap_or(0.0) as f32,
+        );
 
         let mut layer = LdtkLayer {
             id,
             name: layer_name,
             grid_size: Vec2::new(layer_grid_size, layer_grid_size),
             tilemap_id: tilemap_id.map(|p| p as usize),
+            opacity: json::read_value_optional::<f64>(data, "__opacity")?.unwrap_or(1.0) as f32,
+            offset,
+            visible: json::read_value_optional::<bool>(data, "visible")?.unwrap_or(true),
+            columns: json::read_value_optional::<f64>(data, "__cWid")?.unwrap_or(0.0) as usize,
+            rows: json::read_value_optional::<f64>(data, "__cHei")?.unwrap_or(0.0) as usize,
+            int_grid: Vec::default(),
+            int_grid_values: Vec::default(),
             tiles: Vec::default(),
             entities: Vec::default(),
         };
 
+        for value in json::read_array_optional(layer_definition, "intGridValues")? {
+            layer.int_grid_values.push(LdtkIntGridValue {
+                value: json::read_value::<f64>(value, "value")? as i32,
+                name: json::read_value_optional::<String>(value, "identifier")?.unwrap_or_default(),
+                color: json::read_color(value, "color")?,
+            });
+        }
+
+        if let Some(JsonValue::Array(values)) = data.get("intGridCsv") {
+            if !values.is_empty() {
+                if values.len() != layer.columns * layer.rows {
+                    bail!("Invalid IntGrid size in layer {}", layer.name);
+                }
+
+                // Rows are stored from the bottom, so the grid cell (x, y) matches tile positions
+                layer.int_grid = vec![0; values.len()];
+                for (index, value) in values.iter().enumerate() {
+                    let x = index % layer.columns;
+                    let y = layer.rows - index / layer.columns - 1;
+                    layer.int_grid[y * layer.columns + x] = value.get::<f64>().copied().unwrap_or(0.0) as i32;
+                }
+            }
+        }
+
         if let Some(tilemap_id) = tilemap_id {
             let tilemap = match tilemaps.iter().find(|p| p.id == tilemap_id as usize) {
                 Some(tilemap) => tilemap,
                 None => bail!("Failed to find tilemap {}", tilemap_id),
             };
-            let tiles = json::read_array(data, "gridTiles")?;
+            let mut tiles = json::read_array_optional(data, "autoLayerTiles")?;
+            tiles.extend(json::read_array_optional(data, "gridTiles")?);
 
             for data in tiles {
                 let position = json::read_vec2(data, "px")?;
+                let flags = json::read_value_optional::<f64>(data, "f")?.unwrap_or(0.0) as u32;
+
                 layer.tiles.push(LdtkTile {
                     id: json::read_value::<f64>(data, "t")? as usize,
                     position: Vec2::new(position.x, level.size.y - position.y - tilemap.tile_size.y),
                     source: json::read_vec2(data, "src")?,
+                    flip_x: flags & 1 != 0,
+                    flip_y: flags & 2 != 0,
                 });
             }
         }
 
-        let entities = json::read_array(data, "entityInstances")?;
+        let entities = json::read_array_optional(data, "entityInstances")?;
         for data in entities {
             let entity_definition_id = json::read_value::<f64>(data, "defUid")? as usize;
             let entity_definition =
@@ -192,113 +384,329 @@ fn load_level(
                     None => bail!("Failed to find definition for entity {}", entity_definition_id),
                 };
             let field_definitions = json::read_array(entity_definition, "fieldDefs")?;
-            let tile_data = json::read_object(entity_definition, "tileRect")?;
+            let tile_data = json::read_object_nullable(entity_definition, "tileRect")?;
             let pivot = json::read_vec2_dissected(entity_definition, "pivotX", "pivotY")?;
+            let position = json::read_vec2(data, "px")?;
+            let size = json::read_vec2_dissected(data, "width", "height")?;
 
             let entity_definition_tilemap = json::read_value_nullable::<f64>(entity_definition, "tilesetId")?;
-            if let Some(tilemap_id) = layer_tilemap.or(entity_definition_tilemap) {
-                let position = json::read_vec2(data, "px")?;
-                let tilemap = match tilemaps.iter().find(|p| p.id == tilemap_id as usize) {
-                    Some(tilemap) => tilemap,
+            let tilemap_id = layer_tilemap.or(entity_definition_tilemap).map(|p| p as usize);
+            let height = match tilemap_id {
+                Some(tilemap_id) => match tilemaps.iter().find(|p| p.id == tilemap_id) {
+                    Some(tilemap) => tilemap.tile_size.y,
                     None => bail!("Failed to find tilemap {}", tilemap_id),
-                };
+                },
+                None => size.y,
+            };
 
-                let mut fields = FxHashMap::default();
-                let field_instances = json::read_array(data, "fieldInstances")?;
-
-                for data in field_instances {
-                    let field_definition_id = json::read_value::<f64>(data, "defUid")? as usize;
-                    let field_definition =
-                        match field_definitions.iter().find(|p| json::read_value::<f64>(p, "uid").unwrap_or(0.0) as usize == field_definition_id) {
-                            Some(definition) => definition,
-                            None => bail!("Failed to find definition for field {}", field_definition_id),
-                        };
-
-                    let field_name = json::read_value::<String>(field_definition, "identifier")?;
-                    let field_type = json::read_value::<String>(field_definition, "type")?;
-                    let field_is_array = json::read_value::<bool>(field_definition, "isArray")?;
-                    let default_value_object = json::read_object_nullable(field_definition, "defaultOverride")?;
-                    let default_value = if let Some(default_value_object) = default_value_object {
-                        json::read_array_values(default_value_object, "params")?.get(0).cloned()
-                    } else {
-                        None
-                    };
-
-                    let mut values = Vec::default();
-                    let field_value_array = json::read_array_raw(data, "realEditorValues")?;
-                    for field_value in field_value_array {
-                        match field_value {
-                            JsonValue::Object(data) => {
-                                let value = json::read_array_values(data, "params")?;
-                                values.push(value[0].clone());
-                            }
-                            JsonValue::Null => match &default_value {
-                                Some(default_value) => values.push(default_value.clone()),
-                                None => bail!("No default value"),
-                            },
-                            _ => {}
-                        }
-                    }
+            let field_instances = json::read_array(data, "fieldInstances")?;
+            let fields = load_fields(&field_instances, &field_definitions, layer_grid_size, level.size)?;
+
+            layer.entities.push(LdtkEntity {
+                iid: json::read_value_optional::<String>(data, "iid")?.unwrap_or_default(),
+                name: json::read_value::<String>(entity_definition, "identifier")?,
+                position: Vec2::new(position.x, level.size.y - position.y - height + pivot.y * height),
+                size,
+                pivot,
+                source: match tile_data {
+                    Some(tile_data) => json::read_vec2_dissected(tile_data, "x", "y")?,
+                    None => Vec2::ZERO,
+                },
+                tilemap_id,
+                fields,
+            });
+        }
 
-                    if values.is_empty() {
-                        if let Some(default_value) = default_value {
-                            values.push(default_value);
-                        }
-                    }
+        level.layers.push(layer);
+    }
 
-                    let value = if !field_is_array {
-                        match field_type.as_str() {
-                            "F_Bool" => LdtkEntityField::Bool(values.get(0).unwrap_or(&"false".to_string()).parse()?),
-                            "F_Int" => LdtkEntityField::Int(values.get(0).unwrap_or(&"0".to_string()).parse()?),
-                            "F_Float" => LdtkEntityField::Float(values.get(0).unwrap_or(&"0.0".to_string()).parse()?),
-                            "F_String" => LdtkEntityField::String(values.get(0).unwrap_or(&"".to_string()).to_string()),
-                            "F_Text" => LdtkEntityField::String(values.get(0).unwrap_or(&"".to_string()).to_string()),
-                            "F_Color" => {
-                                let hex = format!("{:x}", values.get(0).unwrap_or(&"0".to_string()).to_string().parse::<u32>()?);
-                                let color = Rgb::from_hex_str(&hex).map_err(|_| anyhow!("Failed to parse color"))?.to_vec4();
-                                LdtkEntityField::Color(color)
-                            }
-                            _ => bail!("Invalid field type"),
-                        }
-                    } else {
-                        match field_type.as_str() {
-                            "F_Bool" => LdtkEntityField::BoolArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<bool>, _>>()?),
-                            "F_Int" => LdtkEntityField::IntArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<i32>, _>>()?),
-                            "F_Float" => LdtkEntityField::FloatArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<f32>, _>>()?),
-                            "F_String" => LdtkEntityField::StringArray(values.iter().map(|p| p.to_string()).collect()),
-                            "F_Text" => LdtkEntityField::StringArray(values.iter().map(|p| p.to_string()).collect()),
-                            "F_Color" => LdtkEntityField::ColorArray(
-                                values
-                                    .iter()
-                                    .map(|p| {
-                                        let hex = format!("{:x}", p.parse::<u32>()?);
-                                        let color = Rgb::from_hex_str(&hex).map_err(|_| anyhow!("Failed to parse color"))?.to_vec4();
-
-                                        Ok(color)
-                                    })
-                                    .collect::<Result<Vec<Vec4>, Error>>()?,
-                            ),
-                            _ => bail!("Invalid field type"),
-                        }
-                    };
-
-                    fields.insert(field_name, value);
-                }
+    Ok(level)
+}
 
-                layer.entities.push(LdtkEntity {
-                    name: json::read_value::<String>(entity_definition, "identifier")?,
-                    position: Vec2::new(position.x, level.size.y - position.y - tilemap.tile_size.y + pivot.y * tilemap.tile_size.y),
-                    size: json::read_vec2_dissected(data, "width", "height")?,
-                    pivot,
-                    source: json::read_vec2_dissected(tile_data, "x", "y")?,
-                    tilemap_id: tilemap_id as usize,
-                    fields,
-                });
+fn load_fields(
+    field_instances: &[&HashMap<String, JsonValue>],
+    field_definitions: &[&HashMap<String, JsonValue>],
+    grid_size: f32,
+    level_size: Vec2,
+) -> Result<FxHashMap<String, LdtkEntityField>> {
+    let mut fields = FxHashMap::default();
+
+    for data in field_instances {
+        let field_definition_id = json::read_value::<f64>(data, "defUid")? as usize;
+        let field_definition =
+            match field_definitions.iter().find(|p| json::read_value::<f64>(p, "uid").unwrap_or(0.0) as usize == field_definition_id) {
+                Some(definition) => definition,
+                None => bail!("Failed to find definition for field {}", field_definition_id),
+            };
+
+        let field_name = json::read_value::<String>(field_definition, "identifier")?;
+        let field_type = json::read_value::<String>(field_definition, "type")?;
+        let field_is_array = json::read_value::<bool>(field_definition, "isArray")?;
+
+        let value = match field_type.as_str() {
+            "F_Point" | "F_EntityRef" | "F_Path" => load_field_value(data, &field_type, field_is_array, grid_size, level_size)?,
+            field_type if field_type.starts_with("F_Enum") => load_field_value(data, "F_Enum", field_is_array, grid_size, level_size)?,
+            "F_Bool" | "F_Int" | "F_Float" | "F_String" | "F_Text" | "F_Color" => {
+                Some(load_field_editor_value(data, field_definition, &field_type, field_is_array)?)
             }
+            _ => None,
+        };
+
+        if let Some(value) = value {
+            fields.insert(field_name, value);
         }
+    }
 
-        level.layers.push(layer);
+    Ok(fields)
+}
+
+fn load_field_editor_value(
+    data: &HashMap<String, JsonValue>,
+    field_definition: &HashMap<String, JsonValue>,
+    field_type: &str,
+    field_is_array: bool,
+) -> Result<LdtkEntityField> {
+    let default_value_object = json::read_object_nullable(field_definition, "defaultOverride")?;
+    let default_value = if let Some(default_value_object) = default_value_object {
+        json::read_array_values(default_value_object, "params")?.get(0).cloned()
+    } else {
+        None
+    };
+
+    let mut values = Vec::default();
+    let field_value_array = json::read_array_raw(data, "realEditorValues")?;
+    for field_value in field_value_array {
+        match field_value {
+            JsonValue::Object(data) => {
+                let value = json::read_array_values(data, "params")?;
+                values.push(value[0].clone());
+            }
+            JsonValue::Null => match &default_value {
+                Some(default_value) => values.push(default_value.clone()),
+                None => bail!("No default value"),
+            },
+            _ => {}
+        }
     }
 
-    Ok(level)
+    if values.is_empty() {
+        if let Some(default_value) = default_value {
+            values.push(default_value);
+        }
+    }
+
+    let value = if !field_is_array {
+        match field_type {
+            "F_Bool" => LdtkEntityField::Bool(values.get(0).unwrap_or(&"false".to_string()).parse()?),
+            "F_Int" => LdtkEntityField::Int(values.get(0).unwrap_or(&"0".to_string()).parse()?),
+            "F_Float" => LdtkEntityField::Float(values.get(0).unwrap_or(&"0.0".to_string()).parse()?),
+            "F_String" => LdtkEntityField::String(values.get(0).unwrap_or(&"".to_string()).to_string()),
+            "F_Text" => LdtkEntityField::String(values.get(0).unwrap_or(&"".to_string()).to_string()),
+            "F_Color" => {
+                let hex = format!("{:x}", values.get(0).unwrap_or(&"0".to_string()).to_string().parse::<u32>()?);
+                let color = Rgb::from_hex_str(&hex).map_err(|_| anyhow!("Failed to parse color"))?.to_vec4();
+                LdtkEntityField::Color(color)
+            }
+            _ => bail!("Invalid field type"),
+        }
+    } else {
+        match field_type {
+            "F_Bool" => LdtkEntityField::BoolArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<bool>, _>>()?),
+            "F_Int" => LdtkEntityField::IntArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<i32>, _>>()?),
+            "F_Float" => LdtkEntityField::FloatArray(values.iter().map(|p| p.parse()).collect::<Result<Vec<f32>, _>>()?),
+            "F_String" => LdtkEntityField::StringArray(values.iter().map(|p| p.to_string()).collect()),
+            "F_Text" => LdtkEntityField::StringArray(values.iter().map(|p| p.to_string()).collect()),
+            "F_Color" => LdtkEntityField::ColorArray(
+                values
+                    .iter()
+                    .map(|p| {
+                        let hex = format!("{:x}", p.parse::<u32>()?);
+                        let color = Rgb::from_hex_str(&hex).map_err(|_| anyhow!("Failed to parse color"))?.to_vec4();
+
+                        Ok(color)
+                    })
+                    .collect::<Result<Vec<Vec4>, Error>>()?,
+            ),
+            _ => bail!("Invalid field type"),
+        }
+    };
+
+    Ok(value)
+}
+
+fn load_field_value(
+    data: &HashMap<String, JsonValue>,
+    field_type: &str,
+    field_is_array: bool,
+    grid_size: f32,
+    level_size: Vec2,
+) -> Result<Option<LdtkEntityField>> {
+    let value = data.get("__value").unwrap_or(&JsonValue::Null);
+    let values = match value {
+        JsonValue::Array(array) if field_is_array => array.iter().filter(|p| !p.is_null()).collect::<Vec<&JsonValue>>(),
+        JsonValue::Null => return Ok(None),
+        _ => vec![value],
+    };
+
+    let value = match field_type {
+        "F_Point" => {
+            let points = values.iter().map(|p| load_point(p, grid_size, level_size)).collect::<Result<Vec<Vec2>>>()?;
+            if field_is_array {
+                LdtkEntityField::PointArray(points)
+            } else {
+                LdtkEntityField::Point(points[0])
+            }
+        }
+        "F_EntityRef" => {
+            let references = values.iter().map(|p| load_entity_ref(p)).collect::<Result<Vec<LdtkEntityRef>>>()?;
+            if field_is_array {
+                LdtkEntityField::EntityRefArray(references)
+            } else {
+                LdtkEntityField::EntityRef(references[0].clone())
+            }
+        }
+        _ => {
+            let strings = values
+                .iter()
+                .map(|p| p.get::<String>().cloned().ok_or_else(|| anyhow!("Failed to parse string")))
+                .collect::<Result<Vec<String>>>()?;
+            match (field_type, field_is_array) {
+                ("F_Enum", false) => LdtkEntityField::Enum(strings[0].clone()),
+                ("F_Enum", true) => LdtkEntityField::EnumArray(strings),
+                (_, false) => LdtkEntityField::String(strings[0].clone()),
+                (_, true) => LdtkEntityField::StringArray(strings),
+            }
+        }
+    };
+
+    Ok(Some(value))
+}
+
+fn load_point(value: &JsonValue, grid_size: f32, level_size: Vec2) -> Result<Vec2> {
+    let data = value.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to parse point"))?;
+    let cell = json::read_vec2_dissected(data, "cx", "cy")?;
+
+    // Points are stored as grid cells, convert them to the center of the cell in level coordinates
+    Ok(Vec2::new((cell.x + 0.5) * grid_size, level_size.y - (cell.y + 0.5) * grid_size))
+}
+
+fn load_entity_ref(value: &JsonValue) -> Result<LdtkEntityRef> {
+    let data = value.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to parse entity reference"))?;
+
+    Ok(LdtkEntityRef {
+        entity_iid: json::read_value::<String>(data, "entityIid")?,
+        layer_iid: json::read_value::<String>(data, "layerIid")?,
+        level_iid: json::read_value::<String>(data, "levelIid")?,
+        world_iid: json::read_value::<String>(data, "worldIid")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD: &str = r##"{
+        "worldLayout": "LinearHorizontal", "defaultGridSize": 8,
+        "defs": {
+            "tilesets": [{ "uid": 1, "relPath": "images/tiles.png", "tileGridSize": 8, "customData": [{ "tileId": 2, "data": "spikes" }] }],
+            "layers": [
+                { "uid": 10, "identifier": "Collisions", "gridSize": 8, "tilesetDefUid": 1,
+                  "intGridValues": [{ "value": 1, "identifier": "wall", "color": "#FF0000" }] },
+                { "uid": 11, "identifier": "Entities", "gridSize": 8, "tilesetDefUid": null }
+            ],
+            "entities": [{
+                "uid": 20, "identifier": "Player", "pivotX": 0.5, "pivotY": 1, "tileRect": null, "tilesetId": null,
+                "fieldDefs": [{ "uid": 30, "identifier": "target", "type": "F_Point", "isArray": false }]
+            }]
+        },
+        "levels": [
+            {
+                "uid": 0, "iid": "a", "identifier": "First", "pxWid": 16, "pxHei": 16, "worldX": 0, "worldY": 0, "bgColor": "#000000",
+                "__neighbours": [{ "levelIid": "b", "dir": "e" }],
+                "layerInstances": [
+                    {
+                        "layerDefUid": 10, "overrideTilesetUid": null, "__cWid": 2, "__cHei": 2, "__pxTotalOffsetX": 4, "__pxTotalOffsetY": 2,
+                        "intGridCsv": [1, 0, 0, 1],
+                        "autoLayerTiles": [{ "px": [0, 0], "src": [8, 0], "f": 1, "t": 1 }],
+                        "gridTiles": [{ "px": [8, 8], "src": [0, 8], "f": 3, "t": 2 }]
+                    },
+                    {
+                        "layerDefUid": 11, "overrideTilesetUid": null, "__cWid": 2, "__cHei": 2,
+                        "entityInstances": [{
+                            "defUid": 20, "iid": "e", "px": [8, 16], "width": 8, "height": 8,
+                            "fieldInstances": [{ "defUid": 30, "__value": { "cx": 1, "cy": 0 } }]
+                        }]
+                    }
+                ]
+            },
+            { "uid": 1, "iid": "b", "identifier": "Second", "pxWid": 8, "pxHei": 16, "worldX": -1, "worldY": -1, "bgColor": null }
+        ]
+    }"##;
+
+    fn load(data: &str) -> Result<LdtkWorld> {
+        let json = data.parse::<JsonValue>()?;
+        load_world("world", "/world.ldtk", json.get::<HashMap<_, _>>().unwrap())
+    }
+
+    #[test]
+    fn world() {
+        let world = load(WORLD).unwrap();
+
+        assert_eq!(world.layout, LdtkWorldLayout::LinearHorizontal);
+        assert_eq!(world.tilemaps[0].name, "tiles");
+        assert_eq!(world.tilemaps[0].custom[&2], "spikes");
+
+        // Linear layout ignores stored coordinates and places levels one after another
+        assert_eq!(world.levels[0].world_position, Vec2::new(0.0, -16.0));
+        assert_eq!(world.levels[1].world_position, Vec2::new(16.0, -16.0));
+        assert_eq!(world.levels[0].neighbours, [LdtkNeighbour { level_iid: "b".to_string(), direction: LdtkNeighbourDirection::East }]);
+    }
+
+    #[test]
+    fn int_grid() {
+        let world = load(WORLD).unwrap();
+        let layer = &world.levels[0].layers[0];
+
+        assert_eq!(layer.offset, Vec2::new(4.0, -2.0));
+        assert_eq!(layer.int_grid_values[0].name, "wall");
+        assert_eq!(layer.int_grid_values[0].color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+
+        // First row in the file is the top one, so it ends up at the highest Y
+        assert_eq!(layer.get_int_grid_value(0, 1), 1);
+        assert_eq!(layer.get_int_grid_value(1, 1), 0);
+        assert_eq!(layer.get_int_grid_value(1, 0), 1);
+        assert_eq!(layer.get_int_grid_value(2, 0), 0);
+
+        assert!(load(&WORLD.replace("[1, 0, 0, 1]", "[1, 0, 0]")).is_err());
+    }
+
+    #[test]
+    fn tiles() {
+        let world = load(WORLD).unwrap();
+        let tiles = &world.levels[0].layers[0].tiles;
+
+        // Auto-layer tiles go first, so the ones placed by hand are drawn over them
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[0].id, 1);
+        assert_eq!(tiles[0].position, Vec2::new(0.0, 8.0));
+        assert_eq!(tiles[0].source, Vec2::new(8.0, 0.0));
+        assert!(tiles[0].flip_x && !tiles[0].flip_y);
+
+        assert_eq!(tiles[1].id, 2);
+        assert_eq!(tiles[1].position, Vec2::new(8.0, 0.0));
+        assert!(tiles[1].flip_x && tiles[1].flip_y);
+    }
+
+    #[test]
+    fn entities() {
+        let world = load(WORLD).unwrap();
+        let entity = &world.levels[0].layers[1].entities[0];
+
+        assert_eq!(entity.name, "Player");
+        assert_eq!(entity.position, Vec2::new(8.0, 0.0));
+        assert_eq!(entity.tilemap_id, None);
+        assert_eq!(entity.fields["target"], LdtkEntityField::Point(Vec2::new(12.0, 12.0)));
+
+        assert!(load(&WORLD.replace(r#""defUid": 20"#, r#""defUid": 21"#)).is_err());
+    }
 }
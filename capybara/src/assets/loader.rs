@@ -1,5 +1,16 @@
+use super::bmfont::BitmapFont;
 use super::ldtk::LdtkWorld;
+use super::manifest::AssetsGroup;
+use super::manifest::AssetsManifest;
+use super::packer;
+use super::packer::AtlasPackerSettings;
+use super::tiled::TiledMap;
+use super::tiled::TiledTileset;
+use super::vfs::MountKind;
+use super::vfs::VirtualFileSystem;
 use super::*;
+#[cfg(any(windows, unix))]
+use crate::error_continue;
 use crate::filesystem::FileLoadingStatus;
 use crate::filesystem::FileSystem;
 use anyhow::anyhow;
@@ -7,6 +18,7 @@ use anyhow::bail;
 use anyhow::Result;
 use log::error;
 use log::info;
+use png::ColorType;
 use png::Decoder;
 use png::Transformations;
 use quick_xml::events::Event;
@@ -14,16 +26,16 @@ use quick_xml::Reader;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Cursor;
-use std::io::Read;
+use std::mem;
 use std::path::Path;
 use std::str;
 use tinyjson::JsonValue;
-use zip::ZipArchive;
 
 pub struct AssetsLoader {
     pub input: String,
     pub status: AssetsLoadingStatus,
     pub filesystem: FileSystem,
+    pub vfs: VirtualFileSystem,
     pub current_index: usize,
     pub progress: f32,
 
@@ -31,7 +43,16 @@ pub struct AssetsLoader {
     pub raw_fonts: Vec<RawFont>,
     pub raw_atlases: Vec<RawAtlas>,
     pub raw_sounds: Vec<RawSound>,
+    pub raw_animations: Vec<RawAnimation>,
+    pub raw_shaders: Vec<RawShader>,
     pub worlds: Vec<LdtkWorld>,
+    pub maps: Vec<TiledMap>,
+    pub tilesets: Vec<TiledTileset>,
+    pub manifests: Vec<AssetsManifest>,
+    pub bitmap_fonts: Vec<BitmapFont>,
+
+    mount_index: usize,
+    entries: Vec<String>,
 }
 
 impl AssetsLoader {
@@ -40,6 +61,7 @@ impl AssetsLoader {
             input: String::default(),
             status: AssetsLoadingStatus::Idle,
             filesystem: FileSystem::default(),
+            vfs: VirtualFileSystem::default(),
             current_index: 0,
             progress: 0.0,
 
@@ -47,123 +69,118 @@ impl AssetsLoader {
             raw_fonts: Vec::default(),
             raw_atlases: Vec::default(),
             raw_sounds: Vec::default(),
+            raw_animations: Vec::default(),
+            raw_shaders: Vec::default(),
             worlds: Vec::default(),
+            maps: Vec::default(),
+            tilesets: Vec::default(),
+            manifests: Vec::default(),
+            bitmap_fonts: Vec::default(),
+
+            mount_index: 0,
+            entries: Vec::default(),
         }
     }
 
     pub fn load(&mut self, path: &str) -> AssetsLoadingStatus {
         if (self.status == AssetsLoadingStatus::Finished || self.status == AssetsLoadingStatus::Error) && self.input != path {
             self.status = AssetsLoadingStatus::Idle;
-            self.current_index = 0;
-            self.progress = 0.0;
         }
 
+        if self.status == AssetsLoadingStatus::Idle && (self.input != path || self.vfs.mounts.is_empty()) {
+            self.vfs = VirtualFileSystem::default();
+            self.vfs.mount(path, 0);
+            self.input = path.to_string();
+        }
+
+        self.load_mounted()
+    }
+
+    pub fn mount(&mut self, path: &str, priority: i32) {
+        self.vfs.mount(path, priority);
+    }
+
+    pub fn load_mounted(&mut self) -> AssetsLoadingStatus {
         match self.status {
             AssetsLoadingStatus::Idle => {
-                info!("Loading assets from {}", path);
+                info!("Loading assets from {} mounts", self.vfs.mounts.len());
 
-                self.filesystem.read(path);
-                self.input = path.to_string();
+                self.vfs.clear_files();
+                self.mount_index = 0;
+                self.current_index = 0;
+                self.progress = 0.0;
                 self.status = AssetsLoadingStatus::Loading;
             }
             AssetsLoadingStatus::Loading => {
-                match self.filesystem.read(path) {
-                    FileLoadingStatus::Finished => {
-                        self.status = AssetsLoadingStatus::Parsing;
-                    }
-                    FileLoadingStatus::Error => {
-                        self.status = AssetsLoadingStatus::Error;
-                        error!("Failed to load assets file");
-                    }
-                    _ => {}
-                };
-
-                self.progress = *self.filesystem.progress.borrow() / 2.0;
-            }
-            AssetsLoadingStatus::Parsing => {
-                let buffer = self.filesystem.buffer.clone();
-                let buffer = buffer.borrow();
-
-                let slice = buffer.as_slice();
-                let cursor = Cursor::new(slice);
-                let mut archive = match ZipArchive::new(cursor) {
-                    Ok(archive) => archive,
-                    Err(err) => {
-                        self.status = AssetsLoadingStatus::Error;
-                        error!("Failed to create archive reader ({})", err);
+                if self.mount_index == self.vfs.mounts.len() {
+                    self.entries = self.vfs.get_files().map(|p| p.path.clone()).collect();
+                    self.status = AssetsLoadingStatus::Parsing;
 
-                        return self.status;
-                    }
-                };
-
-                if self.current_index == archive.len() {
-                    self.status = AssetsLoadingStatus::Finished;
                     return self.status;
                 }
 
-                let mut data = Vec::default();
-                let entries_count = archive.len();
+                let mount = &self.vfs.mounts[self.mount_index];
+                let mut file_progress = 0.0;
 
-                let mut entry = match archive.by_index(self.current_index) {
-                    Ok(entry) => entry,
-                    Err(err) => {
-                        self.current_index += 1;
-                        error!("Failed to read archive file ({})", err);
+                match mount.kind {
+                    MountKind::Archive => match self.filesystem.read(&mount.path.clone()) {
+                        FileLoadingStatus::Finished => {
+                            // Buffer is moved into the mount, so the file system has to read the file again next time
+                            let data = mem::take(&mut *self.filesystem.buffer.borrow_mut());
+                            *self.filesystem.status.borrow_mut() = FileLoadingStatus::Idle;
 
-                        return self.status;
-                    }
-                };
+                            if let Err(err) = self.vfs.set_archive(self.mount_index, data) {
+                                self.status = AssetsLoadingStatus::Error;
+                                error!("Failed to create archive reader ({})", err);
 
-                self.current_index += 1;
-                self.progress = 0.5 + (self.current_index as f32 / entries_count as f32 / 2.0);
-
-                if entry.is_file() {
-                    let path = Path::new(entry.name());
-                    let path_str = match path.to_str() {
-                        Some(name) => name.to_string(),
-                        None => {
-                            error!("Failed to get string path {:?}", path);
-                            return self.status;
-                        }
-                    };
-                    let name = match path.file_stem().and_then(|p| p.to_str()) {
-                        Some(name) => name.to_string(),
-                        None => {
-                            error!("Failed to get name from path {:?}", path);
-                            return self.status;
+                                return self.status;
+                            }
+
+                            self.mount_index += 1;
                         }
-                    };
-                    let extension = match path.extension().and_then(|p| p.to_str()) {
-                        Some(extension) => extension.to_string(),
-                        None => {
-                            error!("Failed to get extension from path {:?}", path);
+                        FileLoadingStatus::Error => {
+                            self.status = AssetsLoadingStatus::Error;
+                            error!("Failed to load assets file");
+
                             return self.status;
                         }
-                    };
-                    let asset_path = format!("/{}", path_str);
-
-                    data.clear();
+                        _ => file_progress = *self.filesystem.progress.borrow(),
+                    },
+                    #[cfg(any(windows, unix))]
+                    MountKind::Directory => {
+                        self.vfs.scan_directory(self.mount_index);
+                        self.mount_index += 1;
+                    }
+                    #[cfg(not(any(windows, unix)))]
+                    MountKind::Directory => {
+                        self.status = AssetsLoadingStatus::Error;
+                        error!("Directories are not supported on this platform");
 
-                    if let Err(err) = entry.read_to_end(&mut data) {
-                        error!("Failed to read data from archive file ({})", err);
                         return self.status;
                     }
+                }
 
-                    let result = match extension.as_str() {
-                        "png" => Some(self.load_png(&name, &asset_path, &data)),
-                        "ttf" => Some(self.load_ttf(&name, &asset_path, &data)),
-                        "xml" => Some(self.load_xml(&name, &asset_path, &data)),
-                        "ldtk" => Some(self.load_ldtk(&name, &asset_path, &data)),
-                        "wav" => Some(self.load_wav(&name, &asset_path, &data)),
-                        "ogg" => Some(self.load_ogg(&name, &asset_path, &data)),
-                        _ => None,
-                    };
-
-                    match result {
-                        Some(Ok(())) => info!("Asset {} loaded ({} bytes)", entry.name(), entry.size()),
-                        Some(Err(err)) => error!("Failed to load asset {} ({})", entry.name(), err),
-                        None => info!("Asset {} skipped (extension not supported)", entry.name()),
-                    };
+                self.progress = (self.mount_index as f32 + file_progress) / self.vfs.mounts.len() as f32 / 2.0;
+            }
+            AssetsLoadingStatus::Parsing => {
+                if self.current_index == self.entries.len() {
+                    self.resolve_maps();
+                    self.status = AssetsLoadingStatus::Finished;
+                    return self.status;
+                }
+
+                let path = self.entries[self.current_index].clone();
+
+                self.current_index += 1;
+                self.progress = 0.5 + (self.current_index as f32 / self.entries.len() as f32 / 2.0);
+
+                match self.vfs.read(&path) {
+                    Ok(data) => {
+                        if let Err(err) = self.load_file(&path, &data) {
+                            error!("Failed to load asset {} ({})", path, err);
+                        }
+                    }
+                    Err(err) => error!("Failed to read asset {} ({})", path, err),
                 }
             }
             AssetsLoadingStatus::Finished => {
@@ -175,6 +192,79 @@ impl AssetsLoader {
         self.status
     }
 
+    pub fn read(&self, path: &str) -> Result<Vec<u8>> {
+        self.vfs.read(path)
+    }
+
+    pub fn get_group(&self, name: &str) -> Option<&AssetsGroup> {
+        self.manifests.iter().rev().flat_map(|p| p.groups.iter()).find(|p| p.name == name)
+    }
+
+    pub fn get_group_paths(&self, name: &str) -> Result<Vec<String>> {
+        let mut paths = Vec::default();
+        let mut visited = Vec::default();
+
+        self.collect_group_paths(name, &mut paths, &mut visited)?;
+        Ok(paths)
+    }
+
+    pub fn pack_directory(&mut self, name: &str, directory: &str, settings: &AtlasPackerSettings) {
+        let prefix = format!("/{}/", directory.trim_matches('/'));
+        self.pack_filtered(name, |path| path.starts_with(&prefix), settings);
+    }
+
+    pub fn pack_group(&mut self, name: &str, group: &str, settings: &AtlasPackerSettings) -> Result<()> {
+        let paths = self.get_group_paths(group)?;
+        self.pack_filtered(name, |path| paths.iter().any(|p| p == path), settings);
+
+        Ok(())
+    }
+
+    fn load_file(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let path_buf = Path::new(path);
+        let name = path_buf.file_stem().and_then(|p| p.to_str()).ok_or_else(|| anyhow!("Failed to get name from path {:?}", path_buf))?;
+        let extension = path_buf
+            .extension()
+            .and_then(|p| p.to_str())
+            .ok_or_else(|| anyhow!("Failed to get extension from path {:?}", path_buf))?;
+        let asset_path = format!("/{}", path);
+
+        match extension {
+            "png" => self.load_png(name, &asset_path, data)?,
+            "qoi" => self.load_qoi(name, &asset_path, data)?,
+            "tga" => self.load_tga(name, &asset_path, data)?,
+            "ktx2" => self.load_ktx2(name, &asset_path, data)?,
+            "ttf" => self.load_ttf(name, &asset_path, data)?,
+            "fnt" => self.load_fnt(name, &asset_path, data)?,
+            "xml" => self.load_xml(name, &asset_path, data)?,
+            "json" => {
+                // JSON files are used also by the game itself, so the ones not recognized here aren't an error
+                if !self.load_json(name, &asset_path, data)? {
+                    info!("Asset {} skipped (unknown JSON format)", path);
+                    return Ok(());
+                }
+            }
+            "ldtk" => self.load_ldtk(name, &asset_path, data)?,
+            "tmx" => self.load_tmx(name, &asset_path, data)?,
+            "tmj" => self.load_tmj(name, &asset_path, data)?,
+            "tsx" => self.load_tsx(name, &asset_path, data)?,
+            "tsj" => self.load_tsj(name, &asset_path, data)?,
+            "wav" => self.load_wav(name, &asset_path, data)?,
+            "ogg" => self.load_ogg(name, &asset_path, data)?,
+            "anim" => self.load_anim(name, &asset_path, data)?,
+            "manifest" => self.load_manifest(name, &asset_path, data)?,
+            "vert" => self.load_shader(name, &asset_path, RawShaderKind::Vertex, data)?,
+            "frag" => self.load_shader(name, &asset_path, RawShaderKind::Fragment, data)?,
+            _ => {
+                info!("Asset {} skipped (extension not supported)", path);
+                return Ok(());
+            }
+        };
+
+        info!("Asset {} loaded ({} bytes)", path, data.len());
+        Ok(())
+    }
+
     fn load_png(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
         let cursor = Cursor::new(data);
         let mut decoder = Decoder::new(cursor);
@@ -185,27 +275,45 @@ impl AssetsLoader {
         let info = reader.next_frame(&mut data)?;
         let size = Vec2::new(info.width as f32, info.height as f32);
 
-        for i in 0..data.len() / 4 {
-            let r = data[i * 4 + 0] as f32 / 255.0;
-            let g = data[i * 4 + 1] as f32 / 255.0;
-            let b = data[i * 4 + 2] as f32 / 255.0;
-            let a = data[i * 4 + 3] as f32 / 255.0;
-
-            data[i * 4 + 0] = (r * a * 255.0) as u8;
-            data[i * 4 + 1] = (g * a * 255.0) as u8;
-            data[i * 4 + 2] = (b * a * 255.0) as u8;
-        }
+        // Single-channel masks have to be stored as R8 KTX2 or grayscale TGA, PNG always ends up as color
+        let mut data = match info.color_type {
+            ColorType::Grayscale => data.iter().flat_map(|p| [*p, *p, *p, 255]).collect(),
+            ColorType::GrayscaleAlpha => data.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            ColorType::Rgb => data.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            _ => data,
+        };
 
+        image::premultiply(&mut data);
         self.raw_textures.push(RawTexture::new(name, path, size, &data));
 
         Ok(())
     }
 
+    fn load_qoi(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.raw_textures.push(image::load_qoi(name, path, data)?);
+        Ok(())
+    }
+
+    fn load_tga(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.raw_textures.push(image::load_tga(name, path, data)?);
+        Ok(())
+    }
+
+    fn load_ktx2(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.raw_textures.push(image::load_ktx2(name, path, data)?);
+        Ok(())
+    }
+
     fn load_ttf(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
         self.raw_fonts.push(RawFont::new(name, path, data));
         Ok(())
     }
 
+    fn load_fnt(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.bitmap_fonts.push(bmfont::load_fnt(name, path, str::from_utf8(data)?)?);
+        Ok(())
+    }
+
     fn load_xml(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
         let xml = str::from_utf8(data)?;
         let mut image = String::default();
@@ -267,6 +375,25 @@ impl AssetsLoader {
         Ok(())
     }
 
+    fn load_json(&mut self, name: &str, path: &str, data: &[u8]) -> Result<bool> {
+        let json = str::from_utf8(data)?.parse::<JsonValue>()?;
+        let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
+
+        if aseprite::is_sheet(data) {
+            let (atlas, animation) = aseprite::load_sheet(name, path, data)?;
+            self.raw_atlases.push(atlas);
+            self.raw_animations.push(animation);
+        } else if tiled::is_map(data) {
+            self.maps.push(tiled::load_tmj(name, path, data)?);
+        } else if tiled::is_tileset(data) {
+            self.tilesets.push(tiled::load_tsj(name, path, data)?);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
     fn load_ldtk(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
         let json = str::from_utf8(data)?.parse::<JsonValue>()?;
         let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
@@ -275,6 +402,45 @@ impl AssetsLoader {
         Ok(())
     }
 
+    fn load_tmx(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.maps.push(tiled::load_tmx(name, path, str::from_utf8(data)?)?);
+        Ok(())
+    }
+
+    fn load_tmj(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        let json = str::from_utf8(data)?.parse::<JsonValue>()?;
+        let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
+        self.maps.push(tiled::load_tmj(name, path, data)?);
+
+        Ok(())
+    }
+
+    fn load_tsx(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.tilesets.push(tiled::load_tsx(name, path, str::from_utf8(data)?)?);
+        Ok(())
+    }
+
+    fn load_tsj(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        let json = str::from_utf8(data)?.parse::<JsonValue>()?;
+        let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
+        self.tilesets.push(tiled::load_tsj(name, path, data)?);
+
+        Ok(())
+    }
+
+    fn load_manifest(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        let json = str::from_utf8(data)?.parse::<JsonValue>()?;
+        let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
+        self.manifests.push(manifest::load_manifest(name, path, data)?);
+
+        Ok(())
+    }
+
+    fn load_shader(&mut self, name: &str, path: &str, kind: RawShaderKind, data: &[u8]) -> Result<()> {
+        self.raw_shaders.push(RawShader::new(name, path, kind, str::from_utf8(data)?));
+        Ok(())
+    }
+
     fn load_wav(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
         self.raw_sounds.push(RawSound::new(name, path, data));
         Ok(())
@@ -284,6 +450,163 @@ impl AssetsLoader {
         self.raw_sounds.push(RawSound::new(name, path, data));
         Ok(())
     }
+
+    fn load_anim(&mut self, name: &str, path: &str, data: &[u8]) -> Result<()> {
+        let json = str::from_utf8(data)?.parse::<JsonValue>()?;
+        let data = json.get::<HashMap<_, _>>().ok_or_else(|| anyhow!("Failed to read JSON data"))?;
+        self.raw_animations.push(animation::load_animation(name, path, data)?);
+
+        Ok(())
+    }
+}
+
+#[cfg(any(windows, unix))]
+impl AssetsLoader {
+    pub fn reload_changed(&mut self, interval: f32) -> Vec<String> {
+        if self.status != AssetsLoadingStatus::Idle && self.status != AssetsLoadingStatus::Finished {
+            return Vec::default();
+        }
+
+        let mut reloaded = Vec::default();
+        for path in self.vfs.poll_changes(interval) {
+            let data = match self.vfs.read(&path) {
+                Ok(data) => data,
+                Err(err) => error_continue!("Failed to read asset {} ({})", path, err),
+            };
+
+            info!("Reloading asset {}", path);
+
+            if let Err(err) = self.reload_file(&path, &data) {
+                error_continue!("Failed to reload asset {} ({})", path, err);
+            }
+
+            reloaded.push(format!("/{}", path));
+        }
+
+        if !reloaded.is_empty() {
+            self.resolve_maps();
+        }
+
+        reloaded
+    }
+
+    fn reload_file(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let asset_path = format!("/{}", path);
+        let lengths = [
+            self.raw_textures.len(),
+            self.raw_fonts.len(),
+            self.raw_atlases.len(),
+            self.raw_sounds.len(),
+            self.raw_animations.len(),
+            self.raw_shaders.len(),
+            self.worlds.len(),
+            self.maps.len(),
+            self.tilesets.len(),
+            self.manifests.len(),
+            self.bitmap_fonts.len(),
+        ];
+
+        if let Err(err) = self.load_file(path, data) {
+            self.raw_textures.truncate(lengths[0]);
+            self.raw_fonts.truncate(lengths[1]);
+            self.raw_atlases.truncate(lengths[2]);
+            self.raw_sounds.truncate(lengths[3]);
+            self.raw_animations.truncate(lengths[4]);
+            self.raw_shaders.truncate(lengths[5]);
+            self.worlds.truncate(lengths[6]);
+            self.maps.truncate(lengths[7]);
+            self.tilesets.truncate(lengths[8]);
+            self.manifests.truncate(lengths[9]);
+            self.bitmap_fonts.truncate(lengths[10]);
+
+            return Err(err);
+        }
+
+        replace_reloaded(&mut self.raw_textures, lengths[0], |p| p.path == asset_path);
+        replace_reloaded(&mut self.raw_fonts, lengths[1], |p| p.path == asset_path);
+        replace_reloaded(&mut self.raw_atlases, lengths[2], |p| p.path == asset_path);
+        replace_reloaded(&mut self.raw_sounds, lengths[3], |p| p.path == asset_path);
+        replace_reloaded(&mut self.raw_animations, lengths[4], |p| p.path == asset_path);
+        replace_reloaded(&mut self.raw_shaders, lengths[5], |p| p.path == asset_path);
+        replace_reloaded(&mut self.worlds, lengths[6], |p| p.path == asset_path);
+        replace_reloaded(&mut self.maps, lengths[7], |p| p.path == asset_path);
+        replace_reloaded(&mut self.tilesets, lengths[8], |p| p.path == asset_path);
+        replace_reloaded(&mut self.manifests, lengths[9], |p| p.path == asset_path);
+        replace_reloaded(&mut self.bitmap_fonts, lengths[10], |p| p.path == asset_path);
+
+        Ok(())
+    }
+}
+
+impl AssetsLoader {
+    fn pack_filtered(&mut self, name: &str, filter: impl Fn(&str) -> bool, settings: &AtlasPackerSettings) {
+        let textures = self
+            .raw_textures
+            .iter()
+            .filter(|p| filter(&p.path) && p.format == RawTextureFormat::Rgba8)
+            .collect::<Vec<&RawTexture>>();
+        let (pages, packed) = packer::pack_textures(name, &textures, settings);
+
+        // Loose textures are replaced by the atlas, so they don't take memory after instancing, the rejected ones stay loose
+        self.raw_textures.retain(|p| !packed.contains(&p.path));
+
+        for (texture, atlas) in pages {
+            self.raw_textures.push(texture);
+            self.raw_atlases.push(atlas);
+        }
+    }
+
+    fn collect_group_paths(&self, name: &str, paths: &mut Vec<String>, visited: &mut Vec<String>) -> Result<()> {
+        if visited.iter().any(|p| p == name) {
+            return Ok(());
+        }
+
+        visited.push(name.to_string());
+
+        let group = self.get_group(name).ok_or_else(|| anyhow!("Group {} not found", name))?;
+        for dependency in &group.dependencies {
+            self.collect_group_paths(dependency, paths, visited)?;
+        }
+
+        for file in self.vfs.get_files() {
+            let path = format!("/{}", file.path);
+            if group.contains(&path) && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_maps(&mut self) {
+        // External tilesets can be stored anywhere in the archive, so maps are completed after all files are read
+        for map in &mut self.maps {
+            if let Err(err) = tiled::resolve_map(map, &self.tilesets) {
+                error!("Failed to resolve map {} ({})", map.name, err);
+            }
+        }
+    }
+}
+
+// New items are moved into the slots of the ones they replace, so indices used by running scenes stay valid
+#[cfg(any(windows, unix))]
+fn replace_reloaded<T>(items: &mut Vec<T>, length: usize, is_replaced: impl Fn(&T) -> bool) {
+    let replaced = (0..length).filter(|p| is_replaced(&items[*p])).collect::<Vec<usize>>();
+    let mut reloaded = items.split_off(length).into_iter();
+    let mut removed = Vec::default();
+
+    for index in replaced {
+        match reloaded.next() {
+            Some(item) => items[index] = item,
+            None => removed.push(index),
+        }
+    }
+
+    for index in removed.into_iter().rev() {
+        items.remove(index);
+    }
+
+    items.extend(reloaded);
 }
 
 impl Default for AssetsLoader {
@@ -0,0 +1,77 @@
+use crate::utils::json;
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+
+#[derive(Clone, Debug, Default)]
+pub struct AssetsManifest {
+    pub name: String,
+    pub path: String,
+    pub groups: Vec<AssetsGroup>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetsGroup {
+    pub name: String,
+    pub assets: Vec<String>,
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct AssetsReferences {
+    counts: FxHashMap<String, usize>,
+}
+
+impl AssetsGroup {
+    pub fn contains(&self, path: &str) -> bool {
+        self.assets.iter().any(|p| if p.ends_with('/') { path.starts_with(p.as_str()) } else { path == p })
+    }
+}
+
+impl AssetsReferences {
+    pub fn acquire(&mut self, path: &str) -> bool {
+        let count = self.counts.entry(path.to_string()).or_insert(0);
+        *count += 1;
+
+        *count == 1
+    }
+
+    pub fn release(&mut self, path: &str) -> bool {
+        match self.counts.get_mut(path) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            Some(_) => {
+                self.counts.remove(path);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> usize {
+        self.counts.get(path).copied().unwrap_or(0)
+    }
+}
+
+pub fn load_manifest(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<AssetsManifest> {
+    let mut manifest = AssetsManifest { name: name.to_string(), path: path.to_string(), groups: Vec::default() };
+
+    for group in json::read_array(data, "groups")? {
+        let name = json::read_value::<String>(group, "name")?;
+        let read_strings = |key| match group.get(key) {
+            Some(JsonValue::Array(items)) => items.iter().filter_map(|p| p.get::<String>().cloned()).collect(),
+            _ => Vec::default(),
+        };
+
+        // Paths are stored the same way as asset paths, so they can be compared directly
+        let assets = read_strings("assets").into_iter().map(|p: String| format!("/{}", p.trim_start_matches('/'))).collect();
+        let dependencies = read_strings("dependencies");
+
+        manifest.groups.push(AssetsGroup { name, assets, dependencies });
+    }
+
+    Ok(manifest)
+}
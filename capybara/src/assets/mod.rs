@@ -1,7 +1,23 @@
+use crate::animation::animator::AnimatorParameter;
+use crate::animation::animator::AnimatorState;
+use crate::animation::animator::AnimatorTransition;
+use crate::animation::AnimationClip;
 use glam::Vec2;
 
+pub mod animation;
+pub mod aseprite;
+pub mod bmfont;
+pub mod image;
 pub mod ldtk;
 pub mod loader;
+pub mod manifest;
+pub mod packer;
+pub mod preloader;
+pub mod tiled;
+pub mod vfs;
+
+#[cfg(any(windows, unix))]
+pub mod watcher;
 
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub enum AssetsLoadingStatus {
@@ -18,7 +34,23 @@ pub struct RawTexture {
     pub name: String,
     pub path: String,
     pub size: Vec2,
+    pub format: RawTextureFormat,
     pub data: Vec<u8>,
+    pub mipmaps: Vec<Vec<u8>>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RawTextureFormat {
+    #[default]
+    Rgba8,
+    /// Single channel, sampled as grayscale with opaque alpha.
+    R8,
+    Bc1,
+    Bc3,
+    Bc7,
+    Etc2Rgb8,
+    Etc2Rgba8,
+    Astc4x4,
 }
 
 #[derive(Debug, Default)]
@@ -42,6 +74,39 @@ pub struct RawAtlasEntity {
     pub path: String,
     pub position: Vec2,
     pub size: Vec2,
+    pub center: Option<RawAtlasEntityCenter>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RawAtlasEntityCenter {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+#[derive(Debug, Default)]
+pub struct RawAnimation {
+    pub name: String,
+    pub path: String,
+    pub clips: Vec<AnimationClip>,
+    pub states: Vec<AnimatorState>,
+    pub transitions: Vec<AnimatorTransition>,
+    pub parameters: Vec<(String, AnimatorParameter)>,
+    pub initial_state: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct RawShader {
+    pub name: String,
+    pub path: String,
+    pub kind: RawShaderKind,
+    pub source: String,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum RawShaderKind {
+    #[default]
+    Vertex,
+    Fragment,
 }
 
 #[derive(Debug, Default)]
@@ -53,7 +118,23 @@ pub struct RawSound {
 
 impl RawTexture {
     pub fn new(name: &str, path: &str, size: Vec2, data: &[u8]) -> Self {
-        Self { name: name.to_string(), path: path.to_string(), size, data: data.to_vec() }
+        Self { name: name.to_string(), path: path.to_string(), size, format: RawTextureFormat::Rgba8, data: data.to_vec(), mipmaps: Vec::default() }
+    }
+
+    pub fn with_format(mut self, format: RawTextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_mipmaps(mut self, mipmaps: Vec<Vec<u8>>) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+}
+
+impl RawTextureFormat {
+    pub fn is_compressed(&self) -> bool {
+        !matches!(self, RawTextureFormat::Rgba8 | RawTextureFormat::R8)
     }
 }
 
@@ -71,7 +152,13 @@ impl RawAtlas {
 
 impl RawAtlasEntity {
     pub fn new(name: &str, path: &str, position: Vec2, size: Vec2) -> Self {
-        Self { name: name.to_string(), path: path.to_string(), position, size }
+        Self { name: name.to_string(), path: path.to_string(), position, size, center: None }
+    }
+}
+
+impl RawShader {
+    pub fn new(name: &str, path: &str, kind: RawShaderKind, source: &str) -> Self {
+        Self { name: name.to_string(), path: path.to_string(), kind, source: source.to_string() }
     }
 }
 
@@ -0,0 +1,299 @@
+use super::RawAtlas;
+use super::RawAtlasEntity;
+use super::RawTexture;
+use super::RawTextureFormat;
+use crate::error_continue;
+use glam::Vec2;
+use log::info;
+
+#[derive(Clone, Debug)]
+pub struct AtlasPacker {
+    pub size: Vec2,
+
+    free: Vec<(Vec2, Vec2)>,
+    used_size: Vec2,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasPackerSettings {
+    pub max_size: Vec2,
+    pub padding: u32,
+    pub extrusion: u32,
+}
+
+impl AtlasPacker {
+    pub fn new(size: Vec2) -> Self {
+        Self { size, free: vec![(Vec2::ZERO, size)], used_size: Vec2::ZERO }
+    }
+
+    pub fn insert(&mut self, size: Vec2) -> Option<Vec2> {
+        // Best short side fit, the free rectangle which leaves the smallest gap on any side wins
+        let (position, _) = self
+            .free
+            .iter()
+            .filter(|p| p.1.x >= size.x && p.1.y >= size.y)
+            .map(|p| {
+                let leftover = p.1 - size;
+                (p.0, (leftover.min_element() as u32, leftover.max_element() as u32))
+            })
+            .min_by_key(|p| p.1)?;
+
+        let used = (position, size);
+        let mut split = Vec::default();
+
+        self.free.retain(|free| {
+            if !intersects(*free, used) {
+                return true;
+            }
+
+            let (free_min, free_max) = (free.0, free.0 + free.1);
+            let (used_min, used_max) = (used.0, used.0 + used.1);
+
+            if used_min.x > free_min.x {
+                split.push((free_min, Vec2::new(used_min.x - free_min.x, free.1.y)));
+            }
+            if used_max.x < free_max.x {
+                split.push((Vec2::new(used_max.x, free_min.y), Vec2::new(free_max.x - used_max.x, free.1.y)));
+            }
+            if used_min.y > free_min.y {
+                split.push((free_min, Vec2::new(free.1.x, used_min.y - free_min.y)));
+            }
+            if used_max.y < free_max.y {
+                split.push((Vec2::new(free_min.x, used_max.y), Vec2::new(free.1.x, free_max.y - used_max.y)));
+            }
+
+            false
+        });
+
+        self.free.extend(split);
+        self.prune();
+        self.used_size = self.used_size.max(position + size);
+
+        Some(position)
+    }
+
+    pub fn get_used_size(&self) -> Vec2 {
+        self.used_size
+    }
+
+    fn prune(&mut self) {
+        let mut i = 0;
+
+        while i < self.free.len() {
+            let mut j = i + 1;
+            let mut removed = false;
+
+            while j < self.free.len() {
+                if contains(self.free[j], self.free[i]) {
+                    self.free.swap_remove(i);
+                    removed = true;
+                    break;
+                }
+
+                if contains(self.free[i], self.free[j]) {
+                    self.free.swap_remove(j);
+                } else {
+                    j += 1;
+                }
+            }
+
+            if !removed {
+                i += 1;
+            }
+        }
+    }
+}
+
+impl Default for AtlasPackerSettings {
+    fn default() -> Self {
+        Self { max_size: Vec2::new(2048.0, 2048.0), padding: 2, extrusion: 1 }
+    }
+}
+
+pub fn pack_textures(name: &str, textures: &[&RawTexture], settings: &AtlasPackerSettings) -> (Vec<(RawTexture, RawAtlas)>, Vec<String>) {
+    let extrusion = settings.extrusion as f32;
+    let margin = Vec2::splat(extrusion * 2.0 + settings.padding as f32);
+
+    let mut sorted = textures.to_vec();
+    sorted.sort_by_key(|p| (-(p.size.max_element() as i32), -(p.size.x * p.size.y) as i32));
+
+    let mut pages = Vec::<(AtlasPacker, Vec<(&RawTexture, Vec2)>)>::default();
+    for texture in sorted {
+        if texture.format != RawTextureFormat::Rgba8 {
+            error_continue!("Texture {} has format {:?} and can't be packed into atlas {}", texture.name, texture.format, name);
+        }
+
+        if texture.size.x < 1.0 || texture.size.y < 1.0 {
+            error_continue!("Texture {} is empty and can't be packed into atlas {}", texture.name, name);
+        }
+
+        let size = texture.size + margin;
+        if size.x > settings.max_size.x || size.y > settings.max_size.y {
+            error_continue!("Texture {} is too large for atlas {}", texture.name, name);
+        }
+
+        let placed = pages.iter_mut().enumerate().find_map(|(index, page)| page.0.insert(size).map(|p| (index, p)));
+        let (page, position) = match placed {
+            Some(placed) => placed,
+            None => {
+                let mut packer = AtlasPacker::new(settings.max_size);
+                let position = packer.insert(size).unwrap_or_default();
+
+                pages.push((packer, Vec::default()));
+                (pages.len() - 1, position)
+            }
+        };
+
+        pages[page].1.push((texture, position + extrusion));
+    }
+
+    let mut result = Vec::default();
+    let mut packed = Vec::default();
+
+    for (index, (packer, textures)) in pages.iter().enumerate() {
+        let path = format!("/{}_{}", name.trim_start_matches('/'), index);
+        let file = path.rsplit('/').next().unwrap_or_default();
+        let size = packer.get_used_size();
+        let width = size.x as usize;
+
+        let mut data = vec![0; width * size.y as usize * 4];
+        let mut entities = Vec::<RawAtlasEntity>::default();
+
+        for (texture, position) in textures {
+            let (texture_width, texture_height) = (texture.size.x as i32, texture.size.y as i32);
+            let extrusion = settings.extrusion as i32;
+
+            // Every pixel of the border is a copy of the nearest edge pixel, so filtering doesn't bleed neighbours into the sprite
+            for y in -extrusion..texture_height + extrusion {
+                for x in -extrusion..texture_width + extrusion {
+                    let source = (y.clamp(0, texture_height - 1) * texture_width + x.clamp(0, texture_width - 1)) as usize * 4;
+                    let target = ((position.y as i32 + y) as usize * width + (position.x as i32 + x) as usize) * 4;
+
+                    data[target..target + 4].copy_from_slice(&texture.data[source..source + 4]);
+                }
+            }
+
+            let entity_name = if entities.iter().any(|p| p.name == texture.name) {
+                info!("Entity name {} is ambiguous in atlas {}, {} used instead", texture.name, name, texture.path);
+                &texture.path
+            } else {
+                &texture.name
+            };
+
+            entities.push(RawAtlasEntity::new(entity_name, &path, *position, texture.size));
+            packed.push(texture.path.clone());
+        }
+
+        info!("Atlas {} packed ({}x{}, {} entities)", path, size.x, size.y, entities.len());
+
+        let texture = RawTexture::new(file, &format!("{}.png", path), size, &data);
+        let atlas = RawAtlas::new(file, &path, &format!("{}.png", file), entities);
+        result.push((texture, atlas));
+    }
+
+    (result, packed)
+}
+
+fn intersects(a: (Vec2, Vec2), b: (Vec2, Vec2)) -> bool {
+    a.0.x < b.0.x + b.1.x && b.0.x < a.0.x + a.1.x && a.0.y < b.0.y + b.1.y && b.0.y < a.0.y + a.1.y
+}
+
+fn contains(outer: (Vec2, Vec2), inner: (Vec2, Vec2)) -> bool {
+    inner.0.x >= outer.0.x
+        && inner.0.y >= outer.0.y
+        && inner.0.x + inner.1.x <= outer.0.x + outer.1.x
+        && inner.0.y + inner.1.y <= outer.0.y + outer.1.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::loader::AssetsLoader;
+
+    fn texture(name: &str, size: Vec2, color: u8) -> RawTexture {
+        RawTexture::new(name, &format!("/sprites/{}.png", name), size, &vec![color; (size.x * size.y) as usize * 4])
+    }
+
+    #[test]
+    fn insert() {
+        let mut packer = AtlasPacker::new(Vec2::new(8.0, 8.0));
+        let mut used = Vec::default();
+
+        for _ in 0..4 {
+            let position = packer.insert(Vec2::new(4.0, 4.0)).unwrap();
+            assert!(used.iter().all(|p| !intersects(*p, (position, Vec2::new(4.0, 4.0)))));
+            used.push((position, Vec2::new(4.0, 4.0)));
+        }
+
+        assert_eq!(packer.get_used_size(), Vec2::new(8.0, 8.0));
+        assert_eq!(packer.insert(Vec2::new(1.0, 1.0)), None);
+        assert_eq!(AtlasPacker::new(Vec2::new(8.0, 8.0)).insert(Vec2::new(9.0, 1.0)), None);
+    }
+
+    #[test]
+    fn pack() {
+        let settings = AtlasPackerSettings { max_size: Vec2::new(16.0, 16.0), padding: 0, extrusion: 1 };
+        let large = texture("large", Vec2::new(4.0, 2.0), 10);
+        let small = texture("small", Vec2::new(2.0, 2.0), 20);
+        let (pages, packed) = pack_textures("atlas", &[&small, &large], &settings);
+
+        // Larger textures are placed first, every one is surrounded by its extruded edge
+        assert_eq!(packed, ["/sprites/large.png", "/sprites/small.png"]);
+        assert_eq!(pages.len(), 1);
+
+        let (texture, atlas) = &pages[0];
+        assert_eq!(atlas.path, "/atlas_0");
+        assert_eq!(texture.path, "/atlas_0.png");
+        assert_eq!(atlas.entities[0].name, "large");
+        assert_eq!(atlas.entities[0].position, Vec2::new(1.0, 1.0));
+
+        let width = texture.size.x as usize;
+        for entity in &atlas.entities {
+            let color = if entity.name == "large" { 10 } else { 20 };
+            let (min, max) = (entity.position - 1.0, entity.position + entity.size);
+
+            for (x, y) in [(min.x, min.y), (max.x, min.y), (min.x, max.y), (max.x, max.y)] {
+                assert_eq!(texture.data[(y as usize * width + x as usize) * 4], color);
+            }
+        }
+    }
+
+    #[test]
+    fn pages() {
+        let settings = AtlasPackerSettings { max_size: Vec2::new(8.0, 8.0), padding: 2, extrusion: 0 };
+        let textures = (0..5).map(|p| texture(&p.to_string(), Vec2::new(2.0, 2.0), 255)).collect::<Vec<_>>();
+        let (pages, packed) = pack_textures("atlas", &textures.iter().collect::<Vec<_>>(), &settings);
+
+        assert_eq!(packed.len(), 5);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].1.entities.len(), 4);
+        assert_eq!(pages[1].1.entities.len(), 1);
+    }
+
+    #[test]
+    fn rejected() {
+        let settings = AtlasPackerSettings { max_size: Vec2::new(8.0, 8.0), padding: 0, extrusion: 1 };
+        let fitting = texture("fitting", Vec2::new(6.0, 6.0), 255);
+        let large = texture("large", Vec2::new(7.0, 2.0), 255);
+        let empty = texture("empty", Vec2::new(0.0, 2.0), 255);
+        let gray = texture("gray", Vec2::new(2.0, 2.0), 255).with_format(RawTextureFormat::R8);
+        let (pages, packed) = pack_textures("atlas", &[&large, &empty, &gray, &fitting], &settings);
+
+        assert_eq!(packed, ["/sprites/fitting.png"]);
+        assert_eq!(pages[0].1.entities.len(), 1);
+    }
+
+    #[test]
+    fn rejected_stay_loose() {
+        let mut assets = AssetsLoader::new();
+        assets.raw_textures.push(texture("fitting", Vec2::new(2.0, 2.0), 255));
+        assets.raw_textures.push(texture("large", Vec2::new(64.0, 2.0), 255));
+        assets.raw_textures.push(RawTexture::new("outside", "/outside.png", Vec2::new(2.0, 2.0), &[255; 16]));
+
+        assets.pack_directory("atlas", "sprites", &AtlasPackerSettings { max_size: Vec2::new(16.0, 16.0), ..Default::default() });
+        let paths = assets.raw_textures.iter().map(|p| p.path.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(paths, ["/sprites/large.png", "/outside.png", "/atlas_0.png"]);
+        assert_eq!(assets.raw_atlases[0].entities[0].name, "fitting");
+    }
+}
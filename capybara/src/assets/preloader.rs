@@ -0,0 +1,89 @@
+use super::loader::AssetsLoader;
+use super::AssetsLoadingStatus;
+use crate::utils::storage::Storage;
+use log::error;
+use log::info;
+use std::collections::VecDeque;
+use std::mem;
+
+#[derive(Default)]
+pub struct AssetsPreloader {
+    pub packages: Storage<AssetsLoader>,
+    pub queue: VecDeque<String>,
+    pub failed: Vec<String>,
+    pub progress: f32,
+
+    loader: AssetsLoader,
+    queued_count: usize,
+    loaded_count: usize,
+}
+
+impl AssetsPreloader {
+    pub fn enqueue(&mut self, path: &str) -> bool {
+        if self.packages.contains_by_name(path) {
+            return false;
+        }
+
+        if !self.queue.iter().any(|p| p == path) {
+            // Enqueuing a failed package again means that the caller explicitly wants to retry it
+            self.failed.retain(|p| p != path);
+            info!("Package {} queued for preloading", path);
+
+            if self.queue.is_empty() {
+                self.queued_count = 0;
+                self.loaded_count = 0;
+                self.progress = 0.0;
+            }
+
+            self.queue.push_back(path.to_string());
+            self.queued_count += 1;
+        }
+
+        true
+    }
+
+    pub fn process(&mut self) -> Option<usize> {
+        let path = self.queue.front()?.clone();
+        let status = self.loader.load(&path);
+        let mut result = None;
+
+        match status {
+            AssetsLoadingStatus::Finished => {
+                let loader = mem::take(&mut self.loader);
+                match self.packages.store_with_name(&path, loader) {
+                    Ok(id) => result = Some(id),
+                    Err(err) => error!("Failed to store package {} ({})", path, err),
+                }
+
+                self.queue.pop_front();
+                self.loaded_count += 1;
+            }
+            AssetsLoadingStatus::Error => {
+                error!("Failed to preload package {}", path);
+
+                self.loader = AssetsLoader::default();
+                self.failed.push(path);
+                self.queue.pop_front();
+                self.loaded_count += 1;
+            }
+            _ => {}
+        }
+
+        let current_progress = if self.queue.is_empty() { 0.0 } else { self.loader.progress };
+        self.progress = (self.loaded_count as f32 + current_progress) / self.queued_count as f32;
+
+        result
+    }
+
+    pub fn is_loaded(&self, path: &str) -> bool {
+        self.packages.contains_by_name(path)
+    }
+
+    pub fn is_failed(&self, path: &str) -> bool {
+        self.failed.iter().any(|p| p == path)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        !self.queue.is_empty()
+    }
+}
@@ -0,0 +1,924 @@
+use super::vfs;
+use crate::animation::AnimationClip;
+use crate::animation::AnimationFrame;
+use crate::animation::AnimationFrameSource;
+use crate::animation::AnimationMode;
+use crate::utils::json;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::read::ZlibDecoder;
+use glam::Vec2;
+use glam::Vec4;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use rustc_hash::FxHashMap;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::str;
+use std::str::FromStr;
+use tinyjson::JsonValue;
+
+const FLIPPED_HORIZONTALLY_FLAG: u32 = 0x80000000;
+const FLIPPED_VERTICALLY_FLAG: u32 = 0x40000000;
+const FLIPPED_DIAGONALLY_FLAG: u32 = 0x20000000;
+const ROTATED_HEXAGONAL_FLAG: u32 = 0x10000000;
+
+#[derive(Debug, Default)]
+pub struct TiledMap {
+    pub name: String,
+    pub path: String,
+    pub size: Vec2,
+    pub tile_size: Vec2,
+    pub background: Vec4,
+    pub infinite: bool,
+    pub tilesets: Vec<TiledTileset>,
+    pub layers: Vec<TiledLayer>,
+    pub properties: FxHashMap<String, TiledProperty>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledTileset {
+    pub first_id: usize,
+    pub name: String,
+    pub path: String,
+    pub source: Option<String>,
+    pub image: String,
+    pub image_size: Vec2,
+    pub tile_size: Vec2,
+    pub tile_count: usize,
+    pub columns: usize,
+    pub spacing: f32,
+    pub margin: f32,
+    pub properties: FxHashMap<String, TiledProperty>,
+    pub tiles_properties: FxHashMap<usize, FxHashMap<String, TiledProperty>>,
+    pub animations: FxHashMap<usize, AnimationClip>,
+}
+
+#[derive(Debug, Default)]
+pub struct TiledLayer {
+    pub id: usize,
+    pub name: String,
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub visible: bool,
+    pub tiles: Vec<TiledTile>,
+    pub objects: Vec<TiledObject>,
+    pub properties: FxHashMap<String, TiledProperty>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TiledTile {
+    pub id: usize,
+    pub gid: usize,
+    pub tileset_id: usize,
+    pub position: Vec2,
+    pub source: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub flip_diagonal: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct TiledObject {
+    pub id: usize,
+    pub name: String,
+    pub class: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub rotation: f32,
+    pub visible: bool,
+    pub shape: TiledObjectShape,
+    pub tile: Option<TiledTile>,
+    pub properties: FxHashMap<String, TiledProperty>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TiledObjectShape {
+    #[default]
+    Rectangle,
+    Ellipse,
+    Point,
+    Polygon(Vec<Vec2>),
+    Polyline(Vec<Vec2>),
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TiledProperty {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    String(String),
+    Color(Vec4),
+    File(String),
+    Object(usize),
+    Class(FxHashMap<String, TiledProperty>),
+}
+
+#[derive(Debug, Default)]
+struct XmlNode {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<XmlNode>,
+    text: String,
+}
+
+pub fn load_tmx(name: &str, path: &str, data: &str) -> Result<TiledMap> {
+    let root = parse_xml(data)?;
+    if root.name != "map" {
+        bail!("Root element is not a map");
+    }
+
+    let mut map = create_map(name, path, &root.get_or("orientation", "orthogonal".to_string())?)?;
+    map.size = Vec2::new(root.get_or("width", 0.0)?, root.get_or("height", 0.0)?);
+    map.tile_size = Vec2::new(root.get_or("tilewidth", 0.0)?, root.get_or("tileheight", 0.0)?);
+    map.background = parse_color(&root.get_or("backgroundcolor", String::default())?)?;
+    map.infinite = root.get_or("infinite", 0)? == 1;
+    map.properties = read_xml_properties(&root)?;
+
+    for tileset in root.children("tileset") {
+        map.tilesets.push(read_xml_tileset(tileset, path)?);
+    }
+
+    read_xml_layers(&mut map, &root, Vec2::ZERO, 1.0, true)?;
+    Ok(map)
+}
+
+pub fn load_tmj(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<TiledMap> {
+    let orientation = json::read_value_optional::<String>(data, "orientation")?.unwrap_or_else(|| "orthogonal".to_string());
+
+    let mut map = create_map(name, path, &orientation)?;
+    map.size = json::read_vec2_dissected(data, "width", "height")?;
+    map.tile_size = json::read_vec2_dissected(data, "tilewidth", "tileheight")?;
+    map.background = parse_color(&json::read_value_optional::<String>(data, "backgroundcolor")?.unwrap_or_default())?;
+    map.infinite = json::read_value_optional::<bool>(data, "infinite")?.unwrap_or(false);
+    map.properties = read_json_properties(data)?;
+
+    for tileset in json::read_array_optional(data, "tilesets")? {
+        map.tilesets.push(read_json_tileset(tileset, path)?);
+    }
+
+    let layers = json::read_array_optional(data, "layers")?;
+    read_json_layers(&mut map, &layers, Vec2::ZERO, 1.0, true)?;
+
+    Ok(map)
+}
+
+pub fn load_tsx(name: &str, path: &str, data: &str) -> Result<TiledTileset> {
+    let root = parse_xml(data)?;
+    if root.name != "tileset" {
+        bail!("Root element is not a tileset");
+    }
+
+    let mut tileset = read_xml_tileset(&root, path)?;
+    if tileset.name.is_empty() {
+        tileset.name = name.to_string();
+    }
+
+    Ok(tileset)
+}
+
+pub fn load_tsj(name: &str, path: &str, data: &HashMap<String, JsonValue>) -> Result<TiledTileset> {
+    let mut tileset = read_json_tileset(data, path)?;
+    if tileset.name.is_empty() {
+        tileset.name = name.to_string();
+    }
+
+    Ok(tileset)
+}
+
+pub fn is_map(data: &HashMap<String, JsonValue>) -> bool {
+    matches!(data.get("type"), Some(JsonValue::String(kind)) if kind == "map")
+}
+
+pub fn is_tileset(data: &HashMap<String, JsonValue>) -> bool {
+    matches!(data.get("type"), Some(JsonValue::String(kind)) if kind == "tileset")
+}
+
+pub fn resolve_map(map: &mut TiledMap, tilesets: &[TiledTileset]) -> Result<()> {
+    for tileset in &mut map.tilesets {
+        if let Some(source) = &tileset.source {
+            let source_path = vfs::resolve_path(&map.path, source);
+            let external = match tilesets.iter().find(|p| p.path == source_path) {
+                Some(external) => external,
+                None => bail!("Failed to find external tileset {}", source_path),
+            };
+
+            let first_id = tileset.first_id;
+            *tileset = external.clone();
+            tileset.first_id = first_id;
+        }
+    }
+
+    map.tilesets.sort_by_key(|p| p.first_id);
+
+    let tilesets = &map.tilesets;
+    for layer in &mut map.layers {
+        for tile in &mut layer.tiles {
+            resolve_tile(tile, tilesets)?;
+        }
+
+        for object in &mut layer.objects {
+            if let Some(tile) = &mut object.tile {
+                resolve_tile(tile, tilesets)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn create_map(name: &str, path: &str, orientation: &str) -> Result<TiledMap> {
+    if orientation != "orthogonal" {
+        bail!("Orientation {} not supported", orientation);
+    }
+
+    Ok(TiledMap { name: name.to_string(), path: path.to_string(), ..Default::default() })
+}
+
+fn resolve_tile(tile: &mut TiledTile, tilesets: &[TiledTileset]) -> Result<()> {
+    let tileset_id = match tilesets.iter().rposition(|p| p.first_id <= tile.gid) {
+        Some(tileset_id) => tileset_id,
+        None => bail!("Failed to find tileset for tile {}", tile.gid),
+    };
+    let tileset = &tilesets[tileset_id];
+
+    tile.id = tile.gid - tileset.first_id;
+    tile.tileset_id = tileset_id;
+
+    if tileset.columns > 0 {
+        let x = (tile.id % tileset.columns) as f32;
+        let y = (tile.id / tileset.columns) as f32;
+        tile.source = Vec2::splat(tileset.margin) + Vec2::new(x, y) * (tileset.tile_size + tileset.spacing);
+    }
+
+    Ok(())
+}
+
+fn create_tile(gid: u32, position: Vec2) -> Option<TiledTile> {
+    let id = gid & !(FLIPPED_HORIZONTALLY_FLAG | FLIPPED_VERTICALLY_FLAG | FLIPPED_DIAGONALLY_FLAG | ROTATED_HEXAGONAL_FLAG);
+    if id == 0 {
+        return None;
+    }
+
+    Some(TiledTile {
+        gid: id as usize,
+        position,
+        flip_x: gid & FLIPPED_HORIZONTALLY_FLAG != 0,
+        flip_y: gid & FLIPPED_VERTICALLY_FLAG != 0,
+        flip_diagonal: gid & FLIPPED_DIAGONALLY_FLAG != 0,
+        ..Default::default()
+    })
+}
+
+fn push_tiles(map: &TiledMap, layer: &mut TiledLayer, gids: &[u32], origin: Vec2, width: usize) {
+    if width == 0 {
+        return;
+    }
+
+    for (index, gid) in gids.iter().enumerate() {
+        let x = origin.x + (index % width) as f32;
+        let y = origin.y + (index / width) as f32;
+
+        // Tiled uses top-left origin, flip it so the tiles match LDtk layers and the rest of the engine
+        let position = Vec2::new(x * map.tile_size.x, (map.size.y - y - 1.0) * map.tile_size.y);
+
+        if let Some(tile) = create_tile(*gid, position) {
+            layer.tiles.push(tile);
+        }
+    }
+}
+
+fn decode_tiles(data: &str, encoding: Option<&str>, compression: Option<&str>) -> Result<Vec<u32>> {
+    match encoding {
+        Some("csv") => data.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()).map(|p| Ok(p.parse::<u32>()?)).collect(),
+        Some("base64") => {
+            let data = data.chars().filter(|p| !p.is_whitespace()).collect::<String>();
+            let compressed = STANDARD.decode(data)?;
+            let mut bytes = Vec::default();
+
+            match compression {
+                None | Some("") => bytes = compressed,
+                Some("zlib") => _ = ZlibDecoder::new(compressed.as_slice()).read_to_end(&mut bytes)?,
+                Some("gzip") => _ = GzDecoder::new(compressed.as_slice()).read_to_end(&mut bytes)?,
+                Some(compression) => bail!("Compression {} not supported", compression),
+            }
+
+            Ok(bytes.chunks_exact(4).map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]])).collect())
+        }
+        Some(encoding) => bail!("Encoding {} not supported", encoding),
+        None => bail!("Encoding not specified"),
+    }
+}
+
+fn create_animation(id: usize, tile_size: Vec2, frames: Vec<(usize, f32)>) -> AnimationClip {
+    let frames = frames
+        .into_iter()
+        .map(|(index, duration)| AnimationFrame::new(AnimationFrameSource::Tile { size: tile_size, index }, duration))
+        .collect();
+    AnimationClip::new(&id.to_string(), AnimationMode::Loop, frames)
+}
+
+fn create_object_position(map: &TiledMap, position: Vec2, size: Vec2, tile_object: bool) -> Vec2 {
+    let height = map.size.y * map.tile_size.y;
+
+    // Tile objects are anchored at their bottom-left corner, everything else at top-left one
+    if tile_object {
+        Vec2::new(position.x, height - position.y)
+    } else {
+        Vec2::new(position.x, height - position.y - size.y)
+    }
+}
+
+fn read_xml_tileset(node: &XmlNode, path: &str) -> Result<TiledTileset> {
+    let mut tileset = TiledTileset { first_id: node.get_or("firstgid", 1)?, path: path.to_string(), ..Default::default() };
+
+    if let Some(source) = node.get("source") {
+        tileset.source = Some(source.to_string());
+        return Ok(tileset);
+    }
+
+    tileset.name = node.get_or("name", String::default())?;
+    tileset.tile_size = Vec2::new(node.get_or("tilewidth", 0.0)?, node.get_or("tileheight", 0.0)?);
+    tileset.tile_count = node.get_or("tilecount", 0)?;
+    tileset.columns = node.get_or("columns", 0)?;
+    tileset.spacing = node.get_or("spacing", 0.0)?;
+    tileset.margin = node.get_or("margin", 0.0)?;
+    tileset.properties = read_xml_properties(node)?;
+
+    if let Some(image) = node.child("image") {
+        tileset.image = get_name_from_path(&image.get_or("source", String::default())?)?;
+        tileset.image_size = Vec2::new(image.get_or("width", 0.0)?, image.get_or("height", 0.0)?);
+    }
+
+    for tile in node.children("tile") {
+        let id = tile.get_or("id", 0)?;
+        let properties = read_xml_properties(tile)?;
+
+        if !properties.is_empty() {
+            tileset.tiles_properties.insert(id, properties);
+        }
+
+        if let Some(animation) = tile.child("animation") {
+            let mut frames = Vec::default();
+            for frame in animation.children("frame") {
+                frames.push((frame.get_or("tileid", 0)?, frame.get_or("duration", 0.0)?));
+            }
+
+            tileset.animations.insert(id, create_animation(id, tileset.tile_size, frames));
+        }
+    }
+
+    Ok(tileset)
+}
+
+fn read_xml_layers(map: &mut TiledMap, node: &XmlNode, offset: Vec2, opacity: f32, visible: bool) -> Result<()> {
+    for child in &node.children {
+        if !matches!(child.name.as_str(), "layer" | "objectgroup" | "group") {
+            continue;
+        }
+
+        let offset = offset + Vec2::new(child.get_or("offsetx", 0.0)?, -child.get_or("offsety", 0.0)?);
+        let opacity = opacity * child.get_or("opacity", 1.0)?;
+        let visible = visible && child.get_or("visible", 1)? == 1;
+
+        if child.name == "group" {
+            read_xml_layers(map, child, offset, opacity, visible)?;
+            continue;
+        }
+
+        let mut layer = TiledLayer {
+            id: child.get_or("id", 0)?,
+            name: child.get_or("name", String::default())?,
+            offset,
+            opacity,
+            visible,
+            properties: read_xml_properties(child)?,
+            ..Default::default()
+        };
+
+        if child.name == "layer" {
+            if let Some(data) = child.child("data") {
+                let encoding = data.get("encoding");
+                let compression = data.get("compression");
+
+                if data.children.iter().any(|p| p.name == "chunk") {
+                    for chunk in data.children("chunk") {
+                        let origin = Vec2::new(chunk.get_or("x", 0.0)?, chunk.get_or("y", 0.0)?);
+                        let gids = read_xml_tiles(chunk, encoding, compression)?;
+                        push_tiles(map, &mut layer, &gids, origin, chunk.get_or("width", 0)?);
+                    }
+                } else {
+                    let gids = read_xml_tiles(data, encoding, compression)?;
+                    push_tiles(map, &mut layer, &gids, Vec2::ZERO, child.get_or("width", 0)?);
+                }
+            }
+        } else {
+            for object in child.children("object") {
+                layer.objects.push(read_xml_object(map, object)?);
+            }
+        }
+
+        map.layers.push(layer);
+    }
+
+    Ok(())
+}
+
+fn read_xml_tiles(node: &XmlNode, encoding: Option<&str>, compression: Option<&str>) -> Result<Vec<u32>> {
+    match encoding {
+        Some(_) => decode_tiles(&node.text, encoding, compression),
+        None => node.children("tile").map(|p| p.get_or("gid", 0)).collect(),
+    }
+}
+
+fn read_xml_object(map: &TiledMap, node: &XmlNode) -> Result<TiledObject> {
+    let position = Vec2::new(node.get_or("x", 0.0)?, node.get_or("y", 0.0)?);
+    let size = Vec2::new(node.get_or("width", 0.0)?, node.get_or("height", 0.0)?);
+    let gid = node.get_or::<u32>("gid", 0)?;
+
+    let shape = if node.child("ellipse").is_some() {
+        TiledObjectShape::Ellipse
+    } else if node.child("point").is_some() {
+        TiledObjectShape::Point
+    } else if let Some(polygon) = node.child("polygon") {
+        TiledObjectShape::Polygon(parse_points(&polygon.get_or("points", String::default())?)?)
+    } else if let Some(polyline) = node.child("polyline") {
+        TiledObjectShape::Polyline(parse_points(&polyline.get_or("points", String::default())?)?)
+    } else if let Some(text) = node.child("text") {
+        TiledObjectShape::Text(text.text.clone())
+    } else {
+        TiledObjectShape::Rectangle
+    };
+
+    Ok(TiledObject {
+        id: node.get_or("id", 0)?,
+        name: node.get_or("name", String::default())?,
+        class: node.get("class").or(node.get("type")).unwrap_or_default().to_string(),
+        position: create_object_position(map, position, size, gid != 0),
+        size,
+        rotation: -node.get_or::<f32>("rotation", 0.0)?.to_radians(),
+        visible: node.get_or("visible", 1)? == 1,
+        shape,
+        tile: create_tile(gid, Vec2::ZERO),
+        properties: read_xml_properties(node)?,
+    })
+}
+
+fn read_xml_properties(node: &XmlNode) -> Result<FxHashMap<String, TiledProperty>> {
+    let mut properties = FxHashMap::default();
+
+    if let Some(node) = node.child("properties") {
+        for property in node.children("property") {
+            let name = property.get_or("name", String::default())?;
+            let kind = property.get_or("type", "string".to_string())?;
+            let value = property.get("value").map(|p| p.to_string()).unwrap_or_else(|| property.text.clone());
+
+            let value = match kind.as_str() {
+                "class" => TiledProperty::Class(read_xml_properties(property)?),
+                _ => parse_property(&kind, &value)?,
+            };
+
+            properties.insert(name, value);
+        }
+    }
+
+    Ok(properties)
+}
+
+fn read_json_tileset(data: &HashMap<String, JsonValue>, path: &str) -> Result<TiledTileset> {
+    let first_id = json::read_value_optional::<f64>(data, "firstgid")?.unwrap_or(1.0) as usize;
+    let mut tileset = TiledTileset { first_id, path: path.to_string(), ..Default::default() };
+
+    if let Some(source) = json::read_value_optional::<String>(data, "source")? {
+        tileset.source = Some(source);
+        return Ok(tileset);
+    }
+
+    tileset.name = json::read_value_optional::<String>(data, "name")?.unwrap_or_default();
+    tileset.tile_size = json::read_vec2_dissected(data, "tilewidth", "tileheight")?;
+    tileset.tile_count = json::read_value_optional::<f64>(data, "tilecount")?.unwrap_or(0.0) as usize;
+    tileset.columns = json::read_value_optional::<f64>(data, "columns")?.unwrap_or(0.0) as usize;
+    tileset.spacing = json::read_value_optional::<f64>(data, "spacing")?.unwrap_or(0.0) as f32;
+    tileset.margin = json::read_value_optional::<f64>(data, "margin")?.unwrap_or(0.0) as f32;
+    tileset.properties = read_json_properties(data)?;
+
+    if let Some(image) = json::read_value_optional::<String>(data, "image")? {
+        tileset.image = get_name_from_path(&image)?;
+        tileset.image_size = json::read_vec2_dissected(data, "imagewidth", "imageheight")?;
+    }
+
+    for tile in json::read_array_optional(data, "tiles")? {
+        let id = json::read_value::<f64>(tile, "id")? as usize;
+        let properties = read_json_properties(tile)?;
+
+        if !properties.is_empty() {
+            tileset.tiles_properties.insert(id, properties);
+        }
+
+        let animation = json::read_array_optional(tile, "animation")?;
+        if !animation.is_empty() {
+            let mut frames = Vec::default();
+            for frame in animation {
+                frames.push((json::read_value::<f64>(frame, "tileid")? as usize, json::read_value::<f64>(frame, "duration")? as f32));
+            }
+
+            tileset.animations.insert(id, create_animation(id, tileset.tile_size, frames));
+        }
+    }
+
+    Ok(tileset)
+}
+
+fn read_json_layers(map: &mut TiledMap, layers: &[&HashMap<String, JsonValue>], offset: Vec2, opacity: f32, visible: bool) -> Result<()> {
+    for data in layers {
+        let kind = json::read_value::<String>(data, "type")?;
+        if !matches!(kind.as_str(), "tilelayer" | "objectgroup" | "group") {
+            continue;
+        }
+
+        let layer_offset = Vec2::new(
+            json::read_value_optional::<f64>(data, "offsetx")?.unwrap_or(0.0) as f32,
+            -json::read_value_optional::<f64>(data, "offsety")?.unwrap_or(0.0) as f32,
+        );
+        let offset = offset + layer_offset;
+        let opacity = opacity * json::read_value_optional::<f64>(data, "opacity")?.unwrap_or(1.0) as f32;
+        let visible = visible && json::read_value_optional::<bool>(data, "visible")?.unwrap_or(true);
+
+        if kind == "group" {
+            let children = json::read_array_optional(data, "layers")?;
+            read_json_layers(map, &children, offset, opacity, visible)?;
+            continue;
+        }
+
+        let mut layer = TiledLayer {
+            id: json::read_value_optional::<f64>(data, "id")?.unwrap_or(0.0) as usize,
+            name: json::read_value_optional::<String>(data, "name")?.unwrap_or_default(),
+            offset,
+            opacity,
+            visible,
+            properties: read_json_properties(data)?,
+            ..Default::default()
+        };
+
+        if kind == "tilelayer" {
+            let encoding = json::read_value_optional::<String>(data, "encoding")?;
+            let compression = json::read_value_optional::<String>(data, "compression")?;
+
+            if data.contains_key("chunks") {
+                for chunk in json::read_array(data, "chunks")? {
+                    let origin = json::read_vec2_dissected(chunk, "x", "y")?;
+                    let width = json::read_value::<f64>(chunk, "width")? as usize;
+                    let gids = read_json_tiles(chunk, encoding.as_deref(), compression.as_deref())?;
+
+                    push_tiles(map, &mut layer, &gids, origin, width);
+                }
+            } else {
+                let width = json::read_value::<f64>(data, "width")? as usize;
+                let gids = read_json_tiles(data, encoding.as_deref(), compression.as_deref())?;
+
+                push_tiles(map, &mut layer, &gids, Vec2::ZERO, width);
+            }
+        } else {
+            for object in json::read_array_optional(data, "objects")? {
+                layer.objects.push(read_json_object(map, object)?);
+            }
+        }
+
+        map.layers.push(layer);
+    }
+
+    Ok(())
+}
+
+fn read_json_tiles(data: &HashMap<String, JsonValue>, encoding: Option<&str>, compression: Option<&str>) -> Result<Vec<u32>> {
+    match data.get("data") {
+        Some(JsonValue::Array(array)) => {
+            array.iter().map(|p| p.get::<f64>().map(|p| *p as u32).ok_or_else(|| anyhow!("Failed to parse tile"))).collect()
+        }
+        Some(JsonValue::String(text)) => decode_tiles(text, encoding, compression),
+        _ => bail!("Failed to read tiles"),
+    }
+}
+
+fn read_json_object(map: &TiledMap, data: &HashMap<String, JsonValue>) -> Result<TiledObject> {
+    let position = json::read_vec2_dissected(data, "x", "y")?;
+    let size = Vec2::new(
+        json::read_value_optional::<f64>(data, "width")?.unwrap_or(0.0) as f32,
+        json::read_value_optional::<f64>(data, "height")?.unwrap_or(0.0) as f32,
+    );
+    let gid = json::read_value_optional::<f64>(data, "gid")?.unwrap_or(0.0) as u32;
+    let read_points = |name| -> Result<Vec<Vec2>> {
+        json::read_array(data, name)?
+            .iter()
+            .map(|p| Ok(json::read_vec2_dissected(p, "x", "y")? * Vec2::new(1.0, -1.0)))
+            .collect()
+    };
+
+    let shape = if json::read_value_optional::<bool>(data, "ellipse")?.unwrap_or(false) {
+        TiledObjectShape::Ellipse
+    } else if json::read_value_optional::<bool>(data, "point")?.unwrap_or(false) {
+        TiledObjectShape::Point
+    } else if data.contains_key("polygon") {
+        TiledObjectShape::Polygon(read_points("polygon")?)
+    } else if data.contains_key("polyline") {
+        TiledObjectShape::Polyline(read_points("polyline")?)
+    } else if data.contains_key("text") {
+        TiledObjectShape::Text(json::read_value_optional::<String>(json::read_object(data, "text")?, "text")?.unwrap_or_default())
+    } else {
+        TiledObjectShape::Rectangle
+    };
+
+    let class = match json::read_value_optional::<String>(data, "class")? {
+        Some(class) => class,
+        None => json::read_value_optional::<String>(data, "type")?.unwrap_or_default(),
+    };
+
+    Ok(TiledObject {
+        id: json::read_value_optional::<f64>(data, "id")?.unwrap_or(0.0) as usize,
+        name: json::read_value_optional::<String>(data, "name")?.unwrap_or_default(),
+        class,
+        position: create_object_position(map, position, size, gid != 0),
+        size,
+        rotation: -(json::read_value_optional::<f64>(data, "rotation")?.unwrap_or(0.0) as f32).to_radians(),
+        visible: json::read_value_optional::<bool>(data, "visible")?.unwrap_or(true),
+        shape,
+        tile: create_tile(gid, Vec2::ZERO),
+        properties: read_json_properties(data)?,
+    })
+}
+
+fn read_json_properties(data: &HashMap<String, JsonValue>) -> Result<FxHashMap<String, TiledProperty>> {
+    let mut properties = FxHashMap::default();
+
+    for property in json::read_array_optional(data, "properties")? {
+        let name = json::read_value::<String>(property, "name")?;
+        let kind = json::read_value_optional::<String>(property, "type")?.unwrap_or_else(|| "string".to_string());
+        let value = property.get("value").unwrap_or(&JsonValue::Null);
+
+        properties.insert(name, read_json_property(&kind, value)?);
+    }
+
+    Ok(properties)
+}
+
+fn read_json_property(kind: &str, value: &JsonValue) -> Result<TiledProperty> {
+    Ok(match value {
+        JsonValue::Boolean(value) => TiledProperty::Bool(*value),
+        JsonValue::Number(value) => match kind {
+            "int" => TiledProperty::Int(*value as i32),
+            "object" => TiledProperty::Object(*value as usize),
+            _ => TiledProperty::Float(*value as f32),
+        },
+        JsonValue::String(value) => parse_property(kind, value)?,
+        JsonValue::Object(members) => {
+            let mut properties = FxHashMap::default();
+            for (name, value) in members {
+                properties.insert(name.clone(), read_json_property("", value)?);
+            }
+
+            TiledProperty::Class(properties)
+        }
+        _ => TiledProperty::String(String::default()),
+    })
+}
+
+fn parse_property(kind: &str, value: &str) -> Result<TiledProperty> {
+    Ok(match kind {
+        "bool" => TiledProperty::Bool(value == "true"),
+        "int" => TiledProperty::Int(value.parse().unwrap_or(0)),
+        "float" => TiledProperty::Float(value.parse().unwrap_or(0.0)),
+        "color" => TiledProperty::Color(parse_color(value)?),
+        "file" => TiledProperty::File(value.to_string()),
+        "object" => TiledProperty::Object(value.parse().unwrap_or(0)),
+        _ => TiledProperty::String(value.to_string()),
+    })
+}
+
+fn parse_color(value: &str) -> Result<Vec4> {
+    let hex = value.trim_start_matches('#');
+    if !hex.is_ascii() {
+        bail!("Failed to parse color {}", value);
+    }
+
+    let channel = |index: usize| -> Result<f32> { Ok(u8::from_str_radix(&hex[index..index + 2], 16)? as f32 / 255.0) };
+
+    match hex.len() {
+        0 => Ok(Vec4::ZERO),
+        6 => Ok(Vec4::new(channel(0)?, channel(2)?, channel(4)?, 1.0)),
+        8 => Ok(Vec4::new(channel(2)?, channel(4)?, channel(6)?, channel(0)?)),
+        _ => bail!("Failed to parse color {}", value),
+    }
+}
+
+fn parse_points(value: &str) -> Result<Vec<Vec2>> {
+    let mut points = Vec::default();
+    for point in value.split_whitespace() {
+        let (x, y) = point.split_once(',').ok_or_else(|| anyhow!("Failed to parse point {}", point))?;
+        points.push(Vec2::new(x.parse()?, -y.parse::<f32>()?));
+    }
+
+    Ok(points)
+}
+
+fn get_name_from_path(path: &str) -> Result<String> {
+    match Path::new(path).file_stem().and_then(|p| p.to_str()) {
+        Some(name) => Ok(name.to_string()),
+        None => bail!("Failed to get name from path {:?}", path),
+    }
+}
+
+fn parse_xml(xml: &str) -> Result<XmlNode> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![XmlNode::default()];
+
+    reader.trim_text(true);
+    reader.expand_empty_elements(true);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => {
+                let mut node = XmlNode { name: str::from_utf8(element.name().as_ref())?.to_string(), ..Default::default() };
+                for attribute in element.attributes() {
+                    let attribute = attribute?;
+                    let key = str::from_utf8(attribute.key.as_ref())?.to_string();
+                    node.attributes.insert(key, attribute.decode_and_unescape_value(&reader)?.to_string());
+                }
+
+                stack.push(node);
+            }
+            Ok(Event::End(_)) if stack.len() > 1 => {
+                let node = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(node);
+            }
+            Ok(Event::Text(text)) => stack.last_mut().unwrap().text.push_str(&text.unescape()?),
+            Ok(Event::CData(data)) => stack.last_mut().unwrap().text.push_str(str::from_utf8(&data)?),
+            Ok(Event::Eof) => break,
+            Err(error) => bail!("Error at position {}: {:?}", reader.buffer_position(), error),
+            _ => (),
+        }
+    }
+
+    stack.pop().and_then(|mut p| p.children.pop()).ok_or_else(|| anyhow!("XML document is empty"))
+}
+
+impl XmlNode {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(|p| p.as_str())
+    }
+
+    fn get_or<T>(&self, name: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+    {
+        match self.attributes.get(name) {
+            Some(value) => value.parse::<T>().map_err(|_| anyhow!("Failed to parse attribute {} ({})", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|p| p.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlNode> + 'a {
+        self.children.iter().filter(move |p| p.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TMX: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="16" backgroundcolor="#80ff0000" infinite="0">
+  <properties>
+    <property name="music" type="file" value="music.ogg"/>
+  </properties>
+  <tileset firstgid="1" source="tiles.tsx"/>
+  <group name="ground" offsetx="4" offsety="8" opacity="0.5">
+    <layer id="1" name="floor" width="2" height="2">
+      <data encoding="csv">1,2,0,2147483649</data>
+    </layer>
+  </group>
+  <objectgroup id="2" name="entities">
+    <object id="3" name="spawn" type="player" x="8" y="8" width="16" height="16"/>
+    <object id="4" gid="2" x="0" y="32" width="16" height="16"/>
+    <object id="5" x="0" y="0">
+      <polyline points="0,0 16,16"/>
+    </object>
+  </objectgroup>
+</map>"##;
+
+    const TSX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" name="tiles" tilewidth="16" tileheight="16" spacing="1" margin="2" tilecount="4" columns="2">
+  <image source="images/tiles.png" width="35" height="35"/>
+  <tile id="1">
+    <properties>
+      <property name="solid" type="bool" value="true"/>
+    </properties>
+    <animation>
+      <frame tileid="1" duration="100"/>
+      <frame tileid="2" duration="100"/>
+    </animation>
+  </tile>
+</tileset>"#;
+
+    const TMJ: &str = r##"{
+        "type": "map", "orientation": "orthogonal", "width": 2, "height": 1, "tilewidth": 8, "tileheight": 8, "infinite": false,
+        "tilesets": [{ "firstgid": 1, "source": "tiles.tsj" }],
+        "layers": [
+            { "type": "tilelayer", "id": 1, "name": "floor", "width": 2, "height": 1, "data": [1, 1073741826] },
+            { "type": "objectgroup", "id": 2, "name": "entities", "visible": false, "objects": [
+                { "id": 3, "name": "area", "class": "trigger", "x": 0, "y": 0, "width": 8, "height": 8, "ellipse": true,
+                  "properties": [{ "name": "tint", "type": "color", "value": "#ff00ff00" }] }
+            ]}
+        ]
+    }"##;
+
+    const TSJ: &str = r#"{
+        "type": "tileset", "name": "tiles", "tilewidth": 8, "tileheight": 8, "tilecount": 2, "columns": 2,
+        "image": "tiles.png", "imagewidth": 16, "imageheight": 8,
+        "tiles": [{ "id": 0, "properties": [{ "name": "cost", "type": "int", "value": 3 }] }]
+    }"#;
+
+    fn parse_json(data: &str) -> HashMap<String, JsonValue> {
+        data.parse::<JsonValue>().unwrap().get::<HashMap<_, _>>().unwrap().clone()
+    }
+
+    #[test]
+    fn xml() {
+        let tileset = load_tsx("tiles", "/maps/tiles.tsx", TSX).unwrap();
+        assert_eq!(tileset.image, "tiles");
+        assert_eq!(tileset.tile_count, 4);
+        assert_eq!(tileset.tiles_properties[&1]["solid"], TiledProperty::Bool(true));
+        assert_eq!(tileset.animations[&1].frames.len(), 2);
+
+        let mut map = load_tmx("level", "/maps/level.tmx", TMX).unwrap();
+        resolve_map(&mut map, &[tileset]).unwrap();
+
+        assert_eq!(map.size, Vec2::new(2.0, 2.0));
+        assert_eq!(map.background, Vec4::new(1.0, 0.0, 0.0, 128.0 / 255.0));
+        assert_eq!(map.properties["music"], TiledProperty::File("music.ogg".to_string()));
+        assert_eq!(map.tilesets[0].name, "tiles");
+
+        // Group offset and opacity are applied to the nested layer, Y axis goes up
+        let floor = &map.layers[0];
+        assert_eq!(floor.offset, Vec2::new(4.0, -8.0));
+        assert_eq!(floor.opacity, 0.5);
+        assert_eq!(floor.tiles.len(), 3);
+        assert_eq!(floor.tiles[0].position, Vec2::new(0.0, 16.0));
+        assert_eq!(floor.tiles[1].source, Vec2::new(19.0, 2.0));
+        assert_eq!(floor.tiles[2].position, Vec2::new(16.0, 0.0));
+        assert!(floor.tiles[2].flip_x);
+
+        let entities = &map.layers[1];
+        assert_eq!(entities.objects[0].class, "player");
+        assert_eq!(entities.objects[0].position, Vec2::new(8.0, 8.0));
+        assert_eq!(entities.objects[1].position, Vec2::new(0.0, 0.0));
+        assert_eq!(entities.objects[1].tile.as_ref().unwrap().id, 1);
+        assert_eq!(entities.objects[2].shape, TiledObjectShape::Polyline(vec![Vec2::new(0.0, 0.0), Vec2::new(16.0, -16.0)]));
+    }
+
+    #[test]
+    fn json() {
+        let tileset = load_tsj("tiles", "/maps/tiles.tsj", &parse_json(TSJ)).unwrap();
+        assert_eq!(tileset.columns, 2);
+        assert_eq!(tileset.tiles_properties[&0]["cost"], TiledProperty::Int(3));
+
+        let mut map = load_tmj("level", "/maps/level.tmj", &parse_json(TMJ)).unwrap();
+        resolve_map(&mut map, &[tileset]).unwrap();
+
+        let floor = &map.layers[0];
+        assert_eq!(floor.tiles.len(), 2);
+        assert_eq!(floor.tiles[1].id, 1);
+        assert_eq!(floor.tiles[1].source, Vec2::new(8.0, 0.0));
+        assert!(floor.tiles[1].flip_y);
+
+        let entities = &map.layers[1];
+        assert!(!entities.visible);
+        assert_eq!(entities.objects[0].class, "trigger");
+        assert_eq!(entities.objects[0].shape, TiledObjectShape::Ellipse);
+        assert_eq!(entities.objects[0].properties["tint"], TiledProperty::Color(Vec4::new(0.0, 1.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn missing_tileset() {
+        let mut map = load_tmx("level", "/maps/level.tmx", TMX).unwrap();
+        assert!(resolve_map(&mut map, &[]).is_err());
+    }
+
+    #[test]
+    fn invalid_color() {
+        // Byte lengths match valid colors, but the channels would be sliced in the middle of a character
+        assert!(parse_color("#fé000").is_err());
+        assert!(parse_color("#fé00000").is_err());
+        assert!(parse_color("#fff").is_err());
+        assert!(load_tmx("level", "/maps/level.tmx", &TMX.replace("#80ff0000", "#fé00000")).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(any(windows, unix))]
+use super::watcher::AssetsWatcher;
+use anyhow::anyhow;
+use anyhow::Result;
+use log::info;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::io::Read;
+use zip::ZipArchive;
+
+#[cfg(not(any(windows, unix)))]
+use anyhow::bail;
+#[cfg(any(windows, unix))]
+use std::fs;
+#[cfg(any(windows, unix))]
+use std::path::Path;
+
+#[derive(Default)]
+pub struct VirtualFileSystem {
+    pub mounts: Vec<Mount>,
+    files: BTreeMap<String, VirtualFile>,
+}
+
+pub struct Mount {
+    pub path: String,
+    pub priority: i32,
+    pub kind: MountKind,
+
+    // Central directory is parsed once, reading an entry needs mutable access to the archive
+    archive: Option<RefCell<ZipArchive<Cursor<Vec<u8>>>>>,
+    #[cfg(any(windows, unix))]
+    watcher: Option<AssetsWatcher>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MountKind {
+    Archive,
+    Directory,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualFile {
+    pub path: String,
+    pub mount: usize,
+    pub index: usize,
+}
+
+impl VirtualFileSystem {
+    pub fn mount(&mut self, path: &str, priority: i32) -> usize {
+        #[cfg(any(windows, unix))]
+        let kind = if Path::new(path).is_dir() { MountKind::Directory } else { MountKind::Archive };
+        #[cfg(not(any(windows, unix)))]
+        let kind = MountKind::Archive;
+
+        info!("Mounting {} ({:?}, priority {})", path, kind, priority);
+
+        self.mounts.push(Mount {
+            path: path.to_string(),
+            priority,
+            kind,
+            archive: None,
+            #[cfg(any(windows, unix))]
+            watcher: None,
+        });
+        self.mounts.len() - 1
+    }
+
+    pub fn clear_files(&mut self) {
+        self.files.clear();
+
+        for mount in &mut self.mounts {
+            mount.archive = None;
+
+            #[cfg(any(windows, unix))]
+            {
+                mount.watcher = None;
+            }
+        }
+    }
+
+    pub fn set_archive(&mut self, mount: usize, data: Vec<u8>) -> Result<()> {
+        let mut paths = Vec::default();
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index)?;
+            if entry.is_file() {
+                paths.push((entry.name().to_string(), index));
+            }
+        }
+
+        for (path, index) in paths {
+            self.register(&path, mount, index);
+        }
+
+        self.mounts[mount].archive = Some(RefCell::new(archive));
+        Ok(())
+    }
+
+    #[cfg(any(windows, unix))]
+    pub fn scan_directory(&mut self, mount: usize) {
+        let mut watcher = AssetsWatcher::new(&self.mounts[mount].path);
+        for path in watcher.scan() {
+            self.register(&path, mount, 0);
+        }
+
+        self.mounts[mount].watcher = Some(watcher);
+    }
+
+    #[cfg(any(windows, unix))]
+    pub fn poll_changes(&mut self, interval: f32) -> Vec<String> {
+        let mut changed = Vec::default();
+
+        for mount in 0..self.mounts.len() {
+            let paths = match &mut self.mounts[mount].watcher {
+                Some(watcher) => watcher.poll(interval),
+                None => continue,
+            };
+
+            // Files shadowed by a mount with higher priority are ignored, the overlay decides what the game sees
+            for path in paths {
+                if self.register(&path, mount, 0) {
+                    changed.push(path);
+                }
+            }
+        }
+
+        changed
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains_key(path.trim_start_matches('/'))
+    }
+
+    pub fn get_file(&self, path: &str) -> Option<&VirtualFile> {
+        self.files.get(path.trim_start_matches('/'))
+    }
+
+    pub fn get_files(&self) -> impl Iterator<Item = &VirtualFile> {
+        self.files.values()
+    }
+
+    pub fn read(&self, path: &str) -> Result<Vec<u8>> {
+        let file = self.get_file(path).ok_or_else(|| anyhow!("File {} not found", path))?;
+        let mount = &self.mounts[file.mount];
+        let mut data = Vec::default();
+
+        match mount.kind {
+            MountKind::Archive => {
+                let mut archive = mount.archive.as_ref().ok_or_else(|| anyhow!("Archive {} not loaded", mount.path))?.borrow_mut();
+                archive.by_index(file.index)?.read_to_end(&mut data)?;
+            }
+            #[cfg(any(windows, unix))]
+            MountKind::Directory => {
+                data = fs::read(Path::new(&mount.path).join(&file.path))?;
+            }
+            #[cfg(not(any(windows, unix)))]
+            MountKind::Directory => bail!("Directories are not supported on this platform"),
+        }
+
+        Ok(data)
+    }
+
+    fn register(&mut self, path: &str, mount: usize, index: usize) -> bool {
+        // Mounts with equal priority are resolved by order, so the one mounted later overrides the earlier
+        if let Some(file) = self.files.get(path) {
+            if (self.mounts[file.mount].priority, file.mount) > (self.mounts[mount].priority, mount) {
+                return false;
+            }
+        }
+
+        self.files.insert(path.to_string(), VirtualFile { path: path.to_string(), mount, index });
+        true
+    }
+}
+
+pub fn resolve_path(base: &str, relative: &str) -> String {
+    let mut components = Vec::default();
+    let directory = base.rsplit_once('/').map(|p| p.0).unwrap_or_default();
+
+    for component in directory.split('/').chain(relative.split('/')) {
+        match component {
+            "" | "." => {}
+            ".." => _ = components.pop(),
+            _ => components.push(component),
+        }
+    }
+
+    format!("/{}", components.join("/"))
+}
@@ -0,0 +1,75 @@
+use instant::Instant;
+use log::error;
+use rustc_hash::FxHashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+pub struct AssetsWatcher {
+    pub root: PathBuf,
+
+    files: FxHashMap<String, SystemTime>,
+    timestamp: Instant,
+}
+
+impl AssetsWatcher {
+    pub fn new(root: &str) -> Self {
+        Self { root: PathBuf::from(root), files: FxHashMap::default(), timestamp: Instant::now() }
+    }
+
+    pub fn poll(&mut self, interval: f32) -> Vec<String> {
+        let now = Instant::now();
+        if (now - self.timestamp).as_secs_f32() < interval {
+            return Vec::default();
+        }
+
+        self.timestamp = now;
+        self.scan()
+    }
+
+    pub fn scan(&mut self) -> Vec<String> {
+        let mut files = Vec::default();
+        let mut changed = Vec::default();
+
+        collect_files(&self.root, &self.root, &mut files);
+
+        for (path, modified) in files {
+            if self.files.get(&path) != Some(&modified) {
+                self.files.insert(path.clone(), modified);
+                changed.push(path);
+            }
+        }
+
+        changed.sort();
+        changed
+    }
+}
+
+fn collect_files(root: &Path, directory: &Path, files: &mut Vec<(String, SystemTime)>) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            error!("Failed to read directory {:?} ({})", directory, err);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, files);
+            continue;
+        }
+
+        let modified = match entry.metadata().and_then(|p| p.modified()) {
+            Ok(modified) => modified,
+            Err(_) => continue,
+        };
+
+        // Paths are stored the same way as archive entries, so both sources produce identical asset paths
+        if let Some(relative) = path.strip_prefix(root).ok().and_then(|p| p.to_str()) {
+            files.push((relative.replace('\\', "/"), modified));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::sound::Sound;
 use crate::assets::loader::AssetsLoader;
+use crate::assets::manifest::AssetsReferences;
 use crate::error_continue;
 use crate::utils::storage::Storage;
 use anyhow::Result;
@@ -7,36 +8,75 @@ use kira::manager::backend::cpal::CpalBackend;
 use kira::manager::AudioManager;
 use kira::manager::AudioManagerSettings;
 use kira::track::TrackId;
-use log::error;
 use log::info;
 
 pub struct AudioContext {
     pub inner: AudioManager<CpalBackend>,
     pub sounds: Storage<Sound>,
+    pub references: AssetsReferences,
 }
 
 impl AudioContext {
     pub fn new() -> Result<Self> {
-        Ok(Self { inner: AudioManager::<CpalBackend>::new(AudioManagerSettings::default())?, sounds: Storage::default() })
+        Ok(Self {
+            inner: AudioManager::<CpalBackend>::new(AudioManagerSettings::default())?,
+            sounds: Storage::default(),
+            references: AssetsReferences::default(),
+        })
     }
 
     pub fn instantiate_assets(&mut self, assets: &AssetsLoader, prefix: Option<&str>, track: Option<TrackId>) {
         info!("Instancing audio assets, prefix {}", prefix.unwrap_or("none"));
+        self.instantiate_filtered(assets, |path| prefix.map(|p| path.starts_with(p)).unwrap_or(true), track, false);
+    }
 
-        for raw in &assets.raw_sounds {
-            if let Some(prefix) = &prefix {
-                if !raw.path.starts_with(prefix) {
-                    continue;
+    pub fn instantiate_group(&mut self, assets: &AssetsLoader, group: &str, track: Option<TrackId>) -> Result<()> {
+        info!("Instancing audio assets, group {}", group);
+
+        let paths = assets.get_group_paths(group)?;
+        let acquired = paths.into_iter().filter(|p| self.references.acquire(p)).collect::<Vec<String>>();
+        self.instantiate_filtered(assets, |path| acquired.iter().any(|p| p == path), track, false);
+
+        Ok(())
+    }
+
+    pub fn reload_assets(&mut self, assets: &AssetsLoader, paths: &[String], track: Option<TrackId>) {
+        info!("Reloading audio assets, {} files", paths.len());
+        self.instantiate_filtered(assets, |path| paths.iter().any(|p| p == path), track, true);
+    }
+
+    pub fn unload_group(&mut self, assets: &AssetsLoader, group: &str) -> Result<()> {
+        info!("Unloading audio assets, group {}", group);
+
+        for path in assets.get_group_paths(group)? {
+            if self.references.release(&path) {
+                if let Ok(id) = self.sounds.get_id(&path) {
+                    self.sounds.remove(id);
                 }
             }
+        }
+
+        Ok(())
+    }
+
+    fn instantiate_filtered(&mut self, assets: &AssetsLoader, filter: impl Fn(&str) -> bool, track: Option<TrackId>, reload: bool) {
+        for raw in &assets.raw_sounds {
+            if !filter(&raw.path) {
+                continue;
+            }
+
+            if !reload && self.sounds.contains_by_name(&raw.path) {
+                error_continue!("Failed to instantiate sound {} (name already exists)", raw.path);
+            }
 
             let sound = match Sound::new(raw, track) {
                 Ok(sound) => sound,
                 Err(err) => error_continue!("Failed to create sound {} ({})", raw.name, err),
             };
 
-            if let Err(err) = self.sounds.store_with_name(&raw.name, sound) {
-                error!("Failed to instantiate sound {} ({})", raw.name, err);
+            let (id, _) = self.sounds.store_or_replace_with_name(&raw.path, sound);
+            if !self.sounds.contains_by_name(&raw.name) {
+                let _ = self.sounds.add_alias(id, &raw.name);
             }
         }
     }
@@ -0,0 +1,85 @@
+use instant::Instant;
+
+pub const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+pub const DEFAULT_MAX_DELTA: f32 = 0.1;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TimeSource {
+    Real,
+    Fixed { delta: f32 },
+    Scaled { scale: f32 },
+    Paused,
+}
+
+#[derive(Clone, Debug)]
+pub struct Clock {
+    pub source: TimeSource,
+    pub timestep: f32,
+    pub max_delta: f32,
+
+    pub delta: f32,
+    pub real_delta: f32,
+    pub elapsed: f32,
+    pub steps: u64,
+
+    timestamp: Instant,
+    accumulator: f32,
+}
+
+impl Clock {
+    pub fn new(source: TimeSource, timestep: f32, max_delta: f32) -> Self {
+        Self { source, timestep, max_delta, delta: 0.0, real_delta: 0.0, elapsed: 0.0, steps: 0, timestamp: Instant::now(), accumulator: 0.0 }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        let now = Instant::now();
+        self.real_delta = (now - self.timestamp).as_secs_f32();
+        self.timestamp = now;
+
+        self.delta = match self.source {
+            TimeSource::Real => self.real_delta.min(self.max_delta),
+            TimeSource::Fixed { delta } => delta,
+            TimeSource::Scaled { scale } => self.real_delta.min(self.max_delta) * scale,
+            TimeSource::Paused => 0.0,
+        };
+
+        self.elapsed += self.delta;
+        self.accumulator += self.delta;
+
+        self.delta
+    }
+
+    pub fn step(&mut self) -> bool {
+        if self.accumulator < self.timestep {
+            return false;
+        }
+
+        self.accumulator -= self.timestep;
+        self.steps += 1;
+
+        true
+    }
+
+    pub fn get_alpha(&self) -> f32 {
+        (self.accumulator / self.timestep).clamp(0.0, 1.0)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.source == TimeSource::Paused
+    }
+
+    pub fn reset(&mut self) {
+        self.delta = 0.0;
+        self.real_delta = 0.0;
+        self.elapsed = 0.0;
+        self.steps = 0;
+        self.timestamp = Instant::now();
+        self.accumulator = 0.0;
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(TimeSource::Real, DEFAULT_TIMESTEP, DEFAULT_MAX_DELTA)
+    }
+}
@@ -0,0 +1,6 @@
+pub mod query;
+pub mod systems;
+pub mod world;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LightOccluder;
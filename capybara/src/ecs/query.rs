@@ -0,0 +1,34 @@
+use super::world::World;
+
+pub trait Query {
+    fn get_entities(world: &World) -> Vec<usize>;
+}
+
+macro_rules! query {
+    ($first:ident) => {
+        impl<$first> Query for ($first,)
+        where
+            $first: 'static,
+        {
+            fn get_entities(world: &World) -> Vec<usize> {
+                world.iter::<$first>().map(|(entity, _)| entity).collect()
+            }
+        }
+    };
+    ($first:ident, $($rest:ident),+) => {
+        impl<$first, $($rest),+> Query for ($first, $($rest),+)
+        where
+            $first: 'static,
+            $($rest: 'static,)+
+        {
+            fn get_entities(world: &World) -> Vec<usize> {
+                world.iter::<$first>().map(|(entity, _)| entity).filter(|&entity| $(world.has::<$rest>(entity))&&+).collect()
+            }
+        }
+    };
+}
+
+query!(A);
+query!(A, B);
+query!(A, B, C);
+query!(A, B, C, D);
@@ -0,0 +1,156 @@
+use super::LightOccluder;
+use crate::animation::animator::Animator;
+use crate::app::ApplicationState;
+use crate::light::emitter::LightEmitter;
+use crate::renderer::shape::Shape;
+use crate::renderer::sprite::Sprite;
+use crate::renderer::sprite::TextureId;
+use crate::renderer::text::Text;
+use crate::renderer::tilemap::Tilemap;
+use anyhow::Result;
+use glam::Vec2;
+use instant::Instant;
+
+#[cfg(feature = "physics")]
+use rapier2d::prelude::RigidBodyHandle;
+
+pub type System<G> = fn(ApplicationState<G>) -> Result<()>;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SystemPhase {
+    Fixed,
+    Frame,
+}
+
+pub struct SystemDefinition<G> {
+    pub name: String,
+    pub phase: SystemPhase,
+    pub enabled: bool,
+    pub system: System<G>,
+}
+
+impl<G> SystemDefinition<G> {
+    pub fn new(name: &str, phase: SystemPhase, system: System<G>) -> Self {
+        Self { name: name.to_string(), phase, enabled: true, system }
+    }
+}
+
+pub fn get_default_systems<G>() -> Vec<SystemDefinition<G>> {
+    vec![
+        SystemDefinition::new("animate_sprites", SystemPhase::Frame, animate_sprites),
+        #[cfg(feature = "physics")]
+        SystemDefinition::new("sync_rigidbodies", SystemPhase::Frame, sync_rigidbodies),
+        SystemDefinition::new("draw_tilemaps", SystemPhase::Frame, draw_tilemaps),
+        SystemDefinition::new("draw_sprites", SystemPhase::Frame, draw_sprites),
+        SystemDefinition::new("draw_shapes", SystemPhase::Frame, draw_shapes),
+        SystemDefinition::new("draw_texts", SystemPhase::Frame, draw_texts),
+        SystemDefinition::new("draw_lights", SystemPhase::Frame, draw_lights),
+    ]
+}
+
+pub fn animate_sprites<G>(state: ApplicationState<G>) -> Result<()> {
+    let now = Instant::now();
+    for (_, sprite) in state.world.iter_mut::<Sprite>() {
+        if sprite.is_animation() {
+            sprite.animate(now);
+        }
+    }
+
+    let delta = state.clock.delta;
+    for entity in state.world.query::<(Animator, Sprite)>() {
+        let animator = state.world.get_mut::<Animator>(entity)?;
+        animator.update(delta);
+
+        if let Some(source) = animator.get_frame().map(|p| p.source.clone()) {
+            source.apply(state.world.get_mut::<Sprite>(entity)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "physics")]
+pub fn sync_rigidbodies<G>(state: ApplicationState<G>) -> Result<()> {
+    let alpha = state.clock.get_alpha();
+    let pixels_per_meter = state.world.pixels_per_meter;
+
+    for entity in state.world.query::<(RigidBodyHandle, Sprite)>() {
+        let handle = *state.world.get::<RigidBodyHandle>(entity)?;
+        if let Some(interpolation_data) = state.physics.interpolation_data.get(&handle) {
+            let sprite = state.world.get_mut::<Sprite>(entity)?;
+            sprite.position = interpolation_data.get_position_interpolated(alpha) * pixels_per_meter;
+            sprite.rotation = interpolation_data.get_rotation_interpolated(alpha);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn draw_tilemaps<G>(state: ApplicationState<G>) -> Result<()> {
+    for (_, tilemap) in state.world.iter_mut::<Tilemap>() {
+        state.renderer.draw_tilemap(tilemap);
+    }
+
+    Ok(())
+}
+
+pub fn draw_sprites<G>(state: ApplicationState<G>) -> Result<()> {
+    for (_, sprite) in state.world.iter::<Sprite>() {
+        state.renderer.draw_sprite(sprite);
+    }
+
+    Ok(())
+}
+
+pub fn draw_shapes<G>(state: ApplicationState<G>) -> Result<()> {
+    for (_, shape) in state.world.iter::<Shape>() {
+        state.renderer.draw_shape(shape);
+    }
+
+    Ok(())
+}
+
+pub fn draw_texts<G>(state: ApplicationState<G>) -> Result<()> {
+    for (_, text) in state.world.iter::<Text>() {
+        state.renderer.draw_text(text);
+    }
+
+    Ok(())
+}
+
+pub fn draw_lights<G>(state: ApplicationState<G>) -> Result<()> {
+    let emitters = state.world.query::<(LightEmitter,)>();
+    if emitters.is_empty() {
+        return Ok(());
+    }
+
+    let mut edges = Vec::default();
+    for entity in state.world.query::<(LightOccluder,)>() {
+        if let Ok(sprite) = state.world.get::<Sprite>(entity) {
+            let texture_size = match sprite.texture_id {
+                TextureId::Some(texture_id) => state.renderer.textures.get(texture_id)?.size,
+                _ => Vec2::new(1.0, 1.0),
+            };
+
+            edges.extend(sprite.get_edges(texture_size));
+        }
+
+        if let Ok(shape) = state.world.get::<Shape>(entity) {
+            edges.extend(shape.get_edges());
+        }
+    }
+
+    for entity in emitters {
+        let position = state.world.get::<Sprite>(entity).map(|p| p.position).ok();
+        let emitter = state.world.get_mut::<LightEmitter>(entity)?;
+
+        if let Some(position) = position {
+            emitter.position = position;
+        }
+
+        let response = emitter.generate(&edges);
+        state.renderer.draw_shape(&response.shape);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use super::query::Query;
+use crate::utils::storage::Storage;
+use anyhow::anyhow;
+use anyhow::Result;
+use rustc_hash::FxHashMap;
+use std::any::type_name;
+use std::any::Any;
+use std::any::TypeId;
+
+pub struct World {
+    pub pixels_per_meter: f32,
+
+    entities: Storage<()>,
+    components: FxHashMap<TypeId, Box<dyn ComponentStorage>>,
+}
+
+pub trait ComponentStorage {
+    fn remove_entity(&mut self, entity: usize);
+    fn clear(&mut self);
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl World {
+    pub fn spawn(&mut self) -> usize {
+        self.entities.store(())
+    }
+
+    pub fn spawn_with_name(&mut self, name: &str) -> Result<usize> {
+        self.entities.store_with_name(name, ())
+    }
+
+    pub fn despawn(&mut self, entity: usize) {
+        if self.entities.remove(entity).is_some() {
+            for storage in self.components.values_mut() {
+                storage.remove_entity(entity);
+            }
+        }
+    }
+
+    pub fn is_alive(&self, entity: usize) -> bool {
+        self.entities.get(entity).is_ok()
+    }
+
+    pub fn get_entity_id(&self, name: &str) -> Result<usize> {
+        self.entities.get_id(name)
+    }
+
+    pub fn get_entities(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entities.iter_enumerate().map(|(id, _)| id)
+    }
+
+    pub fn insert<T>(&mut self, entity: usize, component: T) -> Result<()>
+    where
+        T: 'static,
+    {
+        if !self.is_alive(entity) {
+            return Err(anyhow!("Entity {} not found", entity));
+        }
+
+        self.get_storage_mut::<T>().store_with_id(entity, component);
+        Ok(())
+    }
+
+    pub fn remove<T>(&mut self, entity: usize) -> Option<T>
+    where
+        T: 'static,
+    {
+        self.get_storage_mut::<T>().remove(entity)
+    }
+
+    pub fn has<T>(&self, entity: usize) -> bool
+    where
+        T: 'static,
+    {
+        self.get_storage::<T>().map(|p| p.get(entity).is_ok()).unwrap_or(false)
+    }
+
+    pub fn get<T>(&self, entity: usize) -> Result<&T>
+    where
+        T: 'static,
+    {
+        let storage = self.get_storage::<T>().ok_or_else(|| anyhow!("Component {} not registered", type_name::<T>()))?;
+        storage.get(entity)
+    }
+
+    pub fn get_mut<T>(&mut self, entity: usize) -> Result<&mut T>
+    where
+        T: 'static,
+    {
+        self.get_storage_mut::<T>().get_mut(entity)
+    }
+
+    pub fn get_storage<T>(&self) -> Option<&Storage<T>>
+    where
+        T: 'static,
+    {
+        self.components.get(&TypeId::of::<T>()).and_then(|p| p.as_any().downcast_ref::<Storage<T>>())
+    }
+
+    pub fn get_storage_mut<T>(&mut self) -> &mut Storage<T>
+    where
+        T: 'static,
+    {
+        let storage = self.components.entry(TypeId::of::<T>()).or_insert_with(|| Box::<Storage<T>>::default());
+        storage.as_any_mut().downcast_mut::<Storage<T>>().expect("Component storage is keyed by a different type")
+    }
+
+    pub fn iter<T>(&self) -> impl Iterator<Item = (usize, &T)>
+    where
+        T: 'static,
+    {
+        self.get_storage::<T>().into_iter().flat_map(|p| p.iter_enumerate())
+    }
+
+    pub fn iter_mut<T>(&mut self) -> impl Iterator<Item = (usize, &mut T)>
+    where
+        T: 'static,
+    {
+        self.get_storage_mut::<T>().iter_enumerate_mut()
+    }
+
+    pub fn query<Q>(&self) -> Vec<usize>
+    where
+        Q: Query,
+    {
+        Q::get_entities(self)
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+
+        for storage in self.components.values_mut() {
+            storage.clear();
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self { pixels_per_meter: 1.0, entities: Storage::default(), components: FxHashMap::default() }
+    }
+}
+
+impl<T> ComponentStorage for Storage<T>
+where
+    T: 'static,
+{
+    fn remove_entity(&mut self, entity: usize) {
+        self.remove(entity);
+    }
+
+    fn clear(&mut self) {
+        Storage::clear(self);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -12,13 +12,17 @@
     clippy::get_first
 )]
 
+pub mod animation;
 pub mod app;
 pub mod assets;
+pub mod clock;
+pub mod ecs;
 pub mod filesystem;
 pub mod light;
 pub mod particles;
 pub mod renderer;
 pub mod scene;
+pub mod transition;
 pub mod ui;
 pub mod utils;
 pub mod window;
@@ -35,6 +39,9 @@ pub mod powder;
 #[cfg(feature = "network")]
 pub mod network;
 
+#[cfg(test)]
+mod tests;
+
 pub use anyhow;
 pub use egui;
 pub use egui_plot;
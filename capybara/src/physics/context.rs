@@ -1,4 +1,9 @@
 use super::events::EventCollector;
+use super::hooks::OneWayPlatformHooks;
+use super::hooks::ONE_WAY_PLATFORM_USER_DATA;
+use super::tiles::TileColliderSettings;
+use super::tiles::TileColliderShape;
+use super::tiles::TileGrid;
 use glam::Vec2;
 use glam::Vec4;
 use rapier2d::na::Vector2;
@@ -64,7 +69,7 @@ impl PhysicsContext {
             impulse_joints: ImpulseJointSet::default(),
             multibody_joints: MultibodyJointSet::default(),
             solver: CCDSolver::default(),
-            hooks: Box::new(()),
+            hooks: Box::new(OneWayPlatformHooks),
             events: EventCollector::default(),
             running: true,
 
@@ -146,6 +151,44 @@ impl PhysicsContext {
         }
     }
 
+    pub fn insert_tile_colliders(&mut self, grid: &TileGrid, settings: &TileColliderSettings) -> Vec<ColliderHandle> {
+        let mut builders = Vec::default();
+        let pixels_per_meter = settings.pixels_per_meter;
+
+        match settings.shape {
+            TileColliderShape::Rectangles => {
+                for (position, size) in grid.get_rectangles() {
+                    let center = (position + size / 2.0) / pixels_per_meter;
+                    let half_size = size / 2.0 / pixels_per_meter;
+
+                    builders.push(ColliderBuilder::cuboid(half_size.x, half_size.y).translation(vector![center.x, center.y]));
+                }
+            }
+            TileColliderShape::Polylines => {
+                for polyline in grid.get_polylines() {
+                    let points = polyline.iter().map(|p| point![p.x / pixels_per_meter, p.y / pixels_per_meter]).collect::<Vec<_>>();
+                    let indices = (0..points.len() as u32).map(|p| [p, (p + 1) % points.len() as u32]).collect();
+
+                    builders.push(ColliderBuilder::polyline(points, Some(indices)));
+                }
+            }
+        }
+
+        let (active_hooks, user_data) = if settings.one_way {
+            (ActiveHooks::MODIFY_SOLVER_CONTACTS, settings.user_data | ONE_WAY_PLATFORM_USER_DATA)
+        } else {
+            (ActiveHooks::empty(), settings.user_data)
+        };
+
+        builders
+            .into_iter()
+            .map(|p| {
+                let collider = p.friction(settings.friction).restitution(settings.restitution).user_data(user_data).active_hooks(active_hooks);
+                self.colliders.insert(collider)
+            })
+            .collect()
+    }
+
     pub fn get_collision_pair(
         &self,
         handle1: ColliderHandle,
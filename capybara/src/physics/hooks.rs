@@ -0,0 +1,26 @@
+use rapier2d::prelude::*;
+
+pub const ONE_WAY_PLATFORM_ALLOWED_ANGLE: f32 = 0.1;
+
+// Highest bit of the collider user data is reserved for platforms, other hooks can still enable MODIFY_SOLVER_CONTACTS for their colliders
+pub const ONE_WAY_PLATFORM_USER_DATA: u128 = 1 << 127;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OneWayPlatformHooks;
+
+impl PhysicsHooks for OneWayPlatformHooks {
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+        let is_platform = |handle| context.colliders.get(handle).map(|p| (p.user_data & ONE_WAY_PLATFORM_USER_DATA) != 0);
+
+        // Platforms accept contacts only from above, so the allowed normal depends on which collider of the pair is the platform
+        let allowed_local_n1 = if is_platform(context.collider1) == Some(true) {
+            Vector::y()
+        } else if is_platform(context.collider2) == Some(true) {
+            -Vector::y()
+        } else {
+            return;
+        };
+
+        context.update_as_oneway_platform(&allowed_local_n1, ONE_WAY_PLATFORM_ALLOWED_ANGLE);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod context;
 pub mod debug;
 pub mod events;
+pub mod hooks;
+pub mod tiles;
@@ -0,0 +1,199 @@
+use crate::assets::ldtk::LdtkLayer;
+use crate::assets::ldtk::LdtkTilemap;
+use glam::IVec2;
+use glam::Vec2;
+use rustc_hash::FxHashMap;
+
+#[derive(Clone, Debug, Default)]
+pub struct TileGrid {
+    pub origin: Vec2,
+    pub cell_size: Vec2,
+    pub columns: usize,
+    pub rows: usize,
+    pub cells: Vec<bool>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TileColliderSettings {
+    pub pixels_per_meter: f32,
+    pub shape: TileColliderShape,
+    pub one_way: bool,
+    pub friction: f32,
+    pub restitution: f32,
+    pub user_data: u128,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum TileColliderShape {
+    #[default]
+    Rectangles,
+    Polylines,
+}
+
+impl TileGrid {
+    pub fn new(origin: Vec2, cell_size: Vec2, columns: usize, rows: usize) -> Self {
+        Self { origin, cell_size, columns, rows, cells: vec![false; columns * rows] }
+    }
+
+    pub fn new_from_ldtk_int_grid(layer: &LdtkLayer, values: &[i32]) -> Self {
+        let mut grid = Self::new(layer.offset, layer.grid_size, layer.columns, layer.rows);
+
+        for y in 0..layer.rows {
+            for x in 0..layer.columns {
+                let value = layer.get_int_grid_value(x, y);
+                let solid = if values.is_empty() { value != 0 } else { values.contains(&value) };
+
+                grid.set(x, y, solid);
+            }
+        }
+
+        grid
+    }
+
+    pub fn new_from_ldtk_tiles(layer: &LdtkLayer, tilemap: &LdtkTilemap, tag: &str) -> Self {
+        let mut grid = Self::new(layer.offset, layer.grid_size, layer.columns, layer.rows);
+
+        for tile in &layer.tiles {
+            let custom = match tilemap.custom.get(&tile.id) {
+                Some(custom) => custom,
+                None => continue,
+            };
+
+            if custom.split(|p: char| p.is_whitespace() || p == ',' || p == ';').any(|p| p == tag) {
+                let cell = (tile.position / layer.grid_size).floor();
+                if cell.x >= 0.0 && cell.y >= 0.0 {
+                    grid.set(cell.x as usize, cell.y as usize, true);
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        if x >= self.columns || y >= self.rows {
+            return false;
+        }
+
+        self.cells[y * self.columns + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, solid: bool) {
+        if x < self.columns && y < self.rows {
+            self.cells[y * self.columns + x] = solid;
+        }
+    }
+
+    pub fn get_rectangles(&self) -> Vec<(Vec2, Vec2)> {
+        let mut used = vec![false; self.cells.len()];
+        let mut rectangles = Vec::default();
+        let free = |used: &[bool], x: usize, y: usize| self.get(x, y) && !used[y * self.columns + x];
+
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                if !free(&used, x, y) {
+                    continue;
+                }
+
+                let mut width = 1;
+                while x + width < self.columns && free(&used, x + width, y) {
+                    width += 1;
+                }
+
+                let mut height = 1;
+                while y + height < self.rows && (x..x + width).all(|p| free(&used, p, y + height)) {
+                    height += 1;
+                }
+
+                for cell_y in y..y + height {
+                    for cell_x in x..x + width {
+                        used[cell_y * self.columns + cell_x] = true;
+                    }
+                }
+
+                let position = self.origin + Vec2::new(x as f32, y as f32) * self.cell_size;
+                let size = Vec2::new(width as f32, height as f32) * self.cell_size;
+                rectangles.push((position, size));
+            }
+        }
+
+        rectangles
+    }
+
+    pub fn get_polylines(&self) -> Vec<Vec<Vec2>> {
+        let mut edges = FxHashMap::<IVec2, Vec<IVec2>>::default();
+        let solid = |x: i32, y: i32| x >= 0 && y >= 0 && self.get(x as usize, y as usize);
+
+        // Edges are oriented so the solid cell is always on the left, which makes every outline counter-clockwise
+        for y in 0..self.rows as i32 {
+            for x in 0..self.columns as i32 {
+                if !solid(x, y) {
+                    continue;
+                }
+
+                if !solid(x, y - 1) {
+                    edges.entry(IVec2::new(x, y)).or_default().push(IVec2::X);
+                }
+                if !solid(x + 1, y) {
+                    edges.entry(IVec2::new(x + 1, y)).or_default().push(IVec2::Y);
+                }
+                if !solid(x, y + 1) {
+                    edges.entry(IVec2::new(x + 1, y + 1)).or_default().push(IVec2::NEG_X);
+                }
+                if !solid(x - 1, y) {
+                    edges.entry(IVec2::new(x, y + 1)).or_default().push(IVec2::NEG_Y);
+                }
+            }
+        }
+
+        let mut starts = edges.keys().copied().collect::<Vec<IVec2>>();
+        starts.sort_by_key(|p| (p.y, p.x));
+
+        let mut polylines = Vec::default();
+        for start in starts {
+            while let Some(mut direction) = edges.get_mut(&start).and_then(|p| p.pop()) {
+                let mut points = vec![start];
+                let mut current = start + direction;
+
+                while current != start {
+                    let outgoing = match edges.get_mut(&current) {
+                        Some(outgoing) if !outgoing.is_empty() => outgoing,
+                        _ => break,
+                    };
+
+                    // Turning left first keeps cells touching only by a corner in separate outlines
+                    let left = direction.perp();
+                    let next = [left, direction, -left].into_iter().find_map(|p| outgoing.iter().position(|q| *q == p)).unwrap_or(0);
+                    let next = outgoing.swap_remove(next);
+
+                    if next != direction {
+                        points.push(current);
+                    }
+
+                    direction = next;
+                    current += direction;
+                }
+
+                if points.len() > 2 && (points[1] - points[0]).perp_dot(points[0] - *points.last().unwrap()) == 0 {
+                    points.remove(0);
+                }
+
+                polylines.push(points.iter().map(|p| self.origin + p.as_vec2() * self.cell_size).collect());
+            }
+        }
+
+        polylines
+    }
+}
+
+impl TileColliderSettings {
+    pub fn new(pixels_per_meter: f32, shape: TileColliderShape) -> Self {
+        Self { pixels_per_meter, shape, ..Default::default() }
+    }
+}
+
+impl Default for TileColliderSettings {
+    fn default() -> Self {
+        Self { pixels_per_meter: 1.0, shape: TileColliderShape::Rectangles, one_way: false, friction: 0.5, restitution: 0.0, user_data: 0 }
+    }
+}
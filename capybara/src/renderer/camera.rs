@@ -8,6 +8,10 @@ pub struct Camera {
     pub size: Vec2,
     pub origin: CameraOrigin,
     pub autofit: bool,
+    pub zoom: f32,
+    pub rotation: f32,
+    pub viewport_position: Vec2,
+    pub viewport_size: Vec2,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -16,9 +20,37 @@ pub enum CameraOrigin {
     LeftBottom,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraController {
+    pub target: Vec2,
+    pub rotation: f32,
+    pub smoothing: f32,
+    pub dead_zone: Vec2,
+    pub bounds: Option<(Vec2, Vec2)>,
+
+    pub trauma: f32,
+    pub trauma_decay: f32,
+    pub shake_offset: Vec2,
+    pub shake_rotation: f32,
+    pub shake_frequency: f32,
+
+    center: Vec2,
+    time: f32,
+}
+
 impl Camera {
     pub fn new(position: Vec2, size: Vec2, origin: CameraOrigin, autofit: bool) -> Self {
-        Self { position, size, origin, autofit }
+        Self { position, size, origin, autofit, zoom: 1.0, rotation: 0.0, viewport_position: Vec2::ZERO, viewport_size: Vec2::ONE }
+    }
+
+    pub fn with_viewport(mut self, position: Vec2, size: Vec2) -> Self {
+        self.viewport_position = position;
+        self.viewport_size = size;
+        self
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.viewport_position == Vec2::ZERO && self.viewport_size == Vec2::ONE
     }
 
     pub fn get_center_position(&self) -> Vec2 {
@@ -29,6 +61,19 @@ impl Camera {
         self.position = position - self.size / 2.0;
     }
 
+    pub fn get_visible_size(&self) -> Vec2 {
+        self.size / self.zoom
+    }
+
+    pub fn get_bounds(&self) -> (Vec2, Vec2) {
+        let half_size = self.get_visible_size() / 2.0;
+        let rotation = Vec2::from_angle(self.rotation);
+        let extent = rotation.rotate(half_size).abs().max(rotation.rotate(Vec2::new(half_size.x, -half_size.y)).abs());
+        let center = self.get_center_position();
+
+        (center - extent, center + extent)
+    }
+
     pub fn get_projection_matrix(&self) -> Mat4 {
         match self.origin {
             CameraOrigin::LeftTop => Mat4::orthographic_rh(0.0, self.size.x, self.size.y, 0.0, 0.1, 100.0),
@@ -37,23 +82,34 @@ impl Camera {
     }
 
     pub fn get_view_matrix(&self) -> Mat4 {
-        Mat4::from_translation(Vec3::new(-self.position.x, -self.position.y, -1.0))
+        // Zoom and rotation are applied around the center of the camera, so the same point stays in the middle of the screen
+        Mat4::from_translation((self.size / 2.0).extend(-1.0))
+            * Mat4::from_scale(Vec3::new(self.zoom, self.zoom, 1.0))
+            * Mat4::from_rotation_z(-self.rotation)
+            * Mat4::from_translation((-self.get_center_position()).extend(0.0))
     }
 
     pub fn from_window_to_screen_coordinates(&self, position: Vec2) -> Vec2 {
-        Vec2::new(position.x, self.size.y - position.y)
+        // Viewport is normalized and starts at the left-bottom corner of the target, same as in OpenGL
+        let target_size = self.size / self.viewport_size;
+        Vec2::new(position.x, target_size.y - position.y) - self.viewport_position * target_size
     }
 
     pub fn from_window_to_world_coordinates(&self, position: Vec2) -> Vec2 {
-        Vec2::new(position.x, self.size.y - position.y) + self.position
+        let screen = self.from_window_to_screen_coordinates(position);
+        self.get_center_position() + Vec2::from_angle(self.rotation).rotate((screen - self.size / 2.0) / self.zoom)
     }
 
     pub fn from_screen_to_window_coordinates(&self, position: Vec2) -> Vec2 {
-        Vec2::new(position.x, self.size.y - position.y)
+        let target_size = self.size / self.viewport_size;
+        let position = position + self.viewport_position * target_size;
+
+        Vec2::new(position.x, target_size.y - position.y)
     }
 
     pub fn from_world_to_window_coordinates(&self, position: Vec2) -> Vec2 {
-        Vec2::new(position.x, self.size.y - position.y) - self.position
+        let screen = self.size / 2.0 + Vec2::from_angle(-self.rotation).rotate(position - self.get_center_position()) * self.zoom;
+        self.from_screen_to_window_coordinates(screen)
     }
 }
 
@@ -62,3 +118,100 @@ impl Default for Camera {
         Self::new(Vec2::ZERO, Vec2::ZERO, CameraOrigin::LeftBottom, true)
     }
 }
+
+impl CameraController {
+    pub fn new(target: Vec2) -> Self {
+        Self {
+            target,
+            rotation: 0.0,
+            smoothing: 0.0,
+            dead_zone: Vec2::ZERO,
+            bounds: None,
+
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            shake_offset: Vec2::new(16.0, 16.0),
+            shake_rotation: 0.05,
+            shake_frequency: 20.0,
+
+            center: target,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn with_dead_zone(mut self, dead_zone: Vec2) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    pub fn with_bounds(mut self, min: Vec2, max: Vec2) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    pub fn with_shake(mut self, offset: Vec2, rotation: f32, frequency: f32, decay: f32) -> Self {
+        self.shake_offset = offset;
+        self.shake_rotation = rotation;
+        self.shake_frequency = frequency;
+        self.trauma_decay = decay;
+        self
+    }
+
+    pub fn get_center_position(&self) -> Vec2 {
+        self.center
+    }
+
+    pub fn snap(&mut self, target: Vec2) {
+        self.target = target;
+        self.center = target;
+    }
+
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, delta: f32) {
+        let offset = self.target - self.center;
+        let desired = Vec2::new(
+            if offset.x.abs() > self.dead_zone.x { self.target.x - self.dead_zone.x * offset.x.signum() } else { self.center.x },
+            if offset.y.abs() > self.dead_zone.y { self.target.y - self.dead_zone.y * offset.y.signum() } else { self.center.y },
+        );
+
+        // Exponential smoothing doesn't depend on the frame rate, unlike the plain lerp with a constant factor
+        self.center = if self.smoothing > 0.0 { self.center + (desired - self.center) * (1.0 - (-self.smoothing * delta).exp()) } else { desired };
+
+        if let Some((min, max)) = self.bounds {
+            let half_size = camera.get_visible_size() / 2.0;
+            let clamp = |value: f32, min: f32, max: f32, half_size: f32| {
+                if max - min < half_size * 2.0 {
+                    (min + max) / 2.0
+                } else {
+                    value.clamp(min + half_size, max - half_size)
+                }
+            };
+
+            self.center = Vec2::new(clamp(self.center.x, min.x, max.x, half_size.x), clamp(self.center.y, min.y, max.y, half_size.y));
+        }
+
+        self.time += delta;
+        self.trauma = (self.trauma - self.trauma_decay * delta).max(0.0);
+
+        // Shake grows with the square of trauma, so small hits are barely visible and big ones feel strong
+        let shake = self.trauma * self.trauma;
+        let offset = Vec2::new(self.get_noise(0.0), self.get_noise(17.0)) * self.shake_offset * shake;
+        let rotation = self.get_noise(43.0) * self.shake_rotation * shake;
+
+        camera.set_center_position(self.center + offset);
+        camera.rotation = self.rotation + rotation;
+    }
+
+    fn get_noise(&self, seed: f32) -> f32 {
+        let t = self.time * self.shake_frequency + seed;
+        ((t.sin() + (t * 2.17 + 1.3).sin() * 0.5 + (t * 4.31 + 2.9).sin() * 0.25) / 1.75).clamp(-1.0, 1.0)
+    }
+}
@@ -1,5 +1,7 @@
 use super::camera::Camera;
 use super::camera::CameraOrigin;
+use super::font::Font;
+use super::font::FontKind;
 use super::shader::Shader;
 use super::shader::*;
 use super::shape::Shape;
@@ -8,11 +10,17 @@ use super::sprite::Sprite;
 use super::sprite::SpriteVertex;
 use super::sprite::TextureId;
 use super::sprite::TextureType;
+use super::text::Text;
 use super::texture::AtlasEntity;
 use super::texture::Texture;
 use super::texture::TextureKind;
+use super::tilemap::Tilemap;
 use crate::assets::loader::AssetsLoader;
+use crate::assets::manifest::AssetsReferences;
+use crate::assets::vfs;
+use crate::assets::RawShaderKind;
 use crate::assets::RawTexture;
+use crate::assets::RawTextureFormat;
 use crate::error_continue;
 use crate::error_return;
 use crate::renderer::texture::TextureFilterMag;
@@ -21,6 +29,8 @@ use crate::utils::color::Vec4Utils;
 use crate::utils::storage::Storage;
 use anyhow::Error;
 use anyhow::Result;
+use arrayvec::ArrayVec;
+use glam::Mat4;
 use glam::Vec2;
 use glam::Vec4;
 use glow::Buffer;
@@ -34,7 +44,9 @@ use log::error;
 use log::info;
 use rustc_hash::FxHashMap;
 use std::cmp;
+use std::f32::consts;
 use std::mem;
+use std::ops::Range;
 use std::path::Path;
 use std::ptr;
 use std::rc::Rc;
@@ -42,10 +54,12 @@ use std::slice;
 
 pub struct RendererContext {
     pub viewport_size: Vec2,
+    pub window_size: Vec2,
 
     pub default_camera_id: usize,
     pub default_sprite_shader_id: usize,
     pub default_shape_shader_id: usize,
+    pub default_text_shader_id: usize,
     pub default_texture_id: usize,
 
     pub active_camera_id: usize,
@@ -56,10 +70,22 @@ pub struct RendererContext {
     pub cameras: Storage<Camera>,
     pub shaders: Storage<Shader>,
     pub textures: Storage<Texture>,
+    pub fonts: Storage<Font>,
+    pub references: AssetsReferences,
     pub gl: Rc<Context>,
 
     active_camera_data: Camera,
     buffer_metadata: Option<BufferMetadata>,
+    text_outline_color: Vec4,
+    text_outline_threshold: f32,
+    clear_color: Vec4,
+    virtual_resolution: Option<VirtualResolution>,
+    viewport_scissor: bool,
+
+    sorting: SortingMode,
+    sorted_items: Vec<SortedItem>,
+    sorted_shape_vertices: Vec<ShapeVertex>,
+    sorted_shape_indices: Vec<u32>,
 
     framebuffer: Framebuffer,
     framebuffer_texture_id: Option<usize>,
@@ -97,15 +123,35 @@ pub struct RendererContext {
 #[derive(Debug)]
 pub struct BufferMetadata {
     pub content_type: BufferContentType,
-    pub texture_id: TextureId,
+    pub textures: ArrayVec<TextureId, SPRITE_TEXTURE_UNITS>,
+    pub texture_units: usize,
     pub framebuffer_texture_id: Option<usize>,
     pub selected_shader_id: usize,
 }
 
+#[derive(Clone, Debug)]
+pub struct SortedItem {
+    pub layer: i32,
+    pub z: f32,
+    pub y: f32,
+    pub texture_id: TextureId,
+    pub shader_id: usize,
+    pub data: SortedItemData,
+}
+
+#[derive(Clone, Debug)]
+pub enum SortedItemData {
+    Sprite(SpriteVertex),
+    Text(SpriteVertex, Vec4, f32),
+    Shape(Range<usize>, Range<usize>),
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 pub struct RendererStatistics {
     pub draw_calls: usize,
     pub triangles: usize,
+    pub culled_sprites: usize,
+    pub culled_shapes: usize,
     pub sprite_buffer_size: usize,
     pub shape_buffer_size: usize,
     pub loaded_textures_count: usize,
@@ -116,6 +162,33 @@ pub struct RendererStatistics {
 pub enum BufferContentType {
     Sprite,
     Shape,
+    Text,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualResolution {
+    pub size: Vec2,
+    pub scaling: VirtualScaling,
+    pub letterbox_color: Vec4,
+    pub texture_id: usize,
+    pub camera_id: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VirtualScaling {
+    Integer,
+    Aspect,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum SortingMode {
+    #[default]
+    None,
+    Layer,
+    LayerY,
+    /// Like `Layer`, but items with equal layer and z are grouped by shader and texture to reduce draw calls.
+    /// Their submission order is lost, so it's only correct when such items don't overlap.
+    LayerBatched,
 }
 
 impl RendererContext {
@@ -135,10 +208,12 @@ impl RendererContext {
 
             let mut context = Self {
                 viewport_size: Vec2::ZERO,
+                window_size: Vec2::ZERO,
 
                 default_camera_id: usize::MAX,
                 default_sprite_shader_id: usize::MAX,
                 default_shape_shader_id: usize::MAX,
+                default_text_shader_id: usize::MAX,
                 default_texture_id: usize::MAX,
 
                 active_camera_id: usize::MAX,
@@ -149,10 +224,22 @@ impl RendererContext {
                 cameras: Storage::default(),
                 shaders: Storage::default(),
                 textures: Storage::default(),
+                fonts: Storage::default(),
+                references: AssetsReferences::default(),
                 gl: Rc::new(gl),
 
                 active_camera_data: Camera::default(),
                 buffer_metadata: None,
+                text_outline_color: Vec4::ZERO,
+                text_outline_threshold: 0.5,
+                clear_color: Vec4::ZERO,
+                virtual_resolution: None,
+                viewport_scissor: false,
+
+                sorting: SortingMode::None,
+                sorted_items: Vec::default(),
+                sorted_shape_vertices: Vec::default(),
+                sorted_shape_indices: Vec::default(),
 
                 framebuffer,
                 framebuffer_texture_id: None,
@@ -203,6 +290,9 @@ impl RendererContext {
             context.default_shape_shader_id = context.shaders.store(shape_shader);
             context.active_shape_shader_id = context.default_shape_shader_id;
 
+            let text_shader = Shader::new(&context, "text_default", SPRITE_VERTEX_SHADER, TEXT_FRAGMENT_SHADER)?;
+            context.default_text_shader_id = context.shaders.store(text_shader);
+
             let default_texture = Texture::new(&context, &RawTexture::new("blank", "", Vec2::new(1.0, 1.0), &[255, 255, 255, 255]))?;
             context.default_texture_id = context.textures.store(default_texture);
 
@@ -228,26 +318,7 @@ impl RendererContext {
             let indices_u8 = slice::from_raw_parts([0, 1, 2, 0, 2, 3].as_ptr() as *const u8, 6 * 4);
             context.gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, indices_u8, glow::STATIC_DRAW);
 
-            context.gl.enable_vertex_attrib_array(0);
-            context.gl.enable_vertex_attrib_array(1);
-            context.gl.enable_vertex_attrib_array(2);
-            context.gl.enable_vertex_attrib_array(3);
-            context.gl.enable_vertex_attrib_array(4);
-            context.gl.enable_vertex_attrib_array(5);
-
-            context.gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 12 * 4, 0);
-            context.gl.vertex_attrib_pointer_f32(1, 2, glow::FLOAT, false, 12 * 4, 2 * 4);
-            context.gl.vertex_attrib_pointer_f32(2, 1, glow::FLOAT, false, 12 * 4, 4 * 4);
-            context.gl.vertex_attrib_pointer_f32(3, 2, glow::FLOAT, false, 12 * 4, 5 * 4);
-            context.gl.vertex_attrib_pointer_i32(4, 4, glow::UNSIGNED_BYTE, 12 * 4, 7 * 4);
-            context.gl.vertex_attrib_pointer_f32(5, 4, glow::FLOAT, false, 12 * 4, 8 * 4);
-
-            context.gl.vertex_attrib_divisor(0, 1);
-            context.gl.vertex_attrib_divisor(1, 1);
-            context.gl.vertex_attrib_divisor(2, 1);
-            context.gl.vertex_attrib_divisor(3, 1);
-            context.gl.vertex_attrib_divisor(4, 1);
-            context.gl.vertex_attrib_divisor(5, 1);
+            Self::set_sprite_vertex_attributes(&context.gl);
 
             // UI buffers
             context.gl.bind_vertex_array(Some(context.shape_buffer_vao));
@@ -268,35 +339,134 @@ impl RendererContext {
 
     pub fn instantiate_assets(&mut self, assets: &AssetsLoader, prefix: Option<&str>) {
         info!("Instancing renderer assets, prefix {}", prefix.unwrap_or("none"));
+        self.instantiate_filtered(assets, |path| prefix.map(|p| path.starts_with(p)).unwrap_or(true), false);
+    }
 
-        for raw in &assets.raw_textures {
-            if let Some(prefix) = &prefix {
-                if !raw.path.starts_with(prefix) {
-                    continue;
+    pub fn instantiate_group(&mut self, assets: &AssetsLoader, group: &str) -> Result<()> {
+        info!("Instancing renderer assets, group {}", group);
+
+        let paths = assets.get_group_paths(group)?;
+        let acquired = paths.into_iter().filter(|p| self.references.acquire(p)).collect::<Vec<String>>();
+        self.instantiate_filtered(assets, |path| acquired.iter().any(|p| p == path), false);
+
+        Ok(())
+    }
+
+    pub fn reload_assets(&mut self, assets: &AssetsLoader, paths: &[String]) {
+        info!("Reloading renderer assets, {} files", paths.len());
+        self.instantiate_filtered(assets, |path| paths.iter().any(|p| p == path), true);
+    }
+
+    pub fn unload_group(&mut self, assets: &AssetsLoader, group: &str) -> Result<()> {
+        info!("Unloading renderer assets, group {}", group);
+
+        for path in assets.get_group_paths(group)? {
+            if !self.references.release(&path) {
+                continue;
+            }
+
+            if let Ok(id) = self.fonts.get_id(&path) {
+                self.fonts.remove(id);
+            }
+
+            if let Some(texture) = self.textures.get_id(&path).ok().and_then(|p| self.textures.remove(p)) {
+                unsafe {
+                    self.gl.delete_texture(texture.inner);
                 }
             }
+        }
+
+        Ok(())
+    }
+
+    fn instantiate_filtered(&mut self, assets: &AssetsLoader, filter: impl Fn(&str) -> bool, reload: bool) {
+        for raw in &assets.raw_textures {
+            if !filter(&raw.path) {
+                continue;
+            }
+
+            if !reload && self.textures.contains_by_name(&raw.path) {
+                error_continue!("Failed to instantiate texture {} (name already exists)", raw.path);
+            }
 
             let mut texture = match Texture::new(self, raw) {
                 Ok(texture) => texture,
                 Err(err) => error_continue!("Failed to load texture {} ({})", raw.name, err),
             };
 
-            texture.set_filters(TextureFilterMin::LinearMipmap, TextureFilterMag::Linear);
+            // Reloaded texture keeps settings of the one it replaces, they could be changed by the game after instancing
+            match self.textures.get_by_name(&raw.path) {
+                Ok(existing) if reload => {
+                    texture.set_filters(existing.filtering_min, existing.filtering_mag);
+                    texture.set_wrap_mode(existing.wrap_mode);
+                }
+                _ => texture.set_filters(TextureFilterMin::LinearMipmap, TextureFilterMag::Linear),
+            }
+
+            let (id, replaced) = self.textures.store_or_replace_with_name(&raw.path, texture);
+            if let Some(replaced) = replaced {
+                unsafe {
+                    self.gl.delete_texture(replaced.inner);
+                }
+            }
 
-            if let Err(err) = self.textures.store_with_name(&raw.name, texture) {
-                error!("Failed to instantiate texture {} ({})", raw.name, err);
+            if !self.textures.contains_by_name(&raw.name) {
+                let _ = self.textures.add_alias(id, &raw.name);
+            } else if self.textures.get_id(&raw.name).ok() != Some(id) {
+                info!("Texture name {} is ambiguous, {} available only by path", raw.name, raw.path);
             }
         }
 
-        for raw in &assets.raw_atlases {
-            let path = Path::new(&raw.name);
-            let name = match path.file_stem() {
-                Some(name) => name,
-                None => error_continue!("Failed to get filename stem for atlas {}", raw.name),
+        for raw in &assets.raw_shaders {
+            if raw.kind != RawShaderKind::Vertex {
+                continue;
+            }
+
+            let path = raw.path.trim_end_matches(".vert");
+            let fragment_path = format!("{}.frag", path);
+            let fragment = match assets.raw_shaders.iter().find(|p| p.kind == RawShaderKind::Fragment && p.path == fragment_path) {
+                Some(fragment) => fragment,
+                None => error_continue!("Fragment shader {} not found", fragment_path),
+            };
+
+            if !filter(&raw.path) && !filter(&fragment.path) {
+                continue;
+            }
+
+            if !reload && self.shaders.contains_by_name(path) {
+                error_continue!("Failed to instantiate shader {} (name already exists)", path);
+            }
+
+            let shader = match Shader::new(self, &raw.name, &raw.source, &fragment.source) {
+                Ok(shader) => shader,
+                Err(err) => error_continue!("Failed to load shader {} ({})", raw.name, err),
             };
-            let name_str = match name.to_str() {
-                Some(name) => name,
-                None => error_continue!("Failed to get filename string for atlas {}", raw.name),
+
+            let (id, replaced) = self.shaders.store_or_replace_with_name(path, shader);
+            if let Some(replaced) = replaced {
+                unsafe {
+                    self.gl.delete_program(replaced.program);
+                }
+            }
+
+            if !self.shaders.contains_by_name(&raw.name) {
+                let _ = self.shaders.add_alias(id, &raw.name);
+            }
+
+            // Replaced program has to be activated again even if its id hasn't changed
+            self.selected_shader_id = usize::MAX;
+        }
+
+        for raw in &assets.raw_atlases {
+            // Image is referenced relatively to the atlas file, the file stem is kept as a fallback for flat archives
+            let image_path = vfs::resolve_path(&raw.path, &raw.image);
+            let name_str = if self.textures.contains_by_name(&image_path) {
+                image_path.as_str()
+            } else {
+                match Path::new(&raw.name).file_stem().and_then(|p| p.to_str()) {
+                    Some(name) => name,
+                    None => error_continue!("Failed to get filename stem for atlas {}", raw.name),
+                }
             };
 
             if self.textures.contains_by_name(name_str) {
@@ -313,6 +483,26 @@ impl RendererContext {
                 texture.kind = TextureKind::Atlas(entities);
             }
         }
+
+        for raw in &assets.bitmap_fonts {
+            if !filter(&raw.path) {
+                continue;
+            }
+
+            if !reload && self.fonts.contains_by_name(&raw.path) {
+                error_continue!("Failed to instantiate font {} (name already exists)", raw.path);
+            }
+
+            let font = match Font::new_from_bitmap(self, raw) {
+                Ok(font) => font,
+                Err(err) => error_continue!("Failed to load font {} ({})", raw.name, err),
+            };
+
+            let (id, _) = self.fonts.store_or_replace_with_name(&raw.path, font);
+            if !self.fonts.contains_by_name(&raw.name) {
+                let _ = self.fonts.add_alias(id, &raw.name);
+            }
+        }
     }
 
     pub fn clear(&self) {
@@ -322,15 +512,21 @@ impl RendererContext {
     }
 
     pub fn begin_frame(&mut self) {
-        self.clear();
+        if self.virtual_resolution.is_some() {
+            self.set_target_texture(None);
+        }
 
+        // Camera goes first, so the scissor left by a split-screen camera doesn't limit clearing
         if self.active_camera_id != self.default_camera_id {
             self.set_camera(self.default_camera_id);
         }
+
+        self.clear();
     }
 
     pub fn end_frame(&mut self) {
         self.flush_buffer();
+        self.present_virtual_resolution();
 
         unsafe {
             self.gl.flush();
@@ -400,41 +596,14 @@ impl RendererContext {
             sprite.size.unwrap_or(Vec2::new(1.0, 1.0))
         };
 
-        let camera_min = camera.position;
-        let camera_max = camera.position + camera.size;
-
-        let radius = sprite.anchor.length() + 2.0;
-        let sprite_min = sprite.position - radius * sprite_size.max_element() * sprite.scale.max_element();
-        let sprite_max = sprite.position + radius * sprite_size.max_element() * sprite.scale.max_element();
+        let (camera_min, camera_max) = camera.get_bounds();
+        let (sprite_min, sprite_max) = sprite.get_bounds(sprite_size);
 
         if sprite_min.x > camera_max.x || sprite_min.y > camera_max.y || sprite_max.x < camera_min.x || sprite_max.y < camera_min.y {
+            self.statistics_current.culled_sprites += 1;
             return;
         }
 
-        if let Some(buffer_metadata) = &self.buffer_metadata {
-            if buffer_metadata.content_type != BufferContentType::Sprite || buffer_metadata.texture_id != sprite.texture_id {
-                self.flush_buffer();
-                self.buffer_metadata = Some(BufferMetadata::new(
-                    BufferContentType::Sprite, // fmt
-                    sprite.texture_id,
-                    self.framebuffer_texture_id,
-                    self.selected_shader_id,
-                ));
-            }
-        } else {
-            self.buffer_metadata = Some(BufferMetadata::new(
-                BufferContentType::Sprite, // fmt
-                sprite.texture_id,
-                self.framebuffer_texture_id,
-                self.selected_shader_id,
-            ));
-        }
-
-        if self.sprite_buffer_vertices_count >= self.sprite_buffer_vertices_queue.len() {
-            self.sprite_buffer_vertices_queue.resize(self.sprite_buffer_vertices_queue.len() * 2, SpriteVertex::default());
-            self.sprite_buffer_resized = true;
-        }
-
         let (uv_position, uv_size) = if let TextureId::Some(texture_id) = sprite.texture_id {
             let texture = match self.textures.get(texture_id) {
                 Ok(texture) => texture,
@@ -505,7 +674,7 @@ impl RendererContext {
             (Vec2::ZERO, Vec2::new(1.0, 1.0))
         };
 
-        self.sprite_buffer_vertices_queue[self.sprite_buffer_vertices_count] = SpriteVertex {
+        let vertex = SpriteVertex {
             position: if sprite.rounded_coordinates { sprite.position.round() } else { sprite.position },
             anchor: sprite.anchor,
             rotation: sprite.rotation,
@@ -513,84 +682,204 @@ impl RendererContext {
             color: sprite.color.to_rgb_packed(),
             uv_position,
             uv_size,
+            texture_index: 0,
         };
 
-        self.sprite_buffer_count += 1;
-        self.sprite_buffer_vertices_count += 1;
-    }
-
-    pub fn draw_shape(&mut self, shape: &Shape) {
-        if let Some(buffer_metadata) = &self.buffer_metadata {
-            if buffer_metadata.content_type != BufferContentType::Shape || buffer_metadata.texture_id != shape.texture_id {
-                self.flush_buffer();
-                self.buffer_metadata = Some(BufferMetadata::new(
-                    BufferContentType::Shape, // fmt
-                    shape.texture_id,
-                    self.framebuffer_texture_id,
-                    self.selected_shader_id,
-                ));
-            }
+        if self.sorting != SortingMode::None {
+            let data = SortedItemData::Sprite(vertex);
+            self.sorted_items
+                .push(SortedItem::new(sprite.layer, sprite.z, sprite.position.y, sprite.texture_id, self.active_sprite_shader_id, data));
         } else {
-            self.buffer_metadata = Some(BufferMetadata::new(
-                BufferContentType::Shape, // fmt
-                shape.texture_id,
-                self.framebuffer_texture_id,
-                self.selected_shader_id,
-            ));
+            self.push_sprite_vertex(BufferContentType::Sprite, sprite.texture_id, vertex);
         }
+    }
 
-        loop {
-            let mut sufficient_space = true;
+    pub fn draw_text(&mut self, text: &Text) {
+        let font = match self.fonts.get(text.font_id) {
+            Ok(font) => font,
+            Err(err) => error_return!("Failed to draw text ({})", err),
+        };
 
-            if self.shape_buffer_vertices_count + shape.vertices.len() >= self.shape_buffer_vertices_queue.len() {
-                self.shape_buffer_vertices_queue.resize(self.shape_buffer_vertices_queue.len() * 2, ShapeVertex::default());
-                self.shape_buffer_resized = true;
-                sufficient_space = false;
+        let glyphs = text.layout(font);
+        let pages = font.pages.clone();
+        let scale = text.get_scale(font);
+        let mut passes = Vec::default();
+
+        let outline = match font.kind {
+            FontKind::Bitmap => {
+                // Bitmap glyphs have no distance data, so the outline is made of the same text drawn around with an offset
+                if text.outline_width > 0.0 {
+                    for i in 0..8 {
+                        let offset = Vec2::from_angle(i as f32 * consts::FRAC_PI_4) * text.outline_width * scale;
+                        passes.push((offset, text.outline_color));
+                    }
+                }
+
+                None
             }
+            FontKind::Sdf { spread } => {
+                if text.outline_width > 0.0 {
+                    Some((text.outline_color, (0.5 - text.outline_width / (2.0 * spread)).max(0.0)))
+                } else {
+                    Some((Vec4::ZERO, 0.5))
+                }
+            }
+        };
 
-            if self.shape_buffer_indices_count + shape.indices.len() >= self.shape_buffer_indices_queue.len() {
-                self.shape_buffer_indices_queue.resize(self.shape_buffer_indices_queue.len() * 2, 0);
-                self.shape_buffer_resized = true;
-                sufficient_space = false;
+        passes.push((Vec2::ZERO, text.color));
+
+        for (offset, color) in passes {
+            for glyph in &glyphs {
+                let texture_id = match pages.get(glyph.page) {
+                    Some(texture_id) => *texture_id,
+                    None => error_return!("Page {} not found in font {}", glyph.page, text.font_id),
+                };
+                let texture_size = match self.textures.get(texture_id) {
+                    Ok(texture) => texture.size,
+                    Err(err) => error_return!("Failed to draw text ({})", err),
+                };
+
+                let vertex = SpriteVertex {
+                    position: glyph.position + offset,
+                    anchor: Vec2::new(0.0, 1.0),
+                    rotation: text.rotation,
+                    size: glyph.size,
+                    color: color.to_rgb_packed(),
+                    uv_position: glyph.source_position / texture_size,
+                    uv_size: glyph.source_size / texture_size,
+                    texture_index: 0,
+                };
+
+                let texture_id = TextureId::Some(texture_id);
+
+                if self.sorting != SortingMode::None {
+                    let (shader_id, data) = match outline {
+                        Some((outline_color, outline_threshold)) => {
+                            (self.default_text_shader_id, SortedItemData::Text(vertex, outline_color, outline_threshold))
+                        }
+                        None => (self.active_sprite_shader_id, SortedItemData::Sprite(vertex)),
+                    };
+
+                    self.sorted_items.push(SortedItem::new(text.layer, text.z, text.position.y, texture_id, shader_id, data));
+                } else {
+                    match outline {
+                        Some((outline_color, outline_threshold)) => self.push_text_vertex(texture_id, vertex, outline_color, outline_threshold),
+                        None => self.push_sprite_vertex(BufferContentType::Sprite, texture_id, vertex),
+                    }
+                }
             }
+        }
+    }
 
-            if sufficient_space {
-                break;
+    pub fn draw_shape(&mut self, shape: &Shape) {
+        let camera = match self.cameras.get(self.active_camera_id) {
+            Ok(camera) => camera,
+            Err(err) => error_return!("Failed to draw shape ({})", err),
+        };
+
+        let (camera_min, camera_max) = camera.get_bounds();
+        let (shape_min, shape_max) = shape.get_bounds();
+
+        if shape_min.x > camera_max.x || shape_min.y > camera_max.y || shape_max.x < camera_min.x || shape_max.y < camera_min.y {
+            self.statistics_current.culled_shapes += 1;
+            return;
+        }
+
+        let model = if shape.apply_model { Some(shape.get_model()) } else { None };
+
+        if self.sorting != SortingMode::None {
+            let vertices = self.sorted_shape_vertices.len()..self.sorted_shape_vertices.len() + shape.vertices.len();
+            let indices = self.sorted_shape_indices.len()..self.sorted_shape_indices.len() + shape.indices.len();
+
+            self.sorted_shape_vertices.extend(shape.vertices.iter().map(|p| match model {
+                Some(model) => Self::transform_shape_vertex(*p, model, shape.rounded_coordinates),
+                None => *p,
+            }));
+            self.sorted_shape_indices.extend_from_slice(&shape.indices);
+
+            let data = SortedItemData::Shape(vertices, indices);
+            self.sorted_items
+                .push(SortedItem::new(shape.layer, shape.z, shape.position.y, shape.texture_id, self.active_shape_shader_id, data));
+
+            return;
+        }
+
+        self.push_shape(shape.texture_id, &shape.vertices, &shape.indices, model, shape.rounded_coordinates);
+    }
+
+    pub fn draw_tilemap(&mut self, tilemap: &mut Tilemap) {
+        if !tilemap.visible {
+            return;
+        }
+
+        self.flush_buffer();
+
+        if tilemap.is_dirty() {
+            if let Err(err) = tilemap.build(self) {
+                error_return!("Failed to build tilemap ({})", err);
             }
         }
 
+        self.apply_camera_viewport();
+
+        let camera = match self.cameras.get(self.active_camera_id) {
+            Ok(camera) => camera,
+            Err(err) => error_return!("Failed to draw tilemap ({})", err),
+        };
+
+        let (camera_min, camera_max) = camera.get_bounds();
+
         unsafe {
-            let buffer_ptr = self.shape_buffer_vertices_queue.as_mut_ptr();
-            ptr::copy(shape.vertices.as_ptr(), buffer_ptr.add(self.shape_buffer_vertices_count), shape.vertices.len());
-        }
+            match self.shaders.get(self.active_sprite_shader_id) {
+                Ok(shader) => {
+                    let view = camera.get_view_matrix() * Mat4::from_translation(tilemap.position.extend(0.0));
+
+                    shader.activate();
+                    shader.set_uniform("proj", camera.get_projection_matrix().as_ref().as_ptr());
+                    shader.set_uniform("view", view.as_ref().as_ptr());
+                }
+                Err(err) => error_return!("Failed to draw tilemap ({})", err),
+            }
 
-        if shape.apply_model {
-            let model = shape.get_model();
+            // View matrix contains the tilemap offset now, so the next batch has to upload uniforms again
+            self.selected_shader_id = usize::MAX;
 
-            for i in self.shape_buffer_vertices_count..(self.shape_buffer_vertices_count + shape.vertices.len()) {
-                let position = self.shape_buffer_vertices_queue[i].position;
-                let position = Vec4::new(position.x, position.y, 0.0, 1.0);
-                let position_transformed = model * position;
+            let texture_id = match tilemap.texture_id {
+                TextureId::Some(texture_id) => Some(texture_id),
+                TextureId::Default => Some(self.default_texture_id),
+                TextureId::None => None,
+            };
 
-                self.shape_buffer_vertices_queue[i].position = Vec2::new(position_transformed.x, position_transformed.y);
+            if let Some(texture_id) = texture_id {
+                match self.textures.get(texture_id) {
+                    Ok(texture) => texture.activate(0),
+                    Err(err) => error_return!("Failed to draw tilemap ({})", err),
+                };
+            }
 
-                if shape.rounded_coordinates {
-                    self.shape_buffer_vertices_queue[i].position = self.shape_buffer_vertices_queue[i].position.round();
+            for chunk in tilemap.get_chunks() {
+                if !chunk.is_visible(tilemap.position, camera_min, camera_max) {
+                    continue;
                 }
+
+                self.gl.bind_vertex_array(Some(chunk.get_vertex_array()));
+                self.gl.draw_elements_instanced(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0, chunk.tiles_count as i32);
+
+                self.statistics_current.draw_calls += 1;
+                self.statistics_current.triangles += chunk.tiles_count * 2;
             }
         }
+    }
 
-        let base_indice = self.shape_buffer_indices_max;
-        for i in 0..shape.indices.len() {
-            self.shape_buffer_indices_queue[self.shape_buffer_indices_count + i] = base_indice + shape.indices[i];
-            self.shape_buffer_indices_max = cmp::max(self.shape_buffer_indices_max, base_indice + shape.indices[i] + 1);
+    pub fn flush_buffer(&mut self) {
+        if !self.sorted_items.is_empty() {
+            self.flush_sorted();
         }
 
-        self.shape_buffer_vertices_count += shape.vertices.len();
-        self.shape_buffer_indices_count += shape.indices.len();
-    }
+        if self.buffer_metadata.is_some() {
+            self.apply_camera_viewport();
+        }
 
-    pub fn flush_buffer(&mut self) {
         unsafe {
             if let Some(buffer_metadata) = &self.buffer_metadata {
                 if self.sprite_buffer_resized {
@@ -623,13 +912,22 @@ impl RendererContext {
                 let camera_changed = *camera != self.active_camera_data;
 
                 match buffer_metadata.content_type {
-                    BufferContentType::Sprite => {
-                        if self.selected_shader_id != self.active_sprite_shader_id || camera_changed {
-                            match self.shaders.get(self.active_sprite_shader_id) {
+                    BufferContentType::Sprite | BufferContentType::Text => {
+                        let shader_id = match buffer_metadata.content_type {
+                            BufferContentType::Text => self.default_text_shader_id,
+                            _ => self.active_sprite_shader_id,
+                        };
+
+                        if self.selected_shader_id != shader_id || camera_changed {
+                            match self.shaders.get(shader_id) {
                                 Ok(shader) => {
                                     shader.activate();
                                     shader.set_uniform("proj", camera.get_projection_matrix().as_ref().as_ptr());
                                     shader.set_uniform("view", camera.get_view_matrix().as_ref().as_ptr());
+
+                                    for unit in 0..shader.texture_units {
+                                        shader.set_uniform(&format!("textureSamplers[{}]", unit), &(unit as f32));
+                                    }
                                 }
                                 Err(err) => error!("{}", err),
                             }
@@ -638,7 +936,17 @@ impl RendererContext {
                                 self.active_camera_data = camera.clone();
                             }
 
-                            self.selected_shader_id = self.active_sprite_shader_id;
+                            self.selected_shader_id = shader_id;
+                        }
+
+                        if buffer_metadata.content_type == BufferContentType::Text {
+                            match self.shaders.get(shader_id) {
+                                Ok(shader) => {
+                                    shader.set_uniform("outlineColor", self.text_outline_color.as_ref().as_ptr());
+                                    shader.set_uniform("outlineThreshold", &self.text_outline_threshold);
+                                }
+                                Err(err) => error!("{}", err),
+                            }
                         }
 
                         self.gl.bind_vertex_array(Some(self.sprite_buffer_vao));
@@ -652,14 +960,15 @@ impl RendererContext {
 
                         self.gl.buffer_sub_data_u8_slice(glow::ARRAY_BUFFER, 0, models_u8);
 
-                        if let TextureId::Some(texture_id) = buffer_metadata.texture_id {
-                            match self.textures.get(texture_id) {
-                                Ok(texture) => texture.activate(0),
-                                Err(err) => error!("{}", err),
+                        for (unit, texture_id) in buffer_metadata.textures.iter().enumerate().rev() {
+                            let texture_id = match texture_id {
+                                TextureId::Some(texture_id) => *texture_id,
+                                TextureId::Default => self.default_texture_id,
+                                TextureId::None => continue,
                             };
-                        } else if let TextureId::Default = buffer_metadata.texture_id {
-                            match self.textures.get(self.default_texture_id) {
-                                Ok(texture) => texture.activate(0),
+
+                            match self.textures.get(texture_id) {
+                                Ok(texture) => texture.activate(unit as u32),
                                 Err(err) => error!("{}", err),
                             };
                         }
@@ -707,12 +1016,12 @@ impl RendererContext {
                         self.gl.buffer_sub_data_u8_slice(glow::ARRAY_BUFFER, 0, models_u8);
                         self.gl.buffer_sub_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, 0, indices_u8);
 
-                        if let TextureId::Some(texture_id) = buffer_metadata.texture_id {
+                        if let TextureId::Some(texture_id) = buffer_metadata.textures[0] {
                             match self.textures.get(texture_id) {
                                 Ok(texture) => texture.activate(0),
                                 Err(err) => error!("{}", err),
                             };
-                        } else if let TextureId::Default = buffer_metadata.texture_id {
+                        } else if let TextureId::Default = buffer_metadata.textures[0] {
                             match self.textures.get(self.default_texture_id) {
                                 Ok(texture) => texture.activate(0),
                                 Err(err) => error!("{}", err),
@@ -735,20 +1044,30 @@ impl RendererContext {
     }
 
     pub fn set_camera(&mut self, camera_id: usize) {
+        if self.active_camera_id != camera_id {
+            self.flush_buffer();
+        }
+
         let camera = match self.cameras.get_mut(camera_id) {
             Ok(camera) => camera,
             Err(err) => error_return!("Failed to set camera ({})", err),
         };
 
         if camera.autofit {
-            camera.size = self.viewport_size;
+            camera.size = self.viewport_size * camera.viewport_size;
+        }
+
+        if camera.is_fullscreen() && self.viewport_scissor {
+            self.disable_scissor();
+            self.viewport_scissor = false;
         }
 
         self.active_camera_id = camera_id;
     }
 
     pub fn set_sprite_shader(&mut self, shader_id: Option<usize>) {
-        if Some(self.active_sprite_shader_id) != shader_id {
+        // Sorted items remember their shaders, so they can be reordered across shader changes
+        if Some(self.active_sprite_shader_id) != shader_id && self.sorting == SortingMode::None {
             self.flush_buffer();
         }
 
@@ -759,7 +1078,7 @@ impl RendererContext {
     }
 
     pub fn set_shape_shader(&mut self, shader_id: Option<usize>) {
-        if Some(self.active_shape_shader_id) != shader_id {
+        if Some(self.active_shape_shader_id) != shader_id && self.sorting == SortingMode::None {
             self.flush_buffer();
         }
 
@@ -769,7 +1088,22 @@ impl RendererContext {
         }
     }
 
+    pub fn set_sorting(&mut self, sorting: SortingMode) {
+        if self.sorting != sorting {
+            self.flush_buffer();
+        }
+
+        self.sorting = sorting;
+    }
+
+    pub fn get_sorting(&self) -> SortingMode {
+        self.sorting
+    }
+
     pub fn set_target_texture(&mut self, texture_id: Option<usize>) {
+        // With virtual resolution enabled, the offscreen target acts as the screen until it's presented at the end of the frame
+        let texture_id = texture_id.or(self.virtual_resolution.as_ref().map(|p| p.texture_id));
+
         unsafe {
             if self.framebuffer_texture_id != texture_id {
                 self.flush_buffer();
@@ -851,6 +1185,9 @@ impl RendererContext {
     }
 
     pub fn set_viewport(&mut self, size: Vec2) {
+        self.window_size = size;
+        let size = self.virtual_resolution.as_ref().map(|p| p.size).unwrap_or(size);
+
         unsafe {
             self.gl.viewport(0, 0, size.x as i32, size.y as i32);
             self.viewport_size = size;
@@ -861,7 +1198,7 @@ impl RendererContext {
             };
 
             if camera.autofit {
-                camera.size = self.viewport_size;
+                camera.size = self.viewport_size * camera.viewport_size;
             }
 
             if let Some(framebuffer_texture_id) = self.framebuffer_texture_id {
@@ -895,6 +1232,65 @@ impl RendererContext {
     pub fn set_clear_color(&mut self, color: Vec4) {
         unsafe {
             self.gl.clear_color(color.x, color.y, color.z, color.w);
+            self.clear_color = color;
+        }
+    }
+
+    pub fn set_virtual_resolution(&mut self, size: Option<Vec2>, scaling: VirtualScaling) -> Result<()> {
+        self.flush_buffer();
+
+        // New texture is created first, so a failure leaves the current virtual resolution untouched
+        let texture = match size {
+            Some(size) => {
+                let mut texture = Texture::new(self, &RawTexture::new("virtual_resolution", "", size, &Vec::default()))?;
+                texture.set_filters(TextureFilterMin::Nearest, TextureFilterMag::Nearest);
+                Some(texture)
+            }
+            None => None,
+        };
+
+        if let Some(virtual_resolution) = self.virtual_resolution.take() {
+            self.set_target_texture(None);
+            self.cameras.remove(virtual_resolution.camera_id);
+
+            if let Some(texture) = self.textures.remove(virtual_resolution.texture_id) {
+                unsafe {
+                    self.gl.delete_texture(texture.inner);
+                }
+            }
+        }
+
+        if let (Some(size), Some(texture)) = (size, texture) {
+            self.virtual_resolution = Some(VirtualResolution {
+                size,
+                scaling,
+                letterbox_color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+                texture_id: self.textures.store(texture),
+                camera_id: self.cameras.store(Camera::new(Vec2::ZERO, size, CameraOrigin::LeftTop, false)),
+            });
+        }
+
+        self.set_viewport(self.window_size);
+        self.set_camera(self.active_camera_id);
+        self.set_target_texture(None);
+
+        Ok(())
+    }
+
+    pub fn get_virtual_resolution(&self) -> Option<&VirtualResolution> {
+        self.virtual_resolution.as_ref()
+    }
+
+    pub fn from_window_to_virtual_coordinates(&self, position: Vec2) -> Vec2 {
+        match &self.virtual_resolution {
+            // Minimized window has no viewport to map from
+            Some(virtual_resolution) if self.window_size.min_element() > 0.0 => {
+                let (viewport_position, viewport_size) = virtual_resolution.get_viewport(self.window_size);
+                let top = self.window_size.y - viewport_position.y - viewport_size.y;
+
+                (position - Vec2::new(viewport_position.x, top)) * virtual_resolution.size / viewport_size
+            }
+            _ => position,
         }
     }
 
@@ -928,6 +1324,99 @@ impl RendererContext {
         }
     }
 
+    fn apply_camera_viewport(&mut self) {
+        let camera = match self.cameras.get(self.active_camera_id) {
+            Ok(camera) => camera,
+            Err(err) => error_return!("Failed to apply camera viewport ({})", err),
+        };
+
+        let target_size = if self.framebuffer_texture_id.is_some() { self.viewport_size } else { self.window_size };
+        let position = (camera.viewport_position * target_size).round();
+        let size = (camera.viewport_size * target_size).round();
+        let fullscreen = camera.is_fullscreen();
+
+        unsafe {
+            self.gl.viewport(position.x as i32, position.y as i32, size.x as i32, size.y as i32);
+        }
+
+        // Scissor set by the user (like UI clipping) is left untouched for fullscreen cameras
+        if !fullscreen {
+            self.enable_scissor(position, size);
+            self.viewport_scissor = true;
+        }
+    }
+
+    fn present_virtual_resolution(&mut self) {
+        // Nothing is visible while the window is minimized, and the viewport math would divide by zero
+        if self.window_size.min_element() <= 0.0 {
+            return;
+        }
+
+        let (virtual_camera_id, position, size) = match &self.virtual_resolution {
+            Some(virtual_resolution) => {
+                let (position, size) = virtual_resolution.get_viewport(self.window_size);
+                (virtual_resolution.camera_id, position, size)
+            }
+            None => return,
+        };
+
+        // Camera is updated before the state is taken, so a failure leaves virtual resolution in place
+        match self.cameras.get_mut(virtual_camera_id) {
+            Ok(camera) => {
+                camera.viewport_position = position / self.window_size;
+                camera.viewport_size = size / self.window_size;
+            }
+            Err(err) => error_return!("Failed to present virtual resolution ({})", err),
+        }
+
+        let virtual_resolution = match self.virtual_resolution.take() {
+            Some(virtual_resolution) => virtual_resolution,
+            None => return,
+        };
+
+        let camera_id = self.active_camera_id;
+        let sprite_shader_id = self.active_sprite_shader_id;
+        let letterbox_color = virtual_resolution.letterbox_color;
+
+        self.set_target_texture(None);
+        self.set_camera(virtual_camera_id);
+        self.disable_scissor();
+        self.viewport_scissor = false;
+
+        unsafe {
+            self.gl.clear_color(letterbox_color.x, letterbox_color.y, letterbox_color.z, letterbox_color.w);
+            self.clear();
+            self.gl.clear_color(self.clear_color.x, self.clear_color.y, self.clear_color.z, self.clear_color.w);
+        }
+
+        self.set_sprite_shader(None);
+        self.draw_sprite(&Sprite {
+            anchor: Vec2::ZERO,
+            size: Some(virtual_resolution.size),
+            texture_id: TextureId::Some(virtual_resolution.texture_id),
+            ..Default::default()
+        });
+        self.flush_buffer();
+
+        self.set_sprite_shader(Some(sprite_shader_id));
+        self.set_camera(camera_id);
+        self.virtual_resolution = Some(virtual_resolution);
+    }
+
+    pub fn create_sprite_vertex_array(&self, vbo: Buffer) -> Result<VertexArray> {
+        unsafe {
+            let vao = self.gl.create_vertex_array().map_err(Error::msg)?;
+
+            self.gl.bind_vertex_array(Some(vao));
+            self.gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+            self.gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(self.sprite_buffer_ebo));
+
+            Self::set_sprite_vertex_attributes(&self.gl);
+
+            Ok(vao)
+        }
+    }
+
     pub fn get_hardware_info(&self) -> String {
         unsafe {
             if self.gl.supported_extensions().contains("WEBGL_debug_renderer_info") {
@@ -938,10 +1427,261 @@ impl RendererContext {
             }
         }
     }
+
+    pub fn is_texture_format_supported(&self, format: RawTextureFormat) -> bool {
+        let extensions = self.gl.supported_extensions();
+        let version = self.gl.version();
+        let supported = |names: &[&str]| names.iter().any(|p| extensions.contains(*p));
+
+        match format {
+            RawTextureFormat::Rgba8 | RawTextureFormat::R8 => true,
+            RawTextureFormat::Bc1 | RawTextureFormat::Bc3 => {
+                (supported(&["GL_EXT_texture_compression_s3tc"]) && supported(&["GL_EXT_texture_sRGB", "GL_EXT_texture_compression_s3tc_srgb"]))
+                    || supported(&["WEBGL_compressed_texture_s3tc_srgb"])
+            }
+            RawTextureFormat::Bc7 => {
+                supported(&["GL_ARB_texture_compression_bptc", "GL_EXT_texture_compression_bptc", "EXT_texture_compression_bptc"])
+            }
+            RawTextureFormat::Etc2Rgb8 | RawTextureFormat::Etc2Rgba8 => {
+                (!version.is_embedded && (version.major, version.minor) >= (4, 3))
+                    || supported(&["GL_ARB_ES3_compatibility", "WEBGL_compressed_texture_etc"])
+            }
+            RawTextureFormat::Astc4x4 => supported(&["GL_KHR_texture_compression_astc_ldr", "WEBGL_compressed_texture_astc"]),
+        }
+    }
+
+    fn push_sprite_vertex(&mut self, content_type: BufferContentType, texture_id: TextureId, mut vertex: SpriteVertex) {
+        // Sprites can sample from several textures in one batch, as long as the shader has enough units declared
+        let texture_index = match &mut self.buffer_metadata {
+            Some(buffer_metadata) if buffer_metadata.content_type == content_type => {
+                match buffer_metadata.textures.iter().position(|p| *p == texture_id) {
+                    Some(index) => Some(index),
+                    None if buffer_metadata.textures.len() < buffer_metadata.texture_units => {
+                        buffer_metadata.textures.push(texture_id);
+                        Some(buffer_metadata.textures.len() - 1)
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+
+        let texture_index = match texture_index {
+            Some(texture_index) => texture_index,
+            None => {
+                self.flush_buffer();
+
+                let mut buffer_metadata = BufferMetadata::new(content_type, texture_id, self.framebuffer_texture_id, self.selected_shader_id);
+                if content_type == BufferContentType::Sprite {
+                    buffer_metadata.texture_units = self.shaders.get(self.active_sprite_shader_id).map(|p| p.texture_units.max(1)).unwrap_or(1);
+                }
+
+                self.buffer_metadata = Some(buffer_metadata);
+                0
+            }
+        };
+
+        vertex.texture_index = texture_index as u32;
+
+        if self.sprite_buffer_vertices_count >= self.sprite_buffer_vertices_queue.len() {
+            self.sprite_buffer_vertices_queue.resize(self.sprite_buffer_vertices_queue.len() * 2, SpriteVertex::default());
+            self.sprite_buffer_resized = true;
+        }
+
+        self.sprite_buffer_vertices_queue[self.sprite_buffer_vertices_count] = vertex;
+        self.sprite_buffer_count += 1;
+        self.sprite_buffer_vertices_count += 1;
+    }
+
+    fn push_shape(&mut self, texture_id: TextureId, vertices: &[ShapeVertex], indices: &[u32], model: Option<Mat4>, rounded_coordinates: bool) {
+        if let Some(buffer_metadata) = &self.buffer_metadata {
+            if buffer_metadata.content_type != BufferContentType::Shape || buffer_metadata.textures[0] != texture_id {
+                self.flush_buffer();
+                self.buffer_metadata = Some(BufferMetadata::new(
+                    BufferContentType::Shape, // fmt
+                    texture_id,
+                    self.framebuffer_texture_id,
+                    self.selected_shader_id,
+                ));
+            }
+        } else {
+            self.buffer_metadata = Some(BufferMetadata::new(
+                BufferContentType::Shape, // fmt
+                texture_id,
+                self.framebuffer_texture_id,
+                self.selected_shader_id,
+            ));
+        }
+
+        loop {
+            let mut sufficient_space = true;
+
+            if self.shape_buffer_vertices_count + vertices.len() >= self.shape_buffer_vertices_queue.len() {
+                self.shape_buffer_vertices_queue.resize(self.shape_buffer_vertices_queue.len() * 2, ShapeVertex::default());
+                self.shape_buffer_resized = true;
+                sufficient_space = false;
+            }
+
+            if self.shape_buffer_indices_count + indices.len() >= self.shape_buffer_indices_queue.len() {
+                self.shape_buffer_indices_queue.resize(self.shape_buffer_indices_queue.len() * 2, 0);
+                self.shape_buffer_resized = true;
+                sufficient_space = false;
+            }
+
+            if sufficient_space {
+                break;
+            }
+        }
+
+        unsafe {
+            let buffer_ptr = self.shape_buffer_vertices_queue.as_mut_ptr();
+            ptr::copy(vertices.as_ptr(), buffer_ptr.add(self.shape_buffer_vertices_count), vertices.len());
+        }
+
+        if let Some(model) = model {
+            for i in self.shape_buffer_vertices_count..(self.shape_buffer_vertices_count + vertices.len()) {
+                self.shape_buffer_vertices_queue[i] = Self::transform_shape_vertex(self.shape_buffer_vertices_queue[i], model, rounded_coordinates);
+            }
+        }
+
+        let base_indice = self.shape_buffer_indices_max;
+        for (i, indice) in indices.iter().enumerate() {
+            self.shape_buffer_indices_queue[self.shape_buffer_indices_count + i] = base_indice + indice;
+            self.shape_buffer_indices_max = cmp::max(self.shape_buffer_indices_max, base_indice + indice + 1);
+        }
+
+        self.shape_buffer_vertices_count += vertices.len();
+        self.shape_buffer_indices_count += indices.len();
+    }
+
+    fn push_text_vertex(&mut self, texture_id: TextureId, vertex: SpriteVertex, outline_color: Vec4, outline_threshold: f32) {
+        if self.text_outline_color != outline_color || self.text_outline_threshold != outline_threshold {
+            self.flush_buffer();
+            self.text_outline_color = outline_color;
+            self.text_outline_threshold = outline_threshold;
+        }
+
+        self.push_sprite_vertex(BufferContentType::Text, texture_id, vertex);
+    }
+
+    fn transform_shape_vertex(vertex: ShapeVertex, model: Mat4, rounded_coordinates: bool) -> ShapeVertex {
+        let position = model * Vec4::new(vertex.position.x, vertex.position.y, 0.0, 1.0);
+        let position = Vec2::new(position.x, position.y);
+
+        ShapeVertex { position: if rounded_coordinates { position.round() } else { position }, ..vertex }
+    }
+
+    fn flush_sorted(&mut self) {
+        let mut items = mem::take(&mut self.sorted_items);
+        let mut vertices = mem::take(&mut self.sorted_shape_vertices);
+        let mut indices = mem::take(&mut self.sorted_shape_indices);
+        let sorting = mem::replace(&mut self.sorting, SortingMode::None);
+        let sprite_shader_id = self.active_sprite_shader_id;
+        let shape_shader_id = self.active_shape_shader_id;
+
+        // Stable sort keeps the submission order within the same layer and depth, items may overlap so texture isn't a valid tiebreak
+        // unless the game opted in for batching
+        items.sort_by(|a, b| {
+            let tiebreak = match sorting {
+                SortingMode::LayerY => b.y.total_cmp(&a.y),
+                SortingMode::LayerBatched => (a.data.kind(), a.shader_id, a.texture_id).cmp(&(b.data.kind(), b.shader_id, b.texture_id)),
+                _ => cmp::Ordering::Equal,
+            };
+            a.layer.cmp(&b.layer).then(a.z.total_cmp(&b.z)).then(tiebreak)
+        });
+
+        for item in &items {
+            match &item.data {
+                SortedItemData::Sprite(vertex) => {
+                    self.set_sprite_shader(Some(item.shader_id));
+                    self.push_sprite_vertex(BufferContentType::Sprite, item.texture_id, *vertex);
+                }
+                SortedItemData::Text(vertex, outline_color, outline_threshold) => {
+                    self.push_text_vertex(item.texture_id, *vertex, *outline_color, *outline_threshold);
+                }
+                SortedItemData::Shape(vertices_range, indices_range) => {
+                    self.set_shape_shader(Some(item.shader_id));
+                    self.push_shape(item.texture_id, &vertices[vertices_range.clone()], &indices[indices_range.clone()], None, false);
+                }
+            }
+        }
+
+        self.flush_buffer();
+        self.set_sprite_shader(Some(sprite_shader_id));
+        self.set_shape_shader(Some(shape_shader_id));
+        self.sorting = sorting;
+
+        items.clear();
+        vertices.clear();
+        indices.clear();
+
+        self.sorted_items = items;
+        self.sorted_shape_vertices = vertices;
+        self.sorted_shape_indices = indices;
+    }
+
+    unsafe fn set_sprite_vertex_attributes(gl: &Context) {
+        gl.enable_vertex_attrib_array(0);
+        gl.enable_vertex_attrib_array(1);
+        gl.enable_vertex_attrib_array(2);
+        gl.enable_vertex_attrib_array(3);
+        gl.enable_vertex_attrib_array(4);
+        gl.enable_vertex_attrib_array(5);
+        gl.enable_vertex_attrib_array(6);
+
+        gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 13 * 4, 0);
+        gl.vertex_attrib_pointer_f32(1, 2, glow::FLOAT, false, 13 * 4, 2 * 4);
+        gl.vertex_attrib_pointer_f32(2, 1, glow::FLOAT, false, 13 * 4, 4 * 4);
+        gl.vertex_attrib_pointer_f32(3, 2, glow::FLOAT, false, 13 * 4, 5 * 4);
+        gl.vertex_attrib_pointer_i32(4, 4, glow::UNSIGNED_BYTE, 13 * 4, 7 * 4);
+        gl.vertex_attrib_pointer_f32(5, 4, glow::FLOAT, false, 13 * 4, 8 * 4);
+        gl.vertex_attrib_pointer_i32(6, 1, glow::UNSIGNED_INT, 13 * 4, 12 * 4);
+
+        gl.vertex_attrib_divisor(0, 1);
+        gl.vertex_attrib_divisor(1, 1);
+        gl.vertex_attrib_divisor(2, 1);
+        gl.vertex_attrib_divisor(3, 1);
+        gl.vertex_attrib_divisor(4, 1);
+        gl.vertex_attrib_divisor(5, 1);
+        gl.vertex_attrib_divisor(6, 1);
+    }
 }
 
 impl BufferMetadata {
     pub fn new(content_type: BufferContentType, texture_id: TextureId, framebuffer_texture_id: Option<usize>, selected_shader_id: usize) -> Self {
-        Self { content_type, texture_id, framebuffer_texture_id, selected_shader_id }
+        let mut textures = ArrayVec::new();
+        textures.push(texture_id);
+
+        Self { content_type, textures, texture_units: 1, framebuffer_texture_id, selected_shader_id }
+    }
+}
+
+impl VirtualResolution {
+    pub fn get_viewport(&self, window_size: Vec2) -> (Vec2, Vec2) {
+        let ratio = (window_size / self.size).min_element();
+        let scale = match self.scaling {
+            VirtualScaling::Integer => ratio.floor().max(1.0),
+            VirtualScaling::Aspect => ratio,
+        };
+        let size = (self.size * scale).round();
+
+        (((window_size - size) / 2.0).floor(), size)
+    }
+}
+
+impl SortedItem {
+    pub fn new(layer: i32, z: f32, y: f32, texture_id: TextureId, shader_id: usize, data: SortedItemData) -> Self {
+        Self { layer, z, y, texture_id, shader_id, data }
+    }
+}
+
+impl SortedItemData {
+    // Sprites, texts and shapes are drawn by separate buffers, so switching between them flushes the batch too
+    fn kind(&self) -> usize {
+        match self {
+            SortedItemData::Sprite(_) => 0,
+            SortedItemData::Text(..) => 1,
+            SortedItemData::Shape(..) => 2,
+        }
     }
 }
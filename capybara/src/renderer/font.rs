@@ -0,0 +1,263 @@
+use super::context::RendererContext;
+use super::texture::Texture;
+use super::texture::TextureFilterMag;
+use super::texture::TextureFilterMin;
+use crate::assets::bmfont::BitmapFont;
+use crate::assets::packer::AtlasPacker;
+use crate::assets::RawFont;
+use crate::assets::RawTexture;
+use crate::assets::RawTextureFormat;
+use crate::error_continue;
+use ab_glyph::Font as _;
+use ab_glyph::FontRef;
+use ab_glyph::ScaleFont;
+use anyhow::Result;
+use glam::Vec2;
+use log::info;
+use rustc_hash::FxHashMap;
+
+#[derive(Clone, Debug)]
+pub struct Font {
+    pub name: String,
+    pub kind: FontKind,
+    pub size: f32,
+    pub line_height: f32,
+    pub base: f32,
+    pub pages: Vec<usize>,
+    pub glyphs: FxHashMap<char, FontGlyph>,
+    pub kerning: FxHashMap<(char, char), f32>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FontKind {
+    Bitmap,
+    Sdf { spread: f32 },
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FontGlyph {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub offset: Vec2,
+    pub advance: f32,
+    pub page: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontSdfSettings {
+    pub size: f32,
+    pub spread: f32,
+    pub characters: String,
+    pub texture_size: Vec2,
+}
+
+impl Font {
+    pub fn new_from_bitmap(renderer: &RendererContext, raw: &BitmapFont) -> Result<Self> {
+        let pages = raw.pages.iter().map(|p| renderer.textures.get_id(p)).collect::<Result<Vec<usize>>>()?;
+        let glyphs = raw
+            .glyphs
+            .iter()
+            .map(|p| (p.id, FontGlyph { position: p.position, size: p.size, offset: p.offset, advance: p.advance, page: p.page }))
+            .collect();
+        let kerning = raw.kerning.iter().map(|p| ((p.0, p.1), p.2)).collect();
+
+        Ok(Self {
+            name: raw.name.clone(),
+            kind: FontKind::Bitmap,
+            size: raw.size,
+            line_height: raw.line_height,
+            base: raw.base,
+            pages,
+            glyphs,
+            kerning,
+        })
+    }
+
+    pub fn new_sdf(renderer: &mut RendererContext, raw: &RawFont, settings: &FontSdfSettings) -> Result<Self> {
+        info!("Generating SDF font {} (size {}, spread {})", raw.name, settings.size, settings.spread);
+
+        let font = FontRef::try_from_slice(&raw.data)?;
+        let scaled = font.as_scaled(settings.size);
+        let padding = settings.spread.ceil() as usize;
+
+        let mut glyphs = FxHashMap::default();
+        let mut bitmaps = Vec::default();
+        let mut packers = Vec::<(AtlasPacker, Vec<u8>)>::default();
+
+        for char in settings.characters.chars() {
+            let id = font.glyph_id(char);
+            if id.0 == 0 {
+                continue;
+            }
+
+            let mut glyph = FontGlyph { advance: scaled.h_advance(id), ..Default::default() };
+
+            if let Some(outlined) = font.outline_glyph(id.with_scale(settings.size)) {
+                let bounds = outlined.px_bounds();
+                let width = bounds.width() as usize + padding * 2;
+                let height = bounds.height() as usize + padding * 2;
+                let mut coverage = vec![0.0; width * height];
+
+                outlined.draw(|x, y, value| coverage[(y as usize + padding) * width + x as usize + padding] = value);
+
+                glyph.size = Vec2::new(width as f32, height as f32);
+                glyph.offset = Vec2::new(bounds.min.x, bounds.min.y + scaled.ascent()) - padding as f32;
+                bitmaps.push((char, generate_sdf(&coverage, width, height, settings.spread)));
+            }
+
+            glyphs.insert(char, glyph);
+        }
+
+        for (char, bitmap) in bitmaps {
+            let glyph = glyphs.get_mut(&char).unwrap();
+            let placed = packers.iter_mut().enumerate().find_map(|(index, page)| page.0.insert(glyph.size + 1.0).map(|p| (index, p)));
+
+            let (page, position) = match placed {
+                Some(placed) => placed,
+                None => {
+                    let mut packer = AtlasPacker::new(settings.texture_size);
+                    let position = match packer.insert(glyph.size + 1.0) {
+                        Some(position) => position,
+                        None => {
+                            // Glyph keeps its advance, so the text layout stays the same and only the character isn't drawn
+                            glyph.size = Vec2::ZERO;
+                            error_continue!("Glyph {:?} doesn't fit on a page of font {}", char, raw.name);
+                        }
+                    };
+
+                    packers.push((packer, vec![0; (settings.texture_size.x * settings.texture_size.y) as usize]));
+                    (packers.len() - 1, position)
+                }
+            };
+
+            let texture_width = settings.texture_size.x as usize;
+            let glyph_width = glyph.size.x as usize;
+            let data = &mut packers[page].1;
+
+            for (y, row) in bitmap.chunks(glyph_width).enumerate() {
+                let start = (position.y as usize + y) * texture_width + position.x as usize;
+                data[start..start + glyph_width].copy_from_slice(row);
+            }
+
+            glyph.position = position;
+            glyph.page = page;
+        }
+
+        let mut pages = Vec::default();
+        for (index, (_, data)) in packers.iter().enumerate() {
+            let raw_texture =
+                RawTexture::new(&format!("{}_sdf_{}", raw.name, index), "", settings.texture_size, data).with_format(RawTextureFormat::R8);
+            let mut texture = Texture::new(renderer, &raw_texture)?;
+            texture.set_filters(TextureFilterMin::Linear, TextureFilterMag::Linear);
+
+            pages.push(renderer.textures.store(texture));
+        }
+
+        let mut kerning = FxHashMap::default();
+        for first in glyphs.keys() {
+            for second in glyphs.keys() {
+                let amount = scaled.kern(font.glyph_id(*first), font.glyph_id(*second));
+                if amount != 0.0 {
+                    kerning.insert((*first, *second), amount);
+                }
+            }
+        }
+
+        Ok(Self {
+            name: raw.name.clone(),
+            kind: FontKind::Sdf { spread: settings.spread },
+            size: settings.size,
+            line_height: scaled.height() + scaled.line_gap(),
+            base: scaled.ascent(),
+            pages,
+            glyphs,
+            kerning,
+        })
+    }
+
+    pub fn get_kerning(&self, first: char, second: char) -> f32 {
+        self.kerning.get(&(first, second)).copied().unwrap_or(0.0)
+    }
+}
+
+impl Default for FontSdfSettings {
+    fn default() -> Self {
+        Self { size: 48.0, spread: 8.0, characters: (' '..='~').chain('\u{a0}'..='\u{ff}').collect(), texture_size: Vec2::new(1024.0, 1024.0) }
+    }
+}
+
+fn generate_sdf(coverage: &[f32], width: usize, height: usize, spread: f32) -> Vec<u8> {
+    let inside = coverage.iter().map(|p| *p > 0.5).collect::<Vec<bool>>();
+    let outside = inside.iter().map(|p| !p).collect::<Vec<bool>>();
+    let distance_outside = transform_distance(&inside, width, height);
+    let distance_inside = transform_distance(&outside, width, height);
+
+    // Distances are measured between pixel centers, half of the pixel is subtracted so the edge lies exactly at 0.5
+    (0..width * height)
+        .map(|p| {
+            let distance = if inside[p] { distance_inside[p].sqrt() - 0.5 } else { -(distance_outside[p].sqrt() - 0.5) };
+            ((0.5 + distance / (2.0 * spread)).clamp(0.0, 1.0) * 255.0) as u8
+        })
+        .collect()
+}
+
+fn transform_distance(features: &[bool], width: usize, height: usize) -> Vec<f32> {
+    let mut grid = features.iter().map(|p| if *p { 0.0 } else { f32::MAX / 4.0 }).collect::<Vec<f32>>();
+    let length = width.max(height);
+    let mut input = vec![0.0; length];
+    let mut output = vec![0.0; length];
+
+    for x in 0..width {
+        for y in 0..height {
+            input[y] = grid[y * width + x];
+        }
+
+        transform_distance_1d(&input[..height], &mut output);
+
+        for y in 0..height {
+            grid[y * width + x] = output[y];
+        }
+    }
+
+    for y in 0..height {
+        input[..width].copy_from_slice(&grid[y * width..(y + 1) * width]);
+        transform_distance_1d(&input[..width], &mut output);
+        grid[y * width..(y + 1) * width].copy_from_slice(&output[..width]);
+    }
+
+    grid
+}
+
+fn transform_distance_1d(input: &[f32], output: &mut [f32]) {
+    // Lower envelope of parabolas rooted at every sample (Felzenszwalb and Huttenlocher), gives exact squared distances
+    let mut vertices = vec![0; input.len()];
+    let mut bounds = vec![0.0; input.len() + 1];
+    let mut count = 0;
+    let intersection = |q: usize, v: usize| ((input[q] + (q * q) as f32) - (input[v] + (v * v) as f32)) / (2 * q - 2 * v) as f32;
+
+    bounds[0] = f32::MIN;
+    bounds[1] = f32::MAX;
+
+    for q in 1..input.len() {
+        let mut s = intersection(q, vertices[count]);
+        while s <= bounds[count] {
+            count -= 1;
+            s = intersection(q, vertices[count]);
+        }
+
+        count += 1;
+        vertices[count] = q;
+        bounds[count] = s;
+        bounds[count + 1] = f32::MAX;
+    }
+
+    count = 0;
+    for (q, value) in output.iter_mut().enumerate().take(input.len()) {
+        while bounds[count + 1] < q as f32 {
+            count += 1;
+        }
+
+        let distance = q as f32 - vertices[count] as f32;
+        *value = distance * distance + input[vertices[count]];
+    }
+}
@@ -2,10 +2,14 @@ use glam::Vec2;
 
 pub mod camera;
 pub mod context;
+pub mod font;
+pub mod postprocess;
 pub mod shader;
 pub mod shape;
 pub mod sprite;
+pub mod text;
 pub mod texture;
+pub mod tilemap;
 
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Edge {
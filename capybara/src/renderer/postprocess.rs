@@ -0,0 +1,281 @@
+use super::context::RendererContext;
+use super::shader::*;
+use super::sprite::Sprite;
+use super::sprite::TextureId;
+use super::texture::Texture;
+use crate::assets::RawTexture;
+use crate::error_continue;
+use crate::error_return;
+use anyhow::Result;
+use glam::Vec2;
+use glam::Vec4;
+use glow::HasContext;
+
+#[derive(Debug)]
+pub struct PostProcessStack {
+    pub passes: Vec<PostProcessPass>,
+
+    shaders: PostProcessShaders,
+    targets: [usize; 3],
+}
+
+#[derive(Clone, Debug)]
+pub struct PostProcessPass {
+    pub name: String,
+    pub shader_id: usize,
+    pub uniforms: Vec<(String, PostProcessUniform)>,
+    pub retain_input: bool,
+    pub enabled: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PostProcessUniform {
+    Float(f32),
+    Vec2(Vec2),
+    Vec4(Vec4),
+    Texture(usize),
+    Retained,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PostProcessShaders {
+    copy: usize,
+    blur: usize,
+    bloom_extract: usize,
+    bloom_combine: usize,
+    vignette: usize,
+    lut: usize,
+    crt: usize,
+}
+
+impl PostProcessStack {
+    pub fn new(renderer: &mut RendererContext) -> Result<Self> {
+        let mut targets = [0; 3];
+        for (index, target) in targets.iter_mut().enumerate() {
+            let raw = RawTexture::new(&format!("postprocess_{}", index), "", renderer.viewport_size.max(Vec2::ONE), &Vec::default());
+            *target = renderer.textures.store(Texture::new(renderer, &raw)?);
+        }
+
+        let shaders = PostProcessShaders {
+            copy: get_shader(renderer, "postprocess_copy", POSTPROCESS_COPY_FRAGMENT_SHADER)?,
+            blur: get_shader(renderer, "postprocess_blur", POSTPROCESS_BLUR_FRAGMENT_SHADER)?,
+            bloom_extract: get_shader(renderer, "postprocess_bloom_extract", POSTPROCESS_BLOOM_EXTRACT_FRAGMENT_SHADER)?,
+            bloom_combine: get_shader(renderer, "postprocess_bloom_combine", POSTPROCESS_BLOOM_COMBINE_FRAGMENT_SHADER)?,
+            vignette: get_shader(renderer, "postprocess_vignette", POSTPROCESS_VIGNETTE_FRAGMENT_SHADER)?,
+            lut: get_shader(renderer, "postprocess_lut", POSTPROCESS_LUT_FRAGMENT_SHADER)?,
+            crt: get_shader(renderer, "postprocess_crt", POSTPROCESS_CRT_FRAGMENT_SHADER)?,
+        };
+
+        Ok(Self { passes: Vec::default(), shaders, targets })
+    }
+
+    pub fn with_pass(mut self, pass: PostProcessPass) -> Self {
+        self.passes.push(pass);
+        self
+    }
+
+    pub fn with_blur(self, radius: f32) -> Self {
+        let shader_id = self.shaders.blur;
+        self.with_pass(PostProcessPass::new_blur("blur", shader_id, Vec2::X, radius)).with_pass(PostProcessPass::new_blur(
+            "blur",
+            shader_id,
+            Vec2::Y,
+            radius,
+        ))
+    }
+
+    pub fn with_bloom(self, threshold: f32, intensity: f32, radius: f32) -> Self {
+        let shaders = self.shaders;
+        let mut extract = PostProcessPass::new("bloom", shaders.bloom_extract).with_uniform("threshold", PostProcessUniform::Float(threshold));
+        extract.retain_input = true;
+
+        self.with_pass(extract)
+            .with_pass(PostProcessPass::new_blur("bloom", shaders.blur, Vec2::X, radius))
+            .with_pass(PostProcessPass::new_blur("bloom", shaders.blur, Vec2::Y, radius))
+            .with_pass(
+                PostProcessPass::new("bloom", shaders.bloom_combine)
+                    .with_uniform("baseSampler", PostProcessUniform::Retained)
+                    .with_uniform("intensity", PostProcessUniform::Float(intensity)),
+            )
+    }
+
+    pub fn with_vignette(self, intensity: f32, radius: f32, softness: f32) -> Self {
+        let pass = PostProcessPass::new("vignette", self.shaders.vignette)
+            .with_uniform("intensity", PostProcessUniform::Float(intensity))
+            .with_uniform("radius", PostProcessUniform::Float(radius))
+            .with_uniform("softness", PostProcessUniform::Float(softness));
+
+        self.with_pass(pass)
+    }
+
+    pub fn with_color_grading(self, lut_texture_id: usize, intensity: f32) -> Self {
+        let pass = PostProcessPass::new("color_grading", self.shaders.lut)
+            .with_uniform("lutSampler", PostProcessUniform::Texture(lut_texture_id))
+            .with_uniform("intensity", PostProcessUniform::Float(intensity));
+
+        self.with_pass(pass)
+    }
+
+    pub fn with_crt(self, curvature: f32, scanlines: f32) -> Self {
+        let pass = PostProcessPass::new("crt", self.shaders.crt)
+            .with_uniform("curvature", PostProcessUniform::Float(curvature))
+            .with_uniform("scanlines", PostProcessUniform::Float(scanlines));
+
+        self.with_pass(pass)
+    }
+
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        for pass in self.passes.iter_mut().filter(|p| p.name == name) {
+            pass.enabled = enabled;
+        }
+    }
+
+    pub fn set_uniform(&mut self, name: &str, uniform: &str, value: PostProcessUniform) {
+        for pass in self.passes.iter_mut().filter(|p| p.name == name) {
+            if let Some(entry) = pass.uniforms.iter_mut().find(|p| p.0 == uniform) {
+                entry.1 = value;
+            }
+        }
+    }
+
+    pub fn begin(&mut self, renderer: &mut RendererContext) {
+        for target in self.targets {
+            match renderer.textures.get_mut(target) {
+                Ok(texture) if texture.size != renderer.viewport_size => texture.resize(renderer.viewport_size),
+                Ok(_) => {}
+                Err(err) => error_return!("Failed to begin post-processing ({})", err),
+            }
+        }
+
+        renderer.set_target_texture(Some(self.targets[0]));
+        renderer.clear();
+    }
+
+    pub fn end(&mut self, renderer: &mut RendererContext) {
+        let camera_id = renderer.active_camera_id;
+        let sprite_shader_id = renderer.active_sprite_shader_id;
+        let passes = self.passes.iter().filter(|p| p.enabled).collect::<Vec<&PostProcessPass>>();
+
+        renderer.set_camera(renderer.default_camera_id);
+
+        if passes.is_empty() {
+            self.draw_pass(renderer, self.shaders.copy, &[], self.targets[0], None, None);
+        }
+
+        // Every pass reads the previous output, the retained input stays untouched until the end so later passes can combine with it
+        let mut input = self.targets[0];
+        let mut retained = None;
+
+        for (index, pass) in passes.iter().enumerate() {
+            if pass.retain_input {
+                retained = Some(input);
+            }
+
+            let output = if index == passes.len() - 1 { None } else { self.targets.iter().copied().find(|p| *p != input && Some(*p) != retained) };
+            self.draw_pass(renderer, pass.shader_id, &pass.uniforms, input, output, retained);
+
+            if let Some(output) = output {
+                input = output;
+            }
+        }
+
+        renderer.set_sprite_shader(Some(sprite_shader_id));
+        renderer.set_camera(camera_id);
+    }
+
+    fn draw_pass(
+        &self,
+        renderer: &mut RendererContext,
+        shader_id: usize,
+        uniforms: &[(String, PostProcessUniform)],
+        input: usize,
+        output: Option<usize>,
+        retained: Option<usize>,
+    ) {
+        renderer.set_target_texture(output);
+        renderer.set_sprite_shader(Some(shader_id));
+        renderer.clear();
+
+        let shader = match renderer.shaders.get(shader_id) {
+            Ok(shader) => shader,
+            Err(err) => error_return!("Failed to apply post-processing pass ({})", err),
+        };
+
+        shader.activate();
+
+        if shader.uniforms.contains_key("resolution") {
+            shader.set_uniform("resolution", renderer.viewport_size.as_ref().as_ptr());
+        }
+
+        let mut unit = 1;
+        for (name, value) in uniforms {
+            let texture_id = match value {
+                PostProcessUniform::Float(value) => {
+                    shader.set_uniform(name, value);
+                    continue;
+                }
+                PostProcessUniform::Vec2(value) => {
+                    shader.set_uniform(name, value.as_ref().as_ptr());
+                    continue;
+                }
+                PostProcessUniform::Vec4(value) => {
+                    shader.set_uniform(name, value.as_ref().as_ptr());
+                    continue;
+                }
+                PostProcessUniform::Texture(texture_id) => *texture_id,
+                PostProcessUniform::Retained => match retained {
+                    Some(texture_id) => texture_id,
+                    None => error_continue!("Uniform {} requires retained input, but no pass has retained it", name),
+                },
+            };
+
+            match renderer.textures.get(texture_id) {
+                Ok(texture) => texture.activate(unit),
+                Err(err) => error_continue!("Failed to bind texture for uniform {} ({})", name, err),
+            }
+
+            shader.set_uniform(name, &(unit as f32));
+            unit += 1;
+        }
+
+        unsafe {
+            renderer.gl.active_texture(glow::TEXTURE0);
+        }
+
+        // Program has been activated here to set uniforms, so the renderer has to upload its matrices again
+        renderer.selected_shader_id = usize::MAX;
+        renderer.draw_sprite(&Sprite {
+            size: Some(renderer.viewport_size),
+            anchor: Vec2::ZERO,
+            texture_id: TextureId::Some(input),
+            ..Default::default()
+        });
+        renderer.flush_buffer();
+    }
+}
+
+impl PostProcessPass {
+    pub fn new(name: &str, shader_id: usize) -> Self {
+        Self { name: name.to_string(), shader_id, uniforms: Vec::default(), retain_input: false, enabled: true }
+    }
+
+    pub fn new_blur(name: &str, shader_id: usize, direction: Vec2, radius: f32) -> Self {
+        Self::new(name, shader_id)
+            .with_uniform("direction", PostProcessUniform::Vec2(direction))
+            .with_uniform("radius", PostProcessUniform::Float(radius))
+    }
+
+    pub fn with_uniform(mut self, name: &str, value: PostProcessUniform) -> Self {
+        self.uniforms.push((name.to_string(), value));
+        self
+    }
+}
+
+fn get_shader(renderer: &mut RendererContext, name: &str, fragment_shader_source: &str) -> Result<usize> {
+    if let Ok(shader_id) = renderer.shaders.get_id(name) {
+        return Ok(shader_id);
+    }
+
+    let shader = Shader::new(renderer, name, POSTPROCESS_VERTEX_SHADER, fragment_shader_source)?;
+    renderer.shaders.store_with_name(name, shader)
+}
@@ -16,15 +16,28 @@ use std::slice;
 
 pub const SPRITE_VERTEX_SHADER: &str = include_str!("./shaders/sprite.vert");
 pub const SPRITE_FRAGMENT_SHADER: &str = include_str!("./shaders/sprite.frag");
+pub const SPRITE_TEXTURE_UNITS: usize = 8;
 
 pub const SHAPE_VERTEX_SHADER: &str = include_str!("./shaders/shape.vert");
 pub const SHAPE_FRAGMENT_SHADER: &str = include_str!("./shaders/shape.frag");
 
+pub const TEXT_FRAGMENT_SHADER: &str = include_str!("./shaders/text.frag");
+
+pub const POSTPROCESS_VERTEX_SHADER: &str = include_str!("./shaders/postprocess.vert");
+pub const POSTPROCESS_COPY_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_copy.frag");
+pub const POSTPROCESS_BLUR_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_blur.frag");
+pub const POSTPROCESS_BLOOM_EXTRACT_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_bloom_extract.frag");
+pub const POSTPROCESS_BLOOM_COMBINE_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_bloom_combine.frag");
+pub const POSTPROCESS_VIGNETTE_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_vignette.frag");
+pub const POSTPROCESS_LUT_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_lut.frag");
+pub const POSTPROCESS_CRT_FRAGMENT_SHADER: &str = include_str!("./shaders/postprocess_crt.frag");
+
 #[derive(Debug)]
 pub struct Shader {
     pub name: String,
     pub program: Program,
     pub uniforms: FxHashMap<String, ShaderParameter>,
+    pub texture_units: usize,
 
     gl: Rc<Context>,
 }
@@ -97,7 +110,8 @@ impl Shader {
                 }
             }
 
-            Ok(Shader { name: name.to_string(), program, uniforms, gl })
+            let texture_units = (0..SPRITE_TEXTURE_UNITS).take_while(|p| uniforms.contains_key(&format!("textureSamplers[{}]", p))).count();
+            Ok(Shader { name: name.to_string(), program, uniforms, texture_units, gl })
         }
     }
 
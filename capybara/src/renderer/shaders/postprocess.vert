@@ -0,0 +1,30 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+uniform mat4 view;
+uniform mat4 proj;
+
+layout (location = 0) in vec2 offset;
+layout (location = 1) in vec2 anchor;
+layout (location = 2) in float rotation;
+layout (location = 3) in vec2 size;
+layout (location = 4) in uvec4 color;
+layout (location = 5) in vec4 uv;
+
+out vec4 vertexColor;
+out vec2 vertexUv;
+
+void main()
+{
+    vec2 position = vec2(((gl_VertexID + 1) & 3) >> 1, (gl_VertexID & 3) >> 1);
+    vec2 p_anch = position - anchor;
+
+    gl_Position = proj * view * vec4(p_anch * size + offset, 0.0, 1.0);
+
+    // Render targets are stored from the bottom row, so unlike regular sprites the coordinates aren't flipped
+    vertexColor = vec4(color) / 255.0;
+    vertexUv = uv.xy + uv.zw * position;
+}
\ No newline at end of file
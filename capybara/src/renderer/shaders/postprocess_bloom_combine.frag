@@ -0,0 +1,21 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform sampler2D baseSampler;
+uniform float intensity;
+
+void main()
+{
+    vec4 base = texture(baseSampler, vertexUv);
+    vec4 bloom = texture(textureSampler, vertexUv);
+
+    fragmentColor = vec4(base.rgb + bloom.rgb * intensity, base.a) * vertexColor;
+}
\ No newline at end of file
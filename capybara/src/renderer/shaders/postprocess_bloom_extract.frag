@@ -0,0 +1,21 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform float threshold;
+
+void main()
+{
+    vec4 color = texture(textureSampler, vertexUv);
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - threshold, 0.0) / max(brightness, 0.0001);
+
+    fragmentColor = vec4(color.rgb * contribution, 1.0) * vertexColor;
+}
\ No newline at end of file
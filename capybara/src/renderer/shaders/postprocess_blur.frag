@@ -0,0 +1,30 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform vec2 resolution;
+uniform vec2 direction;
+uniform float radius;
+
+const float weights[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main()
+{
+    vec2 offset = direction * radius / (4.0 * resolution);
+    vec4 color = texture(textureSampler, vertexUv) * weights[0];
+
+    for (int i = 1; i < 5; i++)
+    {
+        color += texture(textureSampler, vertexUv + offset * float(i)) * weights[i];
+        color += texture(textureSampler, vertexUv - offset * float(i)) * weights[i];
+    }
+
+    fragmentColor = color * vertexColor;
+}
\ No newline at end of file
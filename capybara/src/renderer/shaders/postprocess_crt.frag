@@ -0,0 +1,38 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform vec2 resolution;
+uniform float curvature;
+uniform float scanlines;
+
+void main()
+{
+    vec2 uv = vertexUv;
+
+    if (curvature > 0.0)
+    {
+        vec2 centered = uv * 2.0 - 1.0;
+        vec2 offset = centered.yx / curvature;
+
+        uv = (centered + centered * offset * offset) * 0.5 + 0.5;
+    }
+
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0)
+    {
+        fragmentColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    vec4 color = texture(textureSampler, uv);
+    float scanline = sin(uv.y * resolution.y * 3.14159265) * 0.5 + 0.5;
+
+    fragmentColor = vec4(color.rgb * (1.0 - scanlines * (1.0 - scanline)), color.a) * vertexColor;
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform sampler2D lutSampler;
+uniform float intensity;
+
+vec3 fromLinear(vec3 rgb)
+{
+    vec3 a = 12.92 * rgb;
+    vec3 b = 1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055;
+    vec3 c = step(vec3(0.0031308), rgb);
+
+    return mix(a, b, c);
+}
+
+// Table is a 256x16 strip of 16 slices, red grows along X, green along Y and blue selects the slice
+vec3 sampleLut(vec3 color, float slice)
+{
+    vec2 uv = vec2((slice * 16.0 + color.r * 15.0 + 0.5) / 256.0, (color.g * 15.0 + 0.5) / 16.0);
+    return fromLinear(textureLod(lutSampler, uv, 0.0).rgb);
+}
+
+void main()
+{
+    vec4 color = texture(textureSampler, vertexUv);
+    vec3 straight = clamp(color.rgb / max(color.a, 0.0001), 0.0, 1.0);
+
+    float blue = straight.b * 15.0;
+    float slice = floor(blue);
+    vec3 graded = mix(sampleLut(straight, slice), sampleLut(straight, min(slice + 1.0, 15.0)), blue - slice);
+
+    fragmentColor = vec4(mix(straight, graded, intensity) * color.a, color.a) * vertexColor;
+}
\ No newline at end of file
@@ -0,0 +1,22 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform float intensity;
+uniform float radius;
+uniform float softness;
+
+void main()
+{
+    vec4 color = texture(textureSampler, vertexUv);
+    float vignette = smoothstep(radius, radius - softness, distance(vertexUv, vec2(0.5)));
+
+    fragmentColor = vec4(color.rgb * mix(1.0, vignette, intensity), color.a) * vertexColor;
+}
\ No newline at end of file
@@ -6,9 +6,10 @@ precision mediump float;
 
 in vec2 vertexUv;
 in vec4 vertexColor;
+flat in uint vertexTextureIndex;
 out vec4 fragmentColor;
 
-uniform sampler2D textureSampler;
+uniform sampler2D textureSamplers[8];
 
 vec3 fromLinear(vec3 rgb)
 {
@@ -24,7 +25,23 @@ vec4 fromLinear(vec4 rgba)
     return vec4(fromLinear(rgba.rgb), rgba.a);
 }
 
+// Samplers can be indexed only by constant expressions in GLSL ES, so every unit needs its own branch
+vec4 sampleTexture(uint index, vec2 uv)
+{
+    switch (index)
+    {
+        case 1u: return texture(textureSamplers[1], uv);
+        case 2u: return texture(textureSamplers[2], uv);
+        case 3u: return texture(textureSamplers[3], uv);
+        case 4u: return texture(textureSamplers[4], uv);
+        case 5u: return texture(textureSamplers[5], uv);
+        case 6u: return texture(textureSamplers[6], uv);
+        case 7u: return texture(textureSamplers[7], uv);
+        default: return texture(textureSamplers[0], uv);
+    }
+}
+
 void main()
 {
-    fragmentColor = fromLinear(texture(textureSampler, vertexUv)) * vertexColor;
+    fragmentColor = fromLinear(sampleTexture(vertexTextureIndex, vertexUv)) * vertexColor;
 }
\ No newline at end of file
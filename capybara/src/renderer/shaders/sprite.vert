@@ -13,9 +13,11 @@ layout (location = 2) in float rotation;
 layout (location = 3) in vec2 size;
 layout (location = 4) in uvec4 color;
 layout (location = 5) in vec4 uv;
+layout (location = 6) in uint textureIndex;
 
 out vec4 vertexColor;
 out vec2 vertexUv;
+flat out uint vertexTextureIndex;
 
 void main()
 {
@@ -34,4 +36,5 @@ void main()
 
     vertexColor = vec4(color) / 255.0;
     vertexUv = uv.xy + uv.zw * vec2(position.x, 1.0 - position.y);
+    vertexTextureIndex = textureIndex;
 }
\ No newline at end of file
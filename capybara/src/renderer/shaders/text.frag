@@ -0,0 +1,27 @@
+#version <version>
+
+#ifdef GL_ES
+precision mediump float;
+#endif
+
+in vec2 vertexUv;
+in vec4 vertexColor;
+out vec4 fragmentColor;
+
+uniform sampler2D textureSampler;
+uniform vec4 outlineColor;
+uniform float outlineThreshold;
+
+void main()
+{
+    float distance = texture(textureSampler, vertexUv).r;
+    float width = max(fwidth(distance), 0.0001) * 0.5;
+
+    float fill = smoothstep(0.5 - width, 0.5 + width, distance);
+    float outline = smoothstep(outlineThreshold - width, outlineThreshold + width, distance);
+
+    vec4 fillColor = vec4(vertexColor.rgb * vertexColor.a, vertexColor.a) * fill;
+    vec4 outlineColorPremultiplied = vec4(outlineColor.rgb * outlineColor.a, outlineColor.a) * outline;
+
+    fragmentColor = fillColor + outlineColorPremultiplied * (1.0 - fillColor.a);
+}
\ No newline at end of file
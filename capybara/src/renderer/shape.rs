@@ -15,6 +15,8 @@ pub struct Shape {
     pub texture_id: TextureId,
     pub apply_model: bool,
     pub rounded_coordinates: bool,
+    pub layer: i32,
+    pub z: f32,
 
     pub vertices: Vec<ShapeVertex>,
     pub indices: Vec<u32>,
@@ -37,6 +39,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices: Vec::default(),
             indices: Vec::default(),
@@ -67,6 +71,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices,
             indices: vec![0, 1, 2, 0, 2, 3],
@@ -95,6 +101,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices,
             indices: vec![0, 1, 2, 0, 2, 3],
@@ -132,6 +140,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices,
             indices: vec![0, 2, 1, 2, 3, 1, 2, 4, 5, 2, 5, 3, 4, 6, 5, 6, 7, 5, 6, 0, 1, 6, 1, 7],
@@ -173,6 +183,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices,
             indices,
@@ -216,6 +228,8 @@ impl Shape {
             texture_id: TextureId::Default,
             apply_model: true,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             vertices,
             indices,
@@ -245,6 +259,21 @@ impl Shape {
         edges
     }
 
+    pub fn get_bounds(&self) -> (Vec2, Vec2) {
+        let model = self.get_model();
+        let mut min = Vec2::MAX;
+        let mut max = Vec2::MIN;
+
+        for vertex in &self.vertices {
+            let position = if self.apply_model { model.transform_point3(vertex.position.extend(0.0)).truncate() } else { vertex.position };
+
+            min = min.min(position);
+            max = max.max(position);
+        }
+
+        (min, max)
+    }
+
     pub fn get_model(&self) -> Mat4 {
         let translation = Mat4::from_translation(Vec3::new(self.position.x, self.position.y, 0.0));
         let rotation = Mat4::from_rotation_z(self.rotation);
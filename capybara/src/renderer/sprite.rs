@@ -15,6 +15,8 @@ pub struct Sprite {
     pub texture_id: TextureId,
     pub texture_type: TextureType,
     pub rounded_coordinates: bool,
+    pub layer: i32,
+    pub z: f32,
 
     pub animation_frame: i32,
     pub animation_speed: f32,
@@ -33,9 +35,10 @@ pub struct SpriteVertex {
     pub color: u32,
     pub uv_position: Vec2,
     pub uv_size: Vec2,
+    pub texture_index: u32,
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TextureId {
     #[default]
     Default,
@@ -68,6 +71,8 @@ impl Sprite {
             texture_id: TextureId::Default,
             texture_type: TextureType::Simple,
             rounded_coordinates: false,
+            layer: 0,
+            z: 0.0,
 
             animation_frame: 0,
             animation_speed: 1.0,
@@ -78,7 +83,12 @@ impl Sprite {
     }
 
     pub fn get_edges(&self, texture_size: Vec2) -> ArrayVec<Edge, 4> {
-        let size = self.size.unwrap_or(texture_size) * self.scale;
+        let [a, b, c, d] = self.get_corners(self.size.unwrap_or(texture_size));
+        ArrayVec::from([Edge::new(a, b), Edge::new(d, c), Edge::new(a, d), Edge::new(b, c)])
+    }
+
+    pub fn get_corners(&self, size: Vec2) -> [Vec2; 4] {
+        let size = size * self.scale;
         let a = Vec2::ZERO - size * self.anchor;
         let b = Vec2::new(size.x, 0.0) - size * self.anchor;
         let c = Vec2::new(size.x, size.y) - size * self.anchor;
@@ -92,7 +102,15 @@ impl Sprite {
         let c = Vec2::new(c.x * cos - c.y * sin, c.y * cos + c.x * sin) + self.position;
         let d = Vec2::new(d.x * cos - d.y * sin, d.y * cos + d.x * sin) + self.position;
 
-        ArrayVec::from([Edge::new(a, b), Edge::new(d, c), Edge::new(a, d), Edge::new(b, c)])
+        [a, b, c, d]
+    }
+
+    pub fn get_bounds(&self, size: Vec2) -> (Vec2, Vec2) {
+        let corners = self.get_corners(size);
+        let min = corners.iter().fold(Vec2::MAX, |acc, p| acc.min(*p));
+        let max = corners.iter().fold(Vec2::MIN, |acc, p| acc.max(*p));
+
+        (min, max)
     }
 
     pub fn is_animation(&self) -> bool {
@@ -0,0 +1,188 @@
+use super::font::Font;
+use glam::Vec2;
+use glam::Vec4;
+
+#[derive(Clone, Debug)]
+pub struct Text {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub anchor: Vec2,
+    pub font_id: usize,
+    pub text: String,
+    pub size: Option<f32>,
+    pub color: Vec4,
+    pub outline_color: Vec4,
+    pub outline_width: f32,
+    pub alignment: TextAlignment,
+    pub max_width: Option<f32>,
+    pub line_spacing: f32,
+    pub kerning: bool,
+    pub layer: i32,
+    pub z: f32,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextGlyph {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub source_position: Vec2,
+    pub source_size: Vec2,
+    pub page: usize,
+}
+
+impl Text {
+    pub fn new(font_id: usize, text: &str) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            rotation: 0.0,
+            anchor: Vec2::new(0.5, 0.5),
+            font_id,
+            text: text.to_string(),
+            size: None,
+            color: Vec4::new(1.0, 1.0, 1.0, 1.0),
+            outline_color: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            outline_width: 0.0,
+            alignment: TextAlignment::Left,
+            max_width: None,
+            line_spacing: 1.0,
+            kerning: true,
+            layer: 0,
+            z: 0.0,
+        }
+    }
+
+    pub fn get_scale(&self, font: &Font) -> f32 {
+        self.size.map(|p| p / font.size).unwrap_or(1.0)
+    }
+
+    pub fn get_lines(&self, font: &Font) -> Vec<(Vec<char>, f32)> {
+        let scale = self.get_scale(font);
+        let measure = |chars: &[char]| {
+            let mut width = 0.0;
+            for (i, char) in chars.iter().enumerate() {
+                if let Some(glyph) = font.glyphs.get(char) {
+                    width += glyph.advance * scale;
+                }
+
+                if self.kerning && i > 0 {
+                    width += font.get_kerning(chars[i - 1], *char) * scale;
+                }
+            }
+
+            width
+        };
+        let fits = |chars: &[char]| self.max_width.map(|p| measure(chars) <= p).unwrap_or(true);
+
+        let mut lines = Vec::default();
+        for paragraph in self.text.split('\n') {
+            let mut line = Vec::<char>::default();
+
+            for word in paragraph.split(' ') {
+                let mut candidate = line.clone();
+                if !line.is_empty() {
+                    candidate.push(' ');
+                }
+                candidate.extend(word.chars());
+
+                if fits(&candidate) || line.is_empty() {
+                    line = candidate;
+                } else {
+                    lines.push(line);
+                    line = word.chars().collect();
+                }
+
+                // Words longer than the whole line are broken at the last character which still fits
+                while !fits(&line) && line.len() > 1 {
+                    let length = (1..line.len()).rev().find(|p| fits(&line[..*p])).unwrap_or(1);
+                    let rest = line.split_off(length);
+
+                    lines.push(line);
+                    line = rest;
+                }
+            }
+
+            lines.push(line);
+        }
+
+        lines
+            .into_iter()
+            .map(|p| {
+                let width = measure(&p);
+                (p, width)
+            })
+            .collect()
+    }
+
+    pub fn get_size(&self, font: &Font) -> Vec2 {
+        let lines = self.get_lines(font);
+        self.get_block_size(font, &lines)
+    }
+
+    pub fn layout(&self, font: &Font) -> Vec<TextGlyph> {
+        let scale = self.get_scale(font);
+        let lines = self.get_lines(font);
+        let size = self.get_block_size(font, &lines);
+        let origin = Vec2::new(size.x * self.anchor.x, size.y * self.anchor.y - size.y);
+        let rotation = Vec2::from_angle(self.rotation);
+        let mut glyphs = Vec::default();
+
+        for (index, (chars, width)) in lines.iter().enumerate() {
+            let top = -(index as f32) * font.line_height * scale * self.line_spacing;
+            let mut x = match self.alignment {
+                TextAlignment::Left => 0.0,
+                TextAlignment::Center => (size.x - width) / 2.0,
+                TextAlignment::Right => size.x - width,
+            };
+
+            for (i, char) in chars.iter().enumerate() {
+                let glyph = match font.glyphs.get(char) {
+                    Some(glyph) => glyph,
+                    None => continue,
+                };
+
+                if self.kerning && i > 0 {
+                    x += font.get_kerning(chars[i - 1], *char) * scale;
+                }
+
+                if glyph.size.x > 0.0 && glyph.size.y > 0.0 {
+                    // Glyph offsets are measured from the top of the line with Y axis pointing down, world has it pointing up
+                    let local = Vec2::new(x + glyph.offset.x * scale, top - glyph.offset.y * scale) - origin;
+
+                    glyphs.push(TextGlyph {
+                        position: self.position + rotation.rotate(local),
+                        size: glyph.size * scale,
+                        source_position: glyph.position,
+                        source_size: glyph.size,
+                        page: glyph.page,
+                    });
+                }
+
+                x += glyph.advance * scale;
+            }
+        }
+
+        glyphs
+    }
+
+    fn get_block_size(&self, font: &Font, lines: &[(Vec<char>, f32)]) -> Vec2 {
+        let scale = self.get_scale(font);
+        let width = lines.iter().map(|p| p.1).fold(0.0, f32::max);
+        let height = (lines.len().max(1) - 1) as f32 * font.line_height * scale * self.line_spacing + font.line_height * scale;
+
+        Vec2::new(width, height)
+    }
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self::new(0, "")
+    }
+}
@@ -1,19 +1,24 @@
 use super::context::RendererContext;
 use crate::assets::RawTexture;
+use crate::assets::RawTextureFormat;
+use anyhow::bail;
 use anyhow::Error;
 use anyhow::Result;
 use glam::Vec2;
 use glow::Context;
 use glow::HasContext;
 use log::debug;
+use log::error;
 use log::info;
 use rustc_hash::FxHashMap;
+use std::borrow::Cow;
 use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Texture {
     pub name: String,
     pub size: Vec2,
+    pub format: RawTextureFormat,
     pub inner: glow::Texture,
     pub kind: TextureKind,
     pub filtering_min: TextureFilterMin,
@@ -59,6 +64,10 @@ impl Texture {
         unsafe {
             info!("Creating texture {} ({}x{}, {} bytes)", raw.name, raw.size.x, raw.size.y, raw.data.len());
 
+            if !renderer.is_texture_format_supported(raw.format) {
+                bail!("Texture format {:?} is not supported by the GPU", raw.format);
+            }
+
             let gl = renderer.gl.clone();
             let inner = gl.create_texture().map_err(Error::msg)?;
             let data = if !raw.data.is_empty() { Some(&raw.data) } else { None };
@@ -68,21 +77,52 @@ impl Texture {
             gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
             gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::NEAREST as i32);
             gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::NEAREST as i32);
-            gl.tex_image_2d(
-                glow::TEXTURE_2D,
-                0,
-                glow::SRGB8_ALPHA8 as i32,
-                raw.size.x as i32,
-                raw.size.y as i32,
-                0,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
-                data.map(|p| p.as_slice()),
-            );
+
+            if raw.format.is_compressed() {
+                if raw.data.is_empty() {
+                    bail!("Compressed texture {} has no data", raw.name);
+                }
+
+                // Mipmaps of compressed textures can't be generated, so sampling is limited to the levels provided by the file
+                let internal_format = get_compressed_format(raw.format) as i32;
+                for (level, image) in [&raw.data].into_iter().chain(raw.mipmaps.iter()).enumerate() {
+                    let width = (raw.size.x as i32 >> level).max(1);
+                    let height = (raw.size.y as i32 >> level).max(1);
+
+                    gl.compressed_tex_image_2d(glow::TEXTURE_2D, level as i32, internal_format, width, height, 0, image.len() as i32, image);
+                }
+
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, raw.mipmaps.len() as i32);
+            } else {
+                let (internal_format, format) = get_uncompressed_format(raw.format);
+
+                gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    internal_format as i32,
+                    raw.size.x as i32,
+                    raw.size.y as i32,
+                    0,
+                    format,
+                    glow::UNSIGNED_BYTE,
+                    data.map(|p| get_upload_data(raw.format, p)).as_deref(),
+                );
+                gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+
+                // Single-channel textures are sampled as grayscale, so red is copied to the other channels and alpha is opaque
+                #[cfg(not(web))]
+                if raw.format == RawTextureFormat::R8 {
+                    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_SWIZZLE_G, glow::RED as i32);
+                    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_SWIZZLE_B, glow::RED as i32);
+                    gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_SWIZZLE_A, glow::ONE as i32);
+                }
+            }
 
             Ok(Self {
                 name: raw.name.to_string(),
                 size: raw.size,
+                format: raw.format,
                 inner,
                 kind: TextureKind::Simple,
                 filtering_min: TextureFilterMin::Nearest,
@@ -98,7 +138,15 @@ impl Texture {
             // debug! instead of info! because it was too chatty
             debug!("Updating texture {} ({}x{}, {} bytes)", self.name, size.x, size.y, data.len());
 
+            if self.format.is_compressed() {
+                error!("Compressed texture {} can't be updated", self.name);
+                return;
+            }
+
+            let (_, format) = get_uncompressed_format(self.format);
+
             self.gl.bind_texture(glow::TEXTURE_2D, Some(self.inner));
+            self.gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
             self.gl.tex_sub_image_2d(
                 glow::TEXTURE_2D,
                 0,
@@ -106,10 +154,11 @@ impl Texture {
                 position.y as i32,
                 size.x as i32,
                 size.y as i32,
-                glow::RGBA,
+                format,
                 glow::UNSIGNED_BYTE,
-                glow::PixelUnpackData::Slice(data),
+                glow::PixelUnpackData::Slice(&get_upload_data(self.format, data)),
             );
+            self.gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
 
             if self.filtering_min == TextureFilterMin::LinearMipmap || self.filtering_min == TextureFilterMin::NearestMipmap {
                 self.gl.generate_mipmap(glow::TEXTURE_2D);
@@ -121,18 +170,16 @@ impl Texture {
         unsafe {
             info!("Resizing texture {} ({}x{})", self.name, size.x, size.y);
 
+            if self.format.is_compressed() {
+                error!("Compressed texture {} can't be resized", self.name);
+                return;
+            }
+
+            let (internal_format, format) = get_uncompressed_format(self.format);
+
             self.gl.bind_texture(glow::TEXTURE_2D, Some(self.inner));
-            self.gl.tex_image_2d(
-                glow::TEXTURE_2D,
-                0,
-                glow::SRGB8_ALPHA8 as i32,
-                size.x as i32,
-                size.y as i32,
-                0,
-                glow::RGBA,
-                glow::UNSIGNED_BYTE,
-                None,
-            );
+            self.gl
+                .tex_image_2d(glow::TEXTURE_2D, 0, internal_format as i32, size.x as i32, size.y as i32, 0, format, glow::UNSIGNED_BYTE, None);
 
             if self.filtering_min == TextureFilterMin::LinearMipmap || self.filtering_min == TextureFilterMin::NearestMipmap {
                 self.gl.generate_mipmap(glow::TEXTURE_2D);
@@ -162,7 +209,7 @@ impl Texture {
             self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, minification_value);
             self.gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, magnification_value);
 
-            if minification == TextureFilterMin::LinearMipmap || minification == TextureFilterMin::NearestMipmap {
+            if (minification == TextureFilterMin::LinearMipmap || minification == TextureFilterMin::NearestMipmap) && !self.format.is_compressed() {
                 self.gl.generate_mipmap(glow::TEXTURE_2D);
             }
         }
@@ -213,3 +260,34 @@ impl AtlasEntity {
         Self { position, size }
     }
 }
+
+fn get_uncompressed_format(format: RawTextureFormat) -> (u32, u32) {
+    match format {
+        #[cfg(not(web))]
+        RawTextureFormat::R8 => (glow::R8, glow::RED),
+        #[cfg(web)]
+        RawTextureFormat::R8 => (glow::RGBA8, glow::RGBA),
+        _ => (glow::SRGB8_ALPHA8, glow::RGBA),
+    }
+}
+
+// WebGL doesn't support swizzling, so single-channel data is expanded to grayscale RGBA before the upload
+fn get_upload_data(format: RawTextureFormat, data: &[u8]) -> Cow<'_, [u8]> {
+    match format {
+        #[cfg(web)]
+        RawTextureFormat::R8 => Cow::Owned(data.iter().flat_map(|p| [*p, *p, *p, 255]).collect()),
+        _ => Cow::Borrowed(data),
+    }
+}
+
+fn get_compressed_format(format: RawTextureFormat) -> u32 {
+    match format {
+        RawTextureFormat::Bc1 => glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT,
+        RawTextureFormat::Bc3 => glow::COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
+        RawTextureFormat::Bc7 => glow::COMPRESSED_SRGB_ALPHA_BPTC_UNORM,
+        RawTextureFormat::Etc2Rgb8 => glow::COMPRESSED_SRGB8_ETC2,
+        RawTextureFormat::Etc2Rgba8 => glow::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC,
+        RawTextureFormat::Astc4x4 => glow::COMPRESSED_SRGB8_ALPHA8_ASTC_4x4_KHR,
+        _ => glow::SRGB8_ALPHA8,
+    }
+}
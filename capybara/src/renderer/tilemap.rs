@@ -0,0 +1,254 @@
+use super::context::RendererContext;
+use super::sprite::SpriteVertex;
+use super::sprite::TextureId;
+use crate::assets::ldtk::LdtkLayer;
+use crate::assets::ldtk::LdtkTilemap;
+use crate::assets::tiled::TiledLayer;
+use crate::assets::tiled::TiledMap;
+use crate::utils::color::Vec4Utils;
+use anyhow::Error;
+use anyhow::Result;
+use glam::IVec2;
+use glam::Vec2;
+use glam::Vec4;
+use glow::Buffer;
+use glow::Context;
+use glow::HasContext;
+use glow::VertexArray;
+use rustc_hash::FxHashMap;
+use std::f32::consts;
+use std::mem;
+use std::rc::Rc;
+use std::slice;
+
+#[derive(Debug)]
+pub struct Tilemap {
+    pub position: Vec2,
+    pub texture_id: TextureId,
+    pub chunk_size: Vec2,
+    pub visible: bool,
+
+    tiles: Vec<TilemapTile>,
+    chunks: Vec<TilemapChunk>,
+    dirty: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TilemapTile {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub source: Vec2,
+    pub rotation: f32,
+    pub color: Vec4,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+#[derive(Debug)]
+pub struct TilemapChunk {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub tiles_count: usize,
+
+    vao: VertexArray,
+    vbo: Buffer,
+    gl: Rc<Context>,
+}
+
+impl Tilemap {
+    pub fn new(texture_id: TextureId) -> Self {
+        Self {
+            position: Vec2::ZERO,
+            texture_id,
+            chunk_size: Vec2::new(512.0, 512.0),
+            visible: true,
+
+            tiles: Vec::default(),
+            chunks: Vec::default(),
+            dirty: true,
+        }
+    }
+
+    pub fn new_from_ldtk(layer: &LdtkLayer, tilemap: &LdtkTilemap, texture_id: usize) -> Self {
+        let color = Vec4::new(1.0, 1.0, 1.0, layer.opacity);
+        let tiles = layer
+            .tiles
+            .iter()
+            .map(|p| TilemapTile {
+                position: p.position,
+                size: tilemap.tile_size,
+                source: p.source,
+                rotation: 0.0,
+                color,
+                flip_x: p.flip_x,
+                flip_y: p.flip_y,
+            })
+            .collect();
+
+        Self { position: layer.offset, visible: layer.visible, ..Self::new(TextureId::Some(texture_id)) }.with_tiles(tiles)
+    }
+
+    pub fn new_from_tiled(map: &TiledMap, layer: &TiledLayer, tileset_id: usize, texture_id: usize) -> Self {
+        let color = Vec4::new(1.0, 1.0, 1.0, layer.opacity);
+        let tile_size = map.tilesets.get(tileset_id).map(|p| p.tile_size).unwrap_or(map.tile_size);
+        let tiles = layer
+            .tiles
+            .iter()
+            .filter(|p| p.tileset_id == tileset_id)
+            .map(|p| {
+                // Diagonal flip is a transposition, which in the bottom-up space equals a quarter turn of the tile with swapped flips
+                let (rotation, flip_x, flip_y) = if p.flip_diagonal { (consts::FRAC_PI_2, !p.flip_y, p.flip_x) } else { (0.0, p.flip_x, p.flip_y) };
+                TilemapTile { position: p.position, size: tile_size, source: p.source, rotation, color, flip_x, flip_y }
+            })
+            .collect();
+
+        Self { position: layer.offset, visible: layer.visible, ..Self::new(TextureId::Some(texture_id)) }.with_tiles(tiles)
+    }
+
+    pub fn with_tiles(mut self, tiles: Vec<TilemapTile>) -> Self {
+        self.set_tiles(tiles);
+        self
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: Vec2) -> Self {
+        self.chunk_size = chunk_size;
+        self.dirty = true;
+        self
+    }
+
+    pub fn get_tiles(&self) -> &[TilemapTile] {
+        &self.tiles
+    }
+
+    pub fn set_tiles(&mut self, tiles: Vec<TilemapTile>) {
+        self.tiles = tiles;
+        self.dirty = true;
+    }
+
+    pub fn add_tile(&mut self, tile: TilemapTile) {
+        self.tiles.push(tile);
+        self.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+        self.dirty = true;
+    }
+
+    pub fn set_color(&mut self, color: Vec4) {
+        for tile in &mut self.tiles {
+            tile.color = color;
+        }
+
+        self.dirty = true;
+    }
+
+    pub fn get_chunks(&self) -> &[TilemapChunk] {
+        &self.chunks
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn build(&mut self, renderer: &RendererContext) -> Result<()> {
+        let texture_size = match self.texture_id {
+            TextureId::Some(texture_id) => renderer.textures.get(texture_id)?.size,
+            _ => Vec2::new(1.0, 1.0),
+        };
+
+        let mut groups = FxHashMap::<IVec2, (Vec2, Vec2, Vec<SpriteVertex>)>::default();
+        for tile in &self.tiles {
+            let key = (tile.position / self.chunk_size).floor().as_ivec2();
+            let (min, max, vertices) = groups.entry(key).or_insert_with(|| (Vec2::MAX, Vec2::MIN, Vec::default()));
+
+            // Rotated tiles stay within the circle around their center, so the bounds are extended by the half of diagonal
+            let center = tile.position + tile.size / 2.0;
+            let radius = tile.size.length() / 2.0;
+            *min = min.min(center - radius);
+            *max = max.max(center + radius);
+
+            let mut uv_position = tile.source / texture_size;
+            let mut uv_size = tile.size / texture_size;
+
+            if tile.flip_x {
+                uv_position.x += uv_size.x;
+                uv_size.x = -uv_size.x;
+            }
+
+            if tile.flip_y {
+                uv_position.y += uv_size.y;
+                uv_size.y = -uv_size.y;
+            }
+
+            vertices.push(SpriteVertex {
+                position: center,
+                anchor: Vec2::new(0.5, 0.5),
+                rotation: tile.rotation,
+                size: tile.size,
+                color: tile.color.to_rgb_packed(),
+                uv_position,
+                uv_size,
+                texture_index: 0,
+            });
+        }
+
+        self.chunks.clear();
+
+        for (_, (min, max, vertices)) in groups {
+            self.chunks.push(TilemapChunk::new(renderer, min, max, &vertices)?);
+        }
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl Default for Tilemap {
+    fn default() -> Self {
+        Self::new(TextureId::Default)
+    }
+}
+
+impl TilemapTile {
+    pub fn new(position: Vec2, size: Vec2, source: Vec2) -> Self {
+        Self { position, size, source, rotation: 0.0, color: Vec4::new(1.0, 1.0, 1.0, 1.0), flip_x: false, flip_y: false }
+    }
+}
+
+impl TilemapChunk {
+    pub fn new(renderer: &RendererContext, min: Vec2, max: Vec2, vertices: &[SpriteVertex]) -> Result<Self> {
+        unsafe {
+            let gl = renderer.gl.clone();
+            let vbo = gl.create_buffer().map_err(Error::msg)?;
+            let vao = renderer.create_sprite_vertex_array(vbo)?;
+
+            let vertices_u8 = slice::from_raw_parts(vertices.as_ptr() as *const u8, mem::size_of_val(vertices));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, vertices_u8, glow::STATIC_DRAW);
+            gl.bind_vertex_array(None);
+
+            Ok(Self { min, max, tiles_count: vertices.len(), vao, vbo, gl })
+        }
+    }
+
+    pub fn is_visible(&self, offset: Vec2, camera_min: Vec2, camera_max: Vec2) -> bool {
+        let min = self.min + offset;
+        let max = self.max + offset;
+
+        !(min.x > camera_max.x || min.y > camera_max.y || max.x < camera_min.x || max.y < camera_min.y)
+    }
+
+    pub fn get_vertex_array(&self) -> VertexArray {
+        self.vao
+    }
+}
+
+impl Drop for TilemapChunk {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_vertex_array(self.vao);
+            self.gl.delete_buffer(self.vbo);
+        }
+    }
+}
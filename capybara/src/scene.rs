@@ -1,24 +1,45 @@
 use crate::app::ApplicationState;
+use crate::transition::Transition;
 use crate::window::InputEvent;
 use anyhow::Result;
-use egui::FullOutput;
-use egui::RawInput;
+use egui::Context;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum FrameCommand {
     ChangeScene { name: String },
+    TransitionScene { name: String, transition: Transition },
+    PushScene { name: String },
+    PopScene,
     ResetScene,
     Exit,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SceneFlags {
+    /// Scene below keeps calling `frame` (with zero delta if it's not updated) and `ui`, so it stays visible.
+    pub render_below: bool,
+    /// Scene below keeps calling `fixed` and gets the real delta in `frame`.
+    pub update_below: bool,
+    /// Input events are passed to the scene below after being processed by this one.
+    pub input_passthrough: bool,
+}
+
 pub trait Scene<G> {
     fn activation(&mut self, state: ApplicationState<G>) -> Result<()>;
     fn deactivation(&mut self, state: ApplicationState<G>) -> Result<()>;
 
     fn input(&mut self, state: ApplicationState<G>, event: InputEvent) -> Result<()>;
     fn fixed(&mut self, state: ApplicationState<G>) -> Result<Option<FrameCommand>>;
-    fn frame(&mut self, state: ApplicationState<G>, accumulator: f32, delta: f32) -> Result<Option<FrameCommand>>;
-    fn ui(&mut self, state: ApplicationState<G>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)>;
+    fn frame(&mut self, state: ApplicationState<G>, alpha: f32, delta: f32) -> Result<Option<FrameCommand>>;
+    fn ui(&mut self, state: ApplicationState<G>, context: &Context) -> Result<Option<FrameCommand>>;
 
     fn reset(&self) -> Box<dyn Scene<G>>;
+
+    fn flags(&self) -> SceneFlags {
+        SceneFlags::default()
+    }
+
+    fn assets(&self) -> Vec<String> {
+        Vec::default()
+    }
 }
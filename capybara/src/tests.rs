@@ -0,0 +1,348 @@
+use crate::app::ApplicationContext;
+use crate::app::ApplicationState;
+use crate::assets::loader::AssetsLoader;
+use crate::assets::RawShader;
+use crate::assets::RawShaderKind;
+use crate::assets::RawTexture;
+use crate::clock::Clock;
+use crate::clock::TimeSource;
+use crate::clock::DEFAULT_MAX_DELTA;
+use crate::clock::DEFAULT_TIMESTEP;
+use crate::renderer::context::SortingMode;
+use crate::renderer::context::VirtualScaling;
+use crate::renderer::shader::SPRITE_FRAGMENT_SHADER;
+use crate::renderer::shader::SPRITE_VERTEX_SHADER;
+use crate::renderer::sprite::Sprite;
+use crate::renderer::texture::TextureFilterMag;
+use crate::renderer::texture::TextureFilterMin;
+use crate::renderer::texture::TextureWrapMode;
+use crate::scene::FrameCommand;
+use crate::scene::Scene;
+use crate::scene::SceneFlags;
+use crate::window::InputEvent;
+use crate::window::Key;
+use crate::window::Modifiers;
+use crate::window::WindowStyle;
+use anyhow::Result;
+use egui::Context;
+use glam::IVec2;
+use glam::Vec2;
+
+#[derive(Default)]
+struct GlobalData {
+    activations: u32,
+    frames: u32,
+    fixed_steps: u32,
+    keys: u32,
+    hovered_frames: u32,
+    pushed_scene: String,
+}
+
+#[derive(Default)]
+struct CounterScene {}
+
+#[derive(Default)]
+struct OverlayScene {}
+
+#[derive(Default)]
+struct MenuScene {}
+
+#[derive(Default)]
+struct BrokenScene {}
+
+impl Scene<GlobalData> for CounterScene {
+    fn activation(&mut self, state: ApplicationState<GlobalData>) -> Result<()> {
+        state.global.activations += 1;
+        Ok(())
+    }
+
+    fn deactivation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn input(&mut self, state: ApplicationState<GlobalData>, event: InputEvent) -> Result<()> {
+        if let InputEvent::KeyPress { .. } = event {
+            state.global.keys += 1;
+        }
+
+        Ok(())
+    }
+
+    fn fixed(&mut self, state: ApplicationState<GlobalData>) -> Result<Option<FrameCommand>> {
+        state.global.fixed_steps += 1;
+        Ok(None)
+    }
+
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+        state.global.frames += 1;
+
+        if state.global.frames == 3 {
+            return Ok(Some(FrameCommand::PushScene { name: state.global.pushed_scene.clone() }));
+        }
+
+        Ok(None)
+    }
+
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        if context.input(|input| input.pointer.hover_pos().is_some()) {
+            state.global.hovered_frames += 1;
+        }
+
+        Ok(None)
+    }
+
+    fn reset(&self) -> Box<dyn Scene<GlobalData>> {
+        Box::<Self>::default()
+    }
+}
+
+impl Scene<GlobalData> for OverlayScene {
+    fn activation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn deactivation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn input(&mut self, _state: ApplicationState<GlobalData>, _event: InputEvent) -> Result<()> {
+        Ok(())
+    }
+
+    fn fixed(&mut self, _state: ApplicationState<GlobalData>) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn frame(&mut self, _state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+        Ok(Some(FrameCommand::PopScene))
+    }
+
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, _context: &Context) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn reset(&self) -> Box<dyn Scene<GlobalData>> {
+        Box::<Self>::default()
+    }
+}
+
+impl Scene<GlobalData> for MenuScene {
+    fn activation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn deactivation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn input(&mut self, _state: ApplicationState<GlobalData>, _event: InputEvent) -> Result<()> {
+        Ok(())
+    }
+
+    fn fixed(&mut self, _state: ApplicationState<GlobalData>) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn frame(&mut self, _state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, _context: &Context) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn reset(&self) -> Box<dyn Scene<GlobalData>> {
+        Box::<Self>::default()
+    }
+
+    fn flags(&self) -> SceneFlags {
+        SceneFlags { render_below: true, ..Default::default() }
+    }
+}
+
+impl Scene<GlobalData> for BrokenScene {
+    fn activation(&mut self, state: ApplicationState<GlobalData>) -> Result<()> {
+        state.global.activations += 1;
+        Ok(())
+    }
+
+    fn deactivation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn input(&mut self, _state: ApplicationState<GlobalData>, _event: InputEvent) -> Result<()> {
+        Ok(())
+    }
+
+    fn fixed(&mut self, _state: ApplicationState<GlobalData>) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn frame(&mut self, _state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, _context: &Context) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn reset(&self) -> Box<dyn Scene<GlobalData>> {
+        Box::<Self>::default()
+    }
+
+    fn assets(&self) -> Vec<String> {
+        vec!["./missing.zip".to_string()]
+    }
+}
+
+#[test]
+fn run_frames() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: Default::default() }, None)
+        .unwrap()
+        .with_clock(Clock::new(TimeSource::Fixed { delta: DEFAULT_TIMESTEP }, DEFAULT_TIMESTEP, DEFAULT_MAX_DELTA))
+        .with_scene("counter", Box::<CounterScene>::default())
+        .with_scene("overlay", Box::<OverlayScene>::default());
+
+    app.global.pushed_scene = "overlay".to_string();
+    app.window.push_event(InputEvent::KeyPress { key: Key::Space, repeat: false, modifiers: Modifiers::default() });
+    app.run_frames(Some("counter"), 5);
+
+    assert_eq!(app.global.activations, 1);
+    assert_eq!(app.global.keys, 1);
+    assert_eq!(app.get_scene_stack(), ["counter"]);
+
+    // Counter scene is covered by the overlay for one frame, so it's not updated then
+    assert_eq!(app.global.frames, 4);
+    assert_eq!(app.global.fixed_steps, 4);
+    assert!(app.is_running());
+}
+
+#[test]
+fn ui_input_passthrough() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: IVec2::new(800, 600) }, None)
+        .unwrap()
+        .with_scene("counter", Box::<CounterScene>::default())
+        .with_scene("menu", Box::<MenuScene>::default());
+
+    app.global.pushed_scene = "menu".to_string();
+    app.window.push_event(InputEvent::MouseMove { position: IVec2::new(100, 100), modifiers: Modifiers::default() });
+    app.run_frames(Some("counter"), 5);
+
+    // Counter scene is still visible below the menu, but doesn't see the pointer anymore
+    assert_eq!(app.get_scene_stack(), ["counter", "menu"]);
+    assert_eq!(app.global.hovered_frames, 3);
+}
+
+#[test]
+fn failed_preloading() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: Default::default() }, None)
+        .unwrap()
+        .with_scene("counter", Box::<CounterScene>::default())
+        .with_scene("broken", Box::<BrokenScene>::default());
+
+    app.global.pushed_scene = "broken".to_string();
+    app.run_frames(Some("counter"), 20);
+
+    // Scene with a missing package is never activated and the command is dropped instead of enqueuing the package again
+    assert_eq!(app.global.activations, 1);
+    assert_eq!(app.get_scene_stack(), ["counter"]);
+    assert!(app.preloader.is_failed("./missing.zip"));
+    assert!(!app.preloader.is_loading());
+}
+
+#[test]
+fn reload_shader() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: Default::default() }, None).unwrap();
+    let mut assets = AssetsLoader::new();
+
+    assets.raw_shaders.push(RawShader::new("custom", "./shaders/custom.vert", RawShaderKind::Vertex, SPRITE_VERTEX_SHADER));
+    assets
+        .raw_shaders
+        .push(RawShader::new("custom", "./shaders/custom.frag", RawShaderKind::Fragment, SPRITE_FRAGMENT_SHADER));
+    app.renderer.instantiate_assets(&assets, None);
+
+    let id = app.renderer.shaders.get_id("./shaders/custom").unwrap();
+    let program = app.renderer.shaders.get_unchecked(id).program;
+
+    assets.raw_shaders[1].source = SPRITE_FRAGMENT_SHADER.replace("void main", "uniform float strength;\n\nvoid main");
+    app.renderer.reload_assets(&assets, &["./shaders/custom.frag".to_string()]);
+
+    // Reloaded shader keeps its id, so everything referencing it picks up the new program
+    let shader = app.renderer.shaders.get_unchecked(id);
+    assert_eq!(app.renderer.shaders.get_id("custom").unwrap(), id);
+    assert_ne!(shader.program, program);
+    assert!(shader.uniforms.contains_key("strength"));
+}
+
+#[test]
+fn reload_texture() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: Default::default() }, None).unwrap();
+    let mut assets = AssetsLoader::new();
+
+    assets.raw_textures.push(RawTexture::new("tile", "/tile.png", Vec2::new(2.0, 2.0), &[255; 16]));
+    app.renderer.instantiate_assets(&assets, None);
+
+    let id = app.renderer.textures.get_id("/tile.png").unwrap();
+    app.renderer.textures.get_unchecked_mut(id).set_filters(TextureFilterMin::Nearest, TextureFilterMag::Nearest);
+    app.renderer.textures.get_unchecked_mut(id).set_wrap_mode(TextureWrapMode::Repeat);
+
+    assets.raw_textures[0] = RawTexture::new("tile", "/tile.png", Vec2::new(4.0, 4.0), &[0; 64]);
+    app.renderer.reload_assets(&assets, &["/tile.png".to_string()]);
+
+    // Settings changed by the game are kept, only the image is replaced
+    let texture = app.renderer.textures.get_unchecked(id);
+    assert_eq!(texture.size, Vec2::new(4.0, 4.0));
+    assert_eq!(texture.filtering_min, TextureFilterMin::Nearest);
+    assert_eq!(texture.filtering_mag, TextureFilterMag::Nearest);
+    assert_eq!(texture.wrap_mode, TextureWrapMode::Repeat);
+}
+
+#[test]
+fn batched_sorting() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: IVec2::new(800, 600) }, None).unwrap();
+    let mut assets = AssetsLoader::new();
+
+    assets.raw_shaders.push(RawShader::new("custom", "./shaders/custom.vert", RawShaderKind::Vertex, SPRITE_VERTEX_SHADER));
+    assets
+        .raw_shaders
+        .push(RawShader::new("custom", "./shaders/custom.frag", RawShaderKind::Fragment, SPRITE_FRAGMENT_SHADER));
+    app.renderer.instantiate_assets(&assets, None);
+
+    let custom = app.renderer.shaders.get_id("./shaders/custom").unwrap();
+
+    // Alternating shaders break every batch unless they are grouped
+    let mut draw_calls = Vec::default();
+    for sorting in [SortingMode::Layer, SortingMode::LayerBatched] {
+        app.renderer.set_sorting(sorting);
+        app.renderer.begin_frame();
+
+        for shader_id in [None, Some(custom), None, Some(custom)] {
+            app.renderer.set_sprite_shader(shader_id);
+            app.renderer.draw_sprite(&Sprite::new());
+        }
+
+        app.renderer.set_sorting(SortingMode::None);
+        app.renderer.end_frame();
+        draw_calls.push(app.renderer.statistics.draw_calls);
+    }
+
+    assert_eq!(draw_calls, [4, 2]);
+}
+
+#[test]
+fn virtual_resolution_minimized() {
+    let mut app = ApplicationContext::<GlobalData>::new("Test", WindowStyle::Window { size: IVec2::new(800, 600) }, None).unwrap();
+    app.renderer.set_virtual_resolution(Some(Vec2::new(320.0, 240.0)), VirtualScaling::Aspect).unwrap();
+
+    app.renderer.set_viewport(Vec2::ZERO);
+    app.renderer.begin_frame();
+    app.renderer.end_frame();
+    assert_eq!(app.renderer.from_window_to_virtual_coordinates(Vec2::new(10.0, 10.0)), Vec2::new(10.0, 10.0));
+
+    // Presenting works again as soon as the window is restored
+    app.renderer.set_viewport(Vec2::new(640.0, 480.0));
+    app.renderer.begin_frame();
+    app.renderer.end_frame();
+    assert_eq!(app.renderer.from_window_to_virtual_coordinates(Vec2::new(10.0, 10.0)), Vec2::new(5.0, 5.0));
+    assert_eq!(app.renderer.statistics.draw_calls, 1);
+}
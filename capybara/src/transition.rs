@@ -0,0 +1,169 @@
+use crate::assets::RawTexture;
+use crate::error_return;
+use crate::renderer::camera::Camera;
+use crate::renderer::camera::CameraOrigin;
+use crate::renderer::context::RendererContext;
+use crate::renderer::sprite::Sprite;
+use crate::renderer::sprite::TextureId;
+use crate::renderer::sprite::TextureType;
+use crate::renderer::texture::Texture;
+use anyhow::Result;
+use glam::Vec2;
+use glam::Vec4;
+
+pub struct TransitionContext {
+    pub camera_id: usize,
+    pub outgoing_texture_id: usize,
+    pub incoming_texture_id: usize,
+
+    transition: Option<Transition>,
+    elapsed: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    pub duration: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TransitionKind {
+    Fade { color: Vec4 },
+    Crossfade,
+    Wipe { direction: WipeDirection },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WipeDirection {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+}
+
+impl TransitionContext {
+    pub fn new(renderer: &mut RendererContext) -> Result<Self> {
+        let outgoing_texture = Texture::new(renderer, &RawTexture::new("transition_outgoing", "", Vec2::new(1.0, 1.0), &Vec::default()))?;
+        let incoming_texture = Texture::new(renderer, &RawTexture::new("transition_incoming", "", Vec2::new(1.0, 1.0), &Vec::default()))?;
+
+        Ok(Self {
+            camera_id: renderer.cameras.store(Camera::new(Vec2::ZERO, renderer.viewport_size, CameraOrigin::LeftTop, true)),
+            outgoing_texture_id: renderer.textures.store(outgoing_texture),
+            incoming_texture_id: renderer.textures.store(incoming_texture),
+
+            transition: None,
+            elapsed: 0.0,
+        })
+    }
+
+    pub fn begin(&mut self, transition: Transition) {
+        self.transition = Some(transition);
+        self.elapsed = 0.0;
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if let Some(transition) = &self.transition {
+            self.elapsed += delta;
+
+            if self.elapsed >= transition.duration {
+                self.transition = None;
+            }
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    pub fn get_progress(&self) -> f32 {
+        match &self.transition {
+            Some(transition) if transition.duration > 0.0 => (self.elapsed / transition.duration).clamp(0.0, 1.0),
+            _ => 1.0,
+        }
+    }
+
+    pub fn set_target(&self, renderer: &mut RendererContext, texture_id: usize) {
+        let texture = match renderer.textures.get_mut(texture_id) {
+            Ok(texture) => texture,
+            Err(err) => error_return!("Failed to set transition target ({})", err),
+        };
+
+        if texture.size != renderer.viewport_size {
+            texture.resize(renderer.viewport_size);
+        }
+
+        renderer.set_target_texture(Some(texture_id));
+        renderer.clear();
+    }
+
+    pub fn draw(&self, renderer: &mut RendererContext) {
+        let transition = match &self.transition {
+            Some(transition) => transition,
+            None => return,
+        };
+
+        let progress = self.get_progress();
+        let size = renderer.viewport_size;
+
+        renderer.set_target_texture(None);
+        renderer.set_camera(self.camera_id);
+
+        match transition.kind {
+            TransitionKind::Fade { color } => {
+                let (texture_id, alpha) =
+                    if progress < 0.5 { (self.outgoing_texture_id, progress * 2.0) } else { (self.incoming_texture_id, (1.0 - progress) * 2.0) };
+
+                self.draw_texture(renderer, texture_id, Vec2::ZERO, size, Vec4::ONE);
+
+                // Colors are premultiplied, so the whole vector is scaled by alpha
+                let alpha = color.w * alpha;
+                let color = Vec4::new(color.x * alpha, color.y * alpha, color.z * alpha, alpha);
+
+                renderer.draw_sprite(&Sprite { position: Vec2::ZERO, anchor: Vec2::ZERO, size: Some(size), color, ..Default::default() });
+            }
+            TransitionKind::Crossfade => {
+                self.draw_texture(renderer, self.outgoing_texture_id, Vec2::ZERO, size, Vec4::ONE);
+                self.draw_texture(renderer, self.incoming_texture_id, Vec2::ZERO, size, Vec4::splat(progress));
+            }
+            TransitionKind::Wipe { direction } => {
+                let (position, wipe_size) = match direction {
+                    WipeDirection::LeftToRight => (Vec2::ZERO, Vec2::new(size.x * progress, size.y)),
+                    WipeDirection::RightToLeft => (Vec2::new(size.x * (1.0 - progress), 0.0), Vec2::new(size.x * progress, size.y)),
+                    WipeDirection::TopToBottom => (Vec2::ZERO, Vec2::new(size.x, size.y * progress)),
+                    WipeDirection::BottomToTop => (Vec2::new(0.0, size.y * (1.0 - progress)), Vec2::new(size.x, size.y * progress)),
+                };
+
+                self.draw_texture(renderer, self.outgoing_texture_id, Vec2::ZERO, size, Vec4::ONE);
+                self.draw_texture(renderer, self.incoming_texture_id, position.round(), wipe_size.round(), Vec4::ONE);
+            }
+        }
+
+        renderer.flush_buffer();
+        renderer.set_camera(renderer.default_camera_id);
+    }
+
+    fn draw_texture(&self, renderer: &mut RendererContext, texture_id: usize, position: Vec2, size: Vec2, color: Vec4) {
+        if size.x < 1.0 || size.y < 1.0 {
+            return;
+        }
+
+        // Framebuffer textures are stored bottom-up, so the source rectangle has to be flipped vertically
+        let texture_size = renderer.viewport_size;
+        let source_position = Vec2::new(position.x, texture_size.y - position.y - size.y);
+
+        renderer.draw_sprite(&Sprite {
+            position,
+            anchor: Vec2::ZERO,
+            color,
+            texture_id: TextureId::Some(texture_id),
+            texture_type: TextureType::SimpleCoordinates { position: source_position, size },
+            ..Default::default()
+        });
+    }
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind, duration: f32) -> Self {
+        Self { kind, duration }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::assets::loader::AssetsLoader;
+use crate::assets::manifest::AssetsReferences;
 use crate::assets::RawTexture;
+use crate::assets::RawTextureFormat;
 use crate::error_return;
 use crate::renderer::camera::Camera;
 use crate::renderer::camera::CameraOrigin;
@@ -15,6 +17,7 @@ use crate::window::Key;
 use crate::window::Modifiers;
 use crate::window::MouseButton;
 use crate::window::MouseWheelDirection;
+use anyhow::Result;
 use core::slice;
 use egui::epaint::Primitive;
 use egui::Color32;
@@ -53,6 +56,8 @@ pub struct UiContext {
     pub camera_id: usize,
     pub textures: FxHashMap<TextureId, usize>,
     pub handles: FxHashMap<String, TextureHandle>,
+    pub fonts: FontDefinitions,
+    pub references: AssetsReferences,
 
     time: Instant,
     max_texture_size: i32,
@@ -69,6 +74,8 @@ impl UiContext {
             camera_id: renderer.cameras.store(Camera::new(Vec2::ZERO, renderer.viewport_size, CameraOrigin::LeftTop, true)),
             textures: FxHashMap::default(),
             handles: FxHashMap::default(),
+            fonts: FontDefinitions::default(),
+            references: AssetsReferences::default(),
 
             time: Instant::now(),
             max_texture_size: unsafe { renderer.gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) },
@@ -77,12 +84,50 @@ impl UiContext {
 
     pub fn instantiate_assets(&mut self, assets: &AssetsLoader, prefix: Option<&str>) {
         info!("Instancing UI assets, prefix {}", prefix.unwrap_or("none"));
+        self.instantiate_filtered(assets, |path| prefix.map(|p| path.starts_with(p)).unwrap_or(true));
+    }
+
+    pub fn instantiate_group(&mut self, assets: &AssetsLoader, group: &str) -> Result<()> {
+        info!("Instancing UI assets, group {}", group);
+
+        let paths = assets.get_group_paths(group)?;
+        let acquired = paths.into_iter().filter(|p| self.references.acquire(p)).collect::<Vec<String>>();
+        self.instantiate_filtered(assets, |path| acquired.iter().any(|p| p == path));
+
+        Ok(())
+    }
+
+    pub fn reload_assets(&mut self, assets: &AssetsLoader, paths: &[String]) {
+        info!("Reloading UI assets, {} files", paths.len());
+        self.instantiate_filtered(assets, |path| paths.iter().any(|p| p == path));
+    }
+
+    pub fn unload_group(&mut self, assets: &AssetsLoader, group: &str) -> Result<()> {
+        info!("Unloading UI assets, group {}", group);
+
+        for path in assets.get_group_paths(group)? {
+            if !self.references.release(&path) {
+                continue;
+            }
+
+            // egui frees the texture when the last handle is dropped, so the alias inserted under the name must go too
+            if let Some(handle) = self.handles.remove(&path) {
+                self.handles.retain(|_, p| p.id() != handle.id());
+            }
+        }
+
+        Ok(())
+    }
 
+    fn instantiate_filtered(&mut self, assets: &AssetsLoader, filter: impl Fn(&str) -> bool) {
         for raw in &assets.raw_textures {
-            if let Some(prefix) = &prefix {
-                if !raw.path.starts_with(prefix) {
-                    continue;
-                }
+            if !filter(&raw.path) {
+                continue;
+            }
+
+            // egui needs decoded pixels, so compressed textures are available only for the renderer
+            if raw.format.is_compressed() {
+                continue;
             }
 
             let size = [raw.size.x as usize, raw.size.y as usize];
@@ -90,6 +135,11 @@ impl UiContext {
 
             for x in 0..size[0] {
                 for y in 0..size[1] {
+                    if raw.format == RawTextureFormat::R8 {
+                        image.pixels[x + y * size[0]] = Color32::from_gray(raw.data[x + y * size[0]]);
+                        continue;
+                    }
+
                     let base = x * 4 + y * 4 * size[0];
                     let r = raw.data[base + 0];
                     let g = raw.data[base + 1];
@@ -100,17 +150,14 @@ impl UiContext {
                 }
             }
 
-            let handle = self.inner.write().load_texture(raw.name.clone(), image, TextureOptions::default());
+            let handle = self.inner.write().load_texture(raw.path.clone(), image, TextureOptions::default());
+            self.handles.insert(raw.path.clone(), handle.clone());
             self.handles.insert(raw.name.clone(), handle);
         }
 
-        let mut fonts = FontDefinitions::default();
-
         for font in &assets.raw_fonts {
-            if let Some(prefix) = &prefix {
-                if !font.path.starts_with(prefix) {
-                    continue;
-                }
+            if !filter(&font.path) {
+                continue;
             }
 
             let family = match font.name.as_str() {
@@ -119,11 +166,11 @@ impl UiContext {
                 _ => FontFamily::Name(font.name.clone().into()),
             };
 
-            fonts.font_data.insert(font.name.clone(), FontData::from_owned(font.data.clone()));
-            fonts.families.insert(family, vec![font.name.clone()]);
+            self.fonts.font_data.insert(font.name.clone(), FontData::from_owned(font.data.clone()));
+            self.fonts.families.insert(family, vec![font.name.clone()]);
         }
 
-        self.inner.write().set_fonts(fonts);
+        self.inner.write().set_fonts(self.fonts.clone());
     }
 
     pub fn collect_event(&mut self, event: &InputEvent) {
@@ -261,6 +308,9 @@ impl UiContext {
             };
         }
 
+        // Everything drawn so far has to be flushed before the first scissor is enabled, otherwise it would be clipped too
+        renderer.flush_buffer();
+
         for mesh in self.inner.read().tessellate(output.shapes, 1.0) {
             if let Primitive::Mesh(data) = mesh.primitive {
                 let mut vertices = Vec::default();
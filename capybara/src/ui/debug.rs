@@ -157,6 +157,9 @@ impl DebugWindow {
                         ui.horizontal(|ui| {
                             ui.label(format!("Draw calls: {} ({} tris)", data.renderer.draw_calls, data.renderer.triangles));
                         });
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Culled: {} sprites, {} shapes", data.renderer.culled_sprites, data.renderer.culled_shapes));
+                        });
                         ui.horizontal(|ui| {
                             ui.label(format!("Sprite buffer size: {:.1} MB", data.renderer.sprite_buffer_size as f32 / 1024.0 / 1024.0));
                         });
@@ -69,6 +69,24 @@ where
     Ok(Some(value.get::<T>().ok_or_else(|| anyhow!("Failed to parse {}", name))?.clone()))
 }
 
+pub fn read_value_optional<T>(data: &HashMap<String, JsonValue>, name: &str) -> Result<Option<T>>
+where
+    T: Clone + Default + InnerAsRef,
+{
+    match data.get(name) {
+        Some(value) if !value.is_null() => Ok(Some(value.get::<T>().ok_or_else(|| anyhow!("Failed to parse {}", name))?.clone())),
+        _ => Ok(None),
+    }
+}
+
+pub fn read_array_optional<'a>(data: &'a HashMap<String, JsonValue>, name: &str) -> Result<Vec<&'a HashMap<String, JsonValue>>> {
+    match data.get(name) {
+        Some(JsonValue::Array(array)) => Ok(array.iter().filter_map(|p| p.get()).collect()),
+        Some(JsonValue::Null) | None => Ok(Vec::default()),
+        _ => bail!("Failed to read array {}", name),
+    }
+}
+
 pub fn read_color(data: &HashMap<String, JsonValue>, name: &str) -> Result<Vec4> {
     let value = data.get(name).ok_or_else(|| anyhow!("Failed to read {}", name))?;
     if value.is_null() {
@@ -35,6 +35,45 @@ impl<T> Storage<T> {
         Ok(id)
     }
 
+    pub fn store_or_replace_with_name(&mut self, name: &str, item: T) -> (usize, Option<T>) {
+        match self.name_to_id_hashmap.get(name) {
+            Some(id) => (*id, self.data[*id].replace(item)),
+            None => {
+                let id = self.get_new_id();
+                self.data[id] = Some(item);
+
+                self.name_to_id_hashmap.insert(name.to_string(), id);
+                self.id_to_name_hashmap.insert(id, name.to_string());
+
+                (id, None)
+            }
+        }
+    }
+
+    pub fn add_alias(&mut self, id: usize, alias: &str) -> Result<()> {
+        if self.name_to_id_hashmap.contains_key(alias) {
+            bail!("Name already exists".to_string());
+        }
+
+        self.get(id)?;
+        self.name_to_id_hashmap.insert(alias.to_string(), id);
+
+        Ok(())
+    }
+
+    pub fn store_with_id(&mut self, id: usize, item: T) {
+        if id >= self.data.len() {
+            self.removed_ids.extend(self.data.len()..id);
+            self.data.resize_with(id + 1, || None);
+        } else if self.data[id].is_none() {
+            if let Some(index) = self.removed_ids.iter().rposition(|p| *p == id) {
+                self.removed_ids.remove(index);
+            }
+        }
+
+        self.data[id] = Some(item);
+    }
+
     pub fn contains(&self, id: usize) -> bool {
         self.data.get(id).is_some()
     }
@@ -94,9 +133,8 @@ impl<T> Storage<T> {
         let item = self.data[id].take();
         self.removed_ids.push_back(id);
 
-        if let Some(name) = self.id_to_name_hashmap.get(&id) {
-            self.name_to_id_hashmap.remove(name);
-            self.id_to_name_hashmap.remove(&id);
+        if self.id_to_name_hashmap.remove(&id).is_some() {
+            self.name_to_id_hashmap.retain(|_, p| *p != id);
         }
 
         item
@@ -112,7 +150,7 @@ impl<T> Storage<T> {
         let item = self.data[id].take();
         self.removed_ids.push_back(id);
 
-        self.name_to_id_hashmap.remove(name);
+        self.name_to_id_hashmap.retain(|_, p| *p != id);
         self.id_to_name_hashmap.remove(&id);
 
         item
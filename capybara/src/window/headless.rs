@@ -0,0 +1,446 @@
+use super::*;
+use crate::*;
+use anyhow::Result;
+use glow::Context;
+use glow::HasContext;
+use log::debug;
+use log::info;
+use log::Level;
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::ptr;
+use std::slice;
+
+pub const DEFAULT_SIZE: IVec2 = IVec2::new(1280, 720);
+
+#[derive(Debug)]
+pub struct WindowContext {
+    pub size: IVec2,
+    pub cursor_visible: bool,
+    pub cursor_position: IVec2,
+    pub cursor_in_window: bool,
+    pub mouse_state: Vec<bool>,
+    pub keyboard_state: Vec<bool>,
+    pub swap_interval: u32,
+    pub frames_count: u64,
+
+    event_queue: VecDeque<InputEvent>,
+}
+
+#[derive(Debug, Default)]
+struct HeadlessGlState {
+    last_name: u32,
+    shaders: FxHashMap<u32, String>,
+    programs: FxHashMap<u32, Vec<u32>>,
+}
+
+#[derive(Debug)]
+struct HeadlessGlUniform {
+    name: String,
+    r#type: u32,
+    size: i32,
+}
+
+thread_local! {
+    static GL_STATE: RefCell<HeadlessGlState> = RefCell::new(HeadlessGlState::default());
+}
+
+impl WindowContext {
+    pub fn new(title: &str, style: WindowStyle, _msaa: Option<u32>) -> Result<Box<Self>> {
+        // Logger can be already initialized when several contexts are created by the same test binary
+        let _ = simple_logger::init_with_level(Level::Info);
+
+        info!("Capybara {}", VERSION);
+        info!("Headless window initialization ({})", title);
+
+        let mut context = Box::new(Self {
+            size: IVec2::new(1, 1),
+            cursor_visible: true,
+            cursor_position: IVec2::default(),
+            cursor_in_window: false,
+            mouse_state: vec![false; MouseButton::Unknown as usize],
+            keyboard_state: vec![false; Key::Unknown as usize],
+            swap_interval: 0,
+            frames_count: 0,
+
+            event_queue: VecDeque::default(),
+        });
+        context.set_style(style);
+
+        Ok(context)
+    }
+
+    pub fn load_gl_pointers(&self) -> Context {
+        unsafe {
+            let gl = glow::Context::from_loader_function(get_gl_function);
+
+            let version = gl.version();
+            info!("OpenGL {}.{} {}", version.major, version.minor, version.vendor_info);
+
+            gl
+        }
+    }
+
+    pub fn set_style(&mut self, style: WindowStyle) {
+        let size = match style {
+            WindowStyle::Window { size } => size,
+            WindowStyle::Borderless | WindowStyle::Fullscreen => DEFAULT_SIZE,
+        };
+
+        if size != self.size {
+            self.event_queue.push_back(InputEvent::WindowSizeChange { size });
+            self.size = size;
+        }
+    }
+
+    pub fn push_event(&mut self, event: InputEvent) {
+        self.event_queue.push_back(event);
+    }
+
+    pub fn push_events(&mut self, events: &[InputEvent]) {
+        self.event_queue.extend(events);
+    }
+
+    pub fn poll_event(&mut self) -> Option<InputEvent> {
+        let event = self.event_queue.pop_front()?;
+
+        match event {
+            InputEvent::WindowSizeChange { size } => self.size = size,
+            InputEvent::MouseMove { position, .. } => self.cursor_position = position,
+            InputEvent::MouseEnter { position, .. } => {
+                self.cursor_position = position;
+                self.cursor_in_window = true;
+            }
+            InputEvent::MouseLeave => self.cursor_in_window = false,
+            InputEvent::MouseButtonPress { button, position, .. } if button != MouseButton::Unknown => {
+                self.mouse_state[button as usize] = true;
+                self.cursor_position = position;
+            }
+            InputEvent::MouseButtonRelease { button, position, .. } if button != MouseButton::Unknown => {
+                self.mouse_state[button as usize] = false;
+                self.cursor_position = position;
+            }
+            InputEvent::KeyPress { key, .. } if key != Key::Unknown => self.keyboard_state[key as usize] = true,
+            InputEvent::KeyRelease { key, .. } if key != Key::Unknown => self.keyboard_state[key as usize] = false,
+            _ => {}
+        }
+
+        Some(event)
+    }
+
+    pub fn get_modifiers(&self) -> Modifiers {
+        Modifiers::new(self.keyboard_state[Key::Control as usize], self.keyboard_state[Key::Alt as usize], self.keyboard_state[Key::Shift as usize])
+    }
+
+    pub fn set_cursor_visibility(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+    }
+
+    pub fn set_swap_interval(&mut self, interval: u32) {
+        self.swap_interval = interval;
+    }
+
+    pub fn swap_buffers(&mut self) {
+        self.frames_count += 1;
+    }
+
+    pub fn close(&mut self) {
+        self.event_queue.push_back(InputEvent::WindowClose);
+    }
+
+    pub fn get_memory_usage(&self) -> MemoryInfo {
+        MemoryInfo { private: 0, reserved: 0 }
+    }
+}
+
+impl HeadlessGlState {
+    fn get_new_name(&mut self) -> u32 {
+        self.last_name += 1;
+        self.last_name
+    }
+
+    fn get_uniforms(&self, program: u32) -> Vec<HeadlessGlUniform> {
+        let mut uniforms = Vec::default();
+        let shaders = match self.programs.get(&program) {
+            Some(shaders) => shaders,
+            None => return uniforms,
+        };
+
+        for source in shaders.iter().filter_map(|p| self.shaders.get(p)) {
+            for line in source.lines() {
+                let line = line.trim().trim_end_matches(';');
+                let tokens = line.split_whitespace().collect::<Vec<&str>>();
+
+                if tokens.first() != Some(&"uniform") || tokens.len() < 3 {
+                    continue;
+                }
+
+                let r#type = match tokens[tokens.len() - 2] {
+                    "int" => glow::INT,
+                    "float" => glow::FLOAT,
+                    "vec2" => glow::FLOAT_VEC2,
+                    "vec3" => glow::FLOAT_VEC3,
+                    "vec4" => glow::FLOAT_VEC4,
+                    "mat4" => glow::FLOAT_MAT4,
+                    "sampler2D" => glow::SAMPLER_2D,
+                    _ => glow::FLOAT,
+                };
+                let declaration = tokens[tokens.len() - 1];
+                let (name, size) = match declaration.split_once('[') {
+                    Some((name, size)) => (format!("{}[0]", name), size.trim_end_matches(']').parse().unwrap_or(1)),
+                    None => (declaration.to_string(), 1),
+                };
+
+                if !uniforms.iter().any(|p: &HeadlessGlUniform| p.name == name) {
+                    uniforms.push(HeadlessGlUniform { name, r#type, size });
+                }
+            }
+        }
+
+        uniforms
+    }
+}
+
+fn get_gl_function(name: &str) -> *const c_void {
+    match name {
+        "glGetString" => glGetString as *const c_void,
+        "glGetStringi" => glGetStringi as *const c_void,
+        "glGetIntegerv" => glGetIntegerv as *const c_void,
+        "glGetError" => glGetError as *const c_void,
+        "glGenBuffers" => glGenNames as *const c_void,
+        "glGenTextures" => glGenNames as *const c_void,
+        "glGenFramebuffers" => glGenNames as *const c_void,
+        "glGenRenderbuffers" => glGenNames as *const c_void,
+        "glGenVertexArrays" => glGenNames as *const c_void,
+        "glCreateShader" => glCreateShader as *const c_void,
+        "glCreateProgram" => glCreateProgram as *const c_void,
+        "glShaderSource" => glShaderSource as *const c_void,
+        "glAttachShader" => glAttachShader as *const c_void,
+        "glGetShaderiv" => glGetShaderiv as *const c_void,
+        "glGetProgramiv" => glGetProgramiv as *const c_void,
+        "glGetShaderInfoLog" => glGetInfoLog as *const c_void,
+        "glGetProgramInfoLog" => glGetInfoLog as *const c_void,
+        "glGetActiveUniform" => glGetActiveUniform as *const c_void,
+        "glGetUniformLocation" => glGetUniformLocation as *const c_void,
+        _ => get_gl_noop_function(name),
+    }
+}
+
+extern "system" fn glGetString(name: u32) -> *const u8 {
+    let value: &[u8] = match name {
+        glow::VERSION => b"3.3.0 Capybara Headless\0",
+        glow::SHADING_LANGUAGE_VERSION => b"3.30\0",
+        glow::VENDOR => b"Capybara\0",
+        glow::RENDERER => b"Capybara Headless\0",
+        _ => b"\0",
+    };
+
+    value.as_ptr()
+}
+
+extern "system" fn glGetStringi(_name: u32, _index: u32) -> *const u8 {
+    ptr::null()
+}
+
+extern "system" fn glGetIntegerv(name: u32, data: *mut i32) {
+    let value = match name {
+        glow::MAX_TEXTURE_SIZE => 16384,
+        _ => 0,
+    };
+
+    unsafe { *data = value };
+}
+
+extern "system" fn glGetError() -> u32 {
+    glow::NO_ERROR
+}
+
+extern "system" fn glGenNames(count: i32, names: *mut u32) {
+    GL_STATE.with_borrow_mut(|state| {
+        for i in 0..count as usize {
+            unsafe { *names.add(i) = state.get_new_name() };
+        }
+    });
+}
+
+extern "system" fn glCreateShader(_type: u32) -> u32 {
+    GL_STATE.with_borrow_mut(|state| state.get_new_name())
+}
+
+extern "system" fn glCreateProgram() -> u32 {
+    GL_STATE.with_borrow_mut(|state| {
+        let name = state.get_new_name();
+        state.programs.insert(name, Vec::default());
+
+        name
+    })
+}
+
+extern "system" fn glShaderSource(shader: u32, count: i32, sources: *const *const c_char, lengths: *const i32) {
+    let mut source = String::default();
+
+    for i in 0..count as usize {
+        unsafe {
+            let data = slice::from_raw_parts(*sources.add(i) as *const u8, *lengths.add(i) as usize);
+            source.push_str(&String::from_utf8_lossy(data));
+        }
+    }
+
+    GL_STATE.with_borrow_mut(|state| state.shaders.insert(shader, source));
+}
+
+extern "system" fn glAttachShader(program: u32, shader: u32) {
+    GL_STATE.with_borrow_mut(|state| state.programs.entry(program).or_default().push(shader));
+}
+
+extern "system" fn glGetShaderiv(_shader: u32, name: u32, data: *mut i32) {
+    let value = match name {
+        glow::COMPILE_STATUS => glow::TRUE as i32,
+        _ => 0,
+    };
+
+    unsafe { *data = value };
+}
+
+extern "system" fn glGetProgramiv(program: u32, name: u32, data: *mut i32) {
+    let value = match name {
+        glow::LINK_STATUS => glow::TRUE as i32,
+        glow::ACTIVE_UNIFORMS => GL_STATE.with_borrow(|state| state.get_uniforms(program).len() as i32),
+        glow::ACTIVE_UNIFORM_MAX_LENGTH => {
+            GL_STATE.with_borrow(|state| state.get_uniforms(program).iter().map(|p| p.name.len() + 1).max().unwrap_or(0) as i32)
+        }
+        _ => 0,
+    };
+
+    unsafe { *data = value };
+}
+
+extern "system" fn glGetInfoLog(_object: u32, _buffer_size: i32, length: *mut i32, _log: *mut c_char) {
+    unsafe { *length = 0 };
+}
+
+extern "system" fn glGetActiveUniform(
+    program: u32,
+    index: u32,
+    buffer_size: i32,
+    length: *mut i32,
+    size: *mut i32,
+    r#type: *mut u32,
+    name: *mut c_char,
+) {
+    GL_STATE.with_borrow(|state| {
+        let uniforms = state.get_uniforms(program);
+        let uniform = match uniforms.get(index as usize) {
+            Some(uniform) => uniform,
+            None => return,
+        };
+        let name_length = uniform.name.len().min(buffer_size.max(1) as usize - 1);
+
+        unsafe {
+            ptr::copy_nonoverlapping(uniform.name.as_ptr(), name as *mut u8, name_length);
+            *name.add(name_length) = 0;
+            *length = name_length as i32;
+            *size = uniform.size;
+            *r#type = uniform.r#type;
+        }
+    });
+}
+
+extern "system" fn glGetUniformLocation(program: u32, name: *const c_char) -> i32 {
+    let name = unsafe { CStr::from_ptr(name).to_string_lossy() };
+    let (base, index) = match name.split_once('[') {
+        Some((base, index)) => (format!("{}[0]", base), index.trim_end_matches(']').parse::<i32>().unwrap_or(0)),
+        None => (name.to_string(), 0),
+    };
+
+    GL_STATE.with_borrow(|state| {
+        let mut location = 0;
+
+        for uniform in state.get_uniforms(program) {
+            if uniform.name == base && index < uniform.size {
+                return location + index;
+            }
+
+            location += uniform.size;
+        }
+
+        -1
+    })
+}
+
+macro_rules! gl_noop {
+    ($($name:ident($($arg:ty),*);)+) => {
+        $(
+            extern "system" fn $name($(_: $arg),*) {}
+        )+
+
+        fn get_gl_noop_function(name: &str) -> *const c_void {
+            match name {
+                $(stringify!($name) => $name as *const c_void,)+
+                _ => {
+                    debug!("GL function {} not emulated, using a no-op", name);
+                    glUnknown as *const c_void
+                }
+            }
+        }
+    };
+}
+
+// On 64-bit targets arguments are cleaned up by the caller, so the same function can stand in for any entry point, returning zero or null
+extern "system" fn glUnknown() -> usize {
+    0
+}
+
+gl_noop! {
+    glActiveTexture(u32);
+    glBindBuffer(u32, u32);
+    glBindFramebuffer(u32, u32);
+    glBindRenderbuffer(u32, u32);
+    glBindTexture(u32, u32);
+    glBindVertexArray(u32);
+    glBlendFunc(u32, u32);
+    glBlitFramebuffer(i32, i32, i32, i32, i32, i32, i32, i32, u32, u32);
+    glBufferData(u32, isize, *const c_void, u32);
+    glBufferSubData(u32, isize, isize, *const c_void);
+    glClear(u32);
+    glClearColor(f32, f32, f32, f32);
+    glCompileShader(u32);
+    glCompressedTexImage2D(u32, i32, u32, i32, i32, i32, i32, *const c_void);
+    glDeleteBuffers(i32, *const u32);
+    glDeleteFramebuffers(i32, *const u32);
+    glDeleteProgram(u32);
+    glDeleteRenderbuffers(i32, *const u32);
+    glDeleteShader(u32);
+    glDeleteTextures(i32, *const u32);
+    glDeleteVertexArrays(i32, *const u32);
+    glDisable(u32);
+    glDrawElements(u32, i32, u32, *const c_void);
+    glDrawElementsInstanced(u32, i32, u32, *const c_void, i32);
+    glEnable(u32);
+    glEnableVertexAttribArray(u32);
+    glFlush();
+    glFramebufferRenderbuffer(u32, u32, u32, u32);
+    glFramebufferTexture2D(u32, u32, u32, u32, i32);
+    glGenerateMipmap(u32);
+    glLinkProgram(u32);
+    glPixelStorei(u32, i32);
+    glRenderbufferStorageMultisample(u32, i32, u32, i32, i32);
+    glScissor(i32, i32, i32, i32);
+    glTexImage2D(u32, i32, i32, i32, i32, i32, u32, u32, *const c_void);
+    glTexParameteri(u32, u32, i32);
+    glTexSubImage2D(u32, i32, i32, i32, i32, i32, u32, u32, *const c_void);
+    glUniform1f(i32, f32);
+    glUniform1i(i32, i32);
+    glUniform2fv(i32, i32, *const f32);
+    glUniform4fv(i32, i32, *const f32);
+    glUniformMatrix4fv(i32, i32, u8, *const f32);
+    glUseProgram(u32);
+    glVertexAttribDivisor(u32, u32);
+    glVertexAttribIPointer(u32, i32, u32, i32, *const c_void);
+    glVertexAttribPointer(u32, i32, u32, u8, i32, *const c_void);
+    glViewport(i32, i32, i32, i32);
+}
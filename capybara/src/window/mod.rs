@@ -1,15 +1,20 @@
 use glam::IVec2;
 
-#[cfg(windows)]
+#[cfg(all(windows, not(test)))]
 pub mod winapi;
-#[cfg(windows)]
+#[cfg(all(windows, not(test)))]
 pub type WindowContext = winapi::WindowContext;
 
-#[cfg(unix)]
+#[cfg(all(unix, not(test)))]
 pub mod x11;
-#[cfg(unix)]
+#[cfg(all(unix, not(test)))]
 pub type WindowContext = x11::WindowContext;
 
+#[cfg(all(any(windows, unix), test))]
+pub mod headless;
+#[cfg(all(any(windows, unix), test))]
+pub type WindowContext = headless::WindowContext;
+
 #[cfg(web)]
 pub mod web;
 #[cfg(web)]
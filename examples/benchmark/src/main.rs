@@ -1,3 +1,4 @@
+use capybara::anyhow::Error;
 use capybara::anyhow::Result;
 use capybara::app::ApplicationContext;
 use capybara::app::ApplicationState;
@@ -6,11 +7,10 @@ use capybara::assets::AssetsLoadingStatus;
 use capybara::assets::RawTexture;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::egui::Slider;
@@ -42,6 +42,8 @@ struct GlobalData {
 struct MainScene {
     objects: Vec<Object>,
     objects_count: u32,
+    textures: Vec<usize>,
+    textures_count: u32,
     initialized: bool,
     delta_history: VecDeque<f32>,
     target_texture_id: usize,
@@ -87,7 +89,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, mut state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, mut state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -99,6 +101,23 @@ impl Scene<GlobalData> for MainScene {
             state.ui.instantiate_assets(&state.global.assets, None);
             state.window.set_swap_interval(0);
 
+            // Tinted copies of the same sprite, so the batching of several textures can be observed
+            let raw = state.global.assets.raw_textures.iter().find(|p| p.name == "takodachi").ok_or_else(|| Error::msg("Texture not found"))?;
+            for i in 0..8 {
+                let tint = [(i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32];
+                let mut data = raw.data.clone();
+
+                for pixel in data.chunks_exact_mut(4) {
+                    for channel in 0..3 {
+                        pixel[channel] = (pixel[channel] as f32 * (0.5 + tint[channel] * 0.5)) as u8;
+                    }
+                }
+
+                let texture = Texture::new(state.renderer, &RawTexture::new(&format!("takodachi_{}", i), "", raw.size, &data))?;
+                self.textures.push(state.renderer.textures.store(texture));
+            }
+
+            self.textures_count = 1;
             self.regenerate_objects(&state, 100000)?;
 
             let target_texture = Texture::new(state.renderer, &RawTexture::new("target_texture", "", Vec2::new(400.0, 400.0), &Vec::default()))?;
@@ -170,43 +189,50 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let label = format!("FPS: {}", state.renderer.fps);
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let label = format!("FPS: {}", state.renderer.fps);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
-                    let label = format!("Delta: {:.2}", delta_average * 1000.0);
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+                let label = format!("Delta: {:.2}", delta_average * 1000.0);
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    ui.style_mut().drag_value_text_style = TextStyle::Monospace;
-                    ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
+                ui.style_mut().drag_value_text_style = TextStyle::Monospace;
+                ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Objects count:").font(font.clone()).heading().color(color));
-                    if ui.add(Slider::new(&mut self.objects_count, 0..=1000000).text_color(color).logarithmic(true)).changed() {
-                        self.regenerate_objects(&state, self.objects_count).unwrap();
-                    }
+                let label = format!("Draw calls: {}", state.renderer.statistics.draw_calls);
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Shaders:").font(font.clone()).heading().color(color));
-                    ui.radio_value(&mut self.selected_shader, SelectedShader::None, RichText::new("None").font(font.clone()).heading().color(color));
-                    ui.radio_value(&mut self.selected_shader, SelectedShader::Blur, RichText::new("Blur").font(font.clone()).heading().color(color));
-                    ui.radio_value(
-                        &mut self.selected_shader,
-                        SelectedShader::Grayscale,
-                        RichText::new("Grayscale").font(font.clone()).heading().color(color),
-                    );
+                ui.add_space(10.0);
+                ui.label(RichText::new("Objects count:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.objects_count, 0..=1000000).text_color(color).logarithmic(true)).changed() {
+                    self.regenerate_objects(&state, self.objects_count).unwrap();
                 }
-            });
+
+                ui.add_space(10.0);
+                ui.label(RichText::new("Textures count:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.textures_count, 1..=8).text_color(color)).changed() {
+                    self.regenerate_objects(&state, self.objects_count).unwrap();
+                }
+
+                ui.add_space(10.0);
+                ui.label(RichText::new("Shaders:").font(font.clone()).heading().color(color));
+                ui.radio_value(&mut self.selected_shader, SelectedShader::None, RichText::new("None").font(font.clone()).heading().color(color));
+                ui.radio_value(&mut self.selected_shader, SelectedShader::Blur, RichText::new("Blur").font(font.clone()).heading().color(color));
+                ui.radio_value(
+                    &mut self.selected_shader,
+                    SelectedShader::Grayscale,
+                    RichText::new("Grayscale").font(font.clone()).heading().color(color),
+                );
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -219,14 +245,14 @@ impl MainScene {
         self.objects.clear();
         self.objects_count = n;
 
-        for _ in 0..n {
+        for i in 0..n {
             let position = Vec2::new(
                 fastrand::u32(0..state.renderer.viewport_size.x as u32) as f32,
                 fastrand::u32(0..state.renderer.viewport_size.y as u32) as f32,
             );
 
             self.objects.push(Object {
-                sprite: Sprite { position, texture_id: TextureId::Some(state.renderer.textures.get_id("takodachi")?), ..Default::default() },
+                sprite: Sprite { position, texture_id: TextureId::Some(self.textures[(i % self.textures_count) as usize]), ..Default::default() },
                 direction: Vec2::new(fastrand::f32() * 2.0 - 1.0, fastrand::f32() * 2.0 - 1.0),
             });
         }
@@ -5,11 +5,10 @@ use capybara::assets::loader::AssetsLoader;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::fast_gpu;
@@ -18,6 +17,7 @@ use capybara::glam::Vec2;
 use capybara::renderer::sprite::Sprite;
 use capybara::renderer::sprite::TextureId;
 use capybara::renderer::sprite::TextureType;
+use capybara::renderer::tilemap::Tilemap;
 use capybara::scene::FrameCommand;
 use capybara::scene::Scene;
 use capybara::window::InputEvent;
@@ -35,6 +35,7 @@ struct GlobalData {
 #[derive(Default)]
 struct MainScene {
     initialized: bool,
+    tilemaps: Vec<Tilemap>,
     delta_history: VecDeque<f32>,
 }
 
@@ -59,7 +60,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -71,30 +72,35 @@ impl Scene<GlobalData> for MainScene {
             state.ui.instantiate_assets(&state.global.assets, None);
             state.window.set_swap_interval(0);
 
+            let world = &state.global.assets.worlds[0];
+            for layer in &world.levels[0].layers {
+                if let Some(tilemap_id) = layer.tilemap_id {
+                    let tilemap = world.tilemaps.iter().find(|p| p.id == tilemap_id).unwrap();
+                    let texture_id = state.renderer.textures.get_id(&tilemap.name)?;
+                    let mut tilemap = Tilemap::new_from_ldtk(layer, tilemap, texture_id);
+
+                    tilemap.position += Vec2::new(140.0, 0.0);
+                    self.tilemaps.push(tilemap);
+                }
+            }
+
             self.initialized = true;
         }
 
         if self.initialized {
+            for tilemap in &mut self.tilemaps {
+                state.renderer.draw_tilemap(tilemap);
+            }
+
             let world = &state.global.assets.worlds[0];
             let level = &world.levels[0];
 
             for layer in &level.layers {
-                if let Some(tilemap_id) = layer.tilemap_id {
-                    let tilemap = world.tilemaps.iter().find(|p| p.id == tilemap_id).unwrap();
-                    for tile in &layer.tiles {
-                        state.renderer.draw_sprite(&Sprite {
-                            position: tile.position + Vec2::new(140.0, 0.0),
-                            size: Some(tilemap.tile_size),
-                            anchor: Vec2::ZERO,
-                            texture_id: TextureId::Some(state.renderer.textures.get_id(&tilemap.name).unwrap()),
-                            texture_type: TextureType::SimpleCoordinates { position: tile.source, size: tilemap.tile_size },
-                            ..Default::default()
-                        });
-                    }
-                }
-
                 for entity in &layer.entities {
-                    let tilemap = world.tilemaps.iter().find(|p| p.id == entity.tilemap_id).unwrap();
+                    let tilemap = match entity.tilemap_id {
+                        Some(tilemap_id) => world.tilemaps.iter().find(|p| p.id == tilemap_id).unwrap(),
+                        None => continue,
+                    };
                     state.renderer.draw_sprite(&Sprite {
                         position: entity.position + Vec2::new(140.0, 0.0),
                         size: Some(tilemap.tile_size),
@@ -110,25 +116,23 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(120.0).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let label = format!("FPS: {}", state.renderer.fps);
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(120.0).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let label = format!("FPS: {}", state.renderer.fps);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
-                    let label = format!("Delta: {:.2}", delta_average * 1000.0);
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+                let label = format!("Delta: {:.2}", delta_average * 1000.0);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
-                }
-            });
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -6,11 +6,10 @@ use capybara::assets::AssetsLoadingStatus;
 use capybara::assets::RawTexture;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::egui::Slider;
@@ -90,7 +89,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -228,96 +227,94 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
 
-                    ui.label(RichText::new(format!("FPS: {}", state.renderer.fps)).font(font.clone()).heading().color(color));
-                    ui.label(RichText::new(format!("Delta: {:.2}", delta_average * 1000.0)).font(font.clone()).heading().color(color));
-                    ui.label(RichText::new(format!("N: {}", self.objects.len())).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(format!("FPS: {}", state.renderer.fps)).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(format!("Delta: {:.2}", delta_average * 1000.0)).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(format!("N: {}", self.objects.len())).font(font.clone()).heading().color(color));
 
-                    ui.add_space(10.0);
+                ui.add_space(10.0);
 
-                    if let Some(response) = &self.emitter_last_response {
-                        ui.label(RichText::new(format!("Rays: {}", response.points.len())).font(font.clone()).heading().color(color));
-                        ui.label(RichText::new(format!("Tris: {}", response.shape.indices.len())).font(font.clone()).heading().color(color));
-                    }
+                if let Some(response) = &self.emitter_last_response {
+                    ui.label(RichText::new(format!("Rays: {}", response.points.len())).font(font.clone()).heading().color(color));
+                    ui.label(RichText::new(format!("Tris: {}", response.shape.indices.len())).font(font.clone()).heading().color(color));
+                }
 
-                    ui.style_mut().drag_value_text_style = TextStyle::Monospace;
-                    ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
-
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Distance:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.max_length, 0.0..=1000.0).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Angle:").font(font.clone()).heading().color(color));
-                    ui.add(
-                        Slider::new(&mut self.emitter.angle, -consts::PI..=consts::PI)
-                            .custom_formatter(|v, _| format!("{:.2}", v))
-                            .text_color(color),
-                    );
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Arc:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.arc, 0.0..=consts::TAU).custom_formatter(|v, _| format!("{:.2}", v)).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Frame rays:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.frame_rays, 0..=256).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Offset:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.offset, 0.0..=0.02).custom_formatter(|v, _| format!("{:.3}", v)).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Merge distance:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.merge_distance, 0.0..=10.0).custom_formatter(|v, _| format!("{:.1}", v)).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Tolerance:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.tolerance, 0.0..=0.003).custom_formatter(|v, _| format!("{:.4}", v)).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Extension:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.extension, 0.0..=256.0).custom_formatter(|v, _| format!("{:.1}", v)).text_color(color));
-
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Blur directions:").font(font.clone()).heading().color(color));
-                    if ui.add(Slider::new(&mut self.blur_directions, 0.0..=64.0).text_color(color)).changed() {
-                        self.update_shader_uniforms(state.renderer).unwrap();
-                    }
+                ui.style_mut().drag_value_text_style = TextStyle::Monospace;
+                ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
 
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Blur quality:").font(font.clone()).heading().color(color));
-                    if ui.add(Slider::new(&mut self.blur_quality, 0.0..=64.0).text_color(color)).changed() {
-                        self.update_shader_uniforms(state.renderer).unwrap();
-                    }
+                ui.add_space(10.0);
+                ui.label(RichText::new("Distance:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.max_length, 0.0..=1000.0).text_color(color));
 
-                    ui.add_space(6.0);
-                    ui.label(RichText::new("Blur size:").font(font.clone()).heading().color(color));
-                    if ui.add(Slider::new(&mut self.blur_size, 0.0..=64.0).text_color(color)).changed() {
-                        self.update_shader_uniforms(state.renderer).unwrap();
-                    }
+                ui.add_space(6.0);
+                ui.label(RichText::new("Angle:").font(font.clone()).heading().color(color));
+                ui.add(
+                    Slider::new(&mut self.emitter.angle, -consts::PI..=consts::PI)
+                        .custom_formatter(|v, _| format!("{:.2}", v))
+                        .text_color(color),
+                );
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Arc:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.arc, 0.0..=consts::TAU).custom_formatter(|v, _| format!("{:.2}", v)).text_color(color));
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Frame rays:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.frame_rays, 0..=256).text_color(color));
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Offset:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.offset, 0.0..=0.02).custom_formatter(|v, _| format!("{:.3}", v)).text_color(color));
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Merge distance:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.merge_distance, 0.0..=10.0).custom_formatter(|v, _| format!("{:.1}", v)).text_color(color));
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Tolerance:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.tolerance, 0.0..=0.003).custom_formatter(|v, _| format!("{:.4}", v)).text_color(color));
 
-                    ui.add_space(10.0);
-                    if ui.checkbox(&mut self.msaa, RichText::new("Buffer MSAA").font(font.clone()).heading().color(color)).changed() {
-                        if self.msaa {
-                            state.renderer.set_framebuffer_msaa(Some(4));
-                        } else {
-                            state.renderer.set_framebuffer_msaa(None);
-                        }
+                ui.add_space(6.0);
+                ui.label(RichText::new("Extension:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.extension, 0.0..=256.0).custom_formatter(|v, _| format!("{:.1}", v)).text_color(color));
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Blur directions:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.blur_directions, 0.0..=64.0).text_color(color)).changed() {
+                    self.update_shader_uniforms(state.renderer).unwrap();
+                }
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Blur quality:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.blur_quality, 0.0..=64.0).text_color(color)).changed() {
+                    self.update_shader_uniforms(state.renderer).unwrap();
+                }
+
+                ui.add_space(6.0);
+                ui.label(RichText::new("Blur size:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.blur_size, 0.0..=64.0).text_color(color)).changed() {
+                    self.update_shader_uniforms(state.renderer).unwrap();
+                }
+
+                ui.add_space(10.0);
+                if ui.checkbox(&mut self.msaa, RichText::new("Buffer MSAA").font(font.clone()).heading().color(color)).changed() {
+                    if self.msaa {
+                        state.renderer.set_framebuffer_msaa(Some(4));
+                    } else {
+                        state.renderer.set_framebuffer_msaa(None);
                     }
-                    ui.checkbox(&mut self.emitter.debug.enabled, RichText::new("Debug mode").font(font.clone()).heading().color(color));
                 }
-            });
+                ui.checkbox(&mut self.emitter.debug.enabled, RichText::new("Debug mode").font(font.clone()).heading().color(color));
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -8,11 +8,10 @@ use capybara::assets::loader::AssetsLoader;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::egui::Slider;
@@ -85,7 +84,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -165,42 +164,40 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let label = format!("FPS: {}", state.renderer.fps);
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(160.0).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let label = format!("FPS: {}", state.renderer.fps);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
-                    let label = format!("Delta: {:.2}", delta_average * 1000.0);
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+                let label = format!("Delta: {:.2}", delta_average * 1000.0);
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let label = format!("Ping: {} ms", *self.client.ping.read());
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                let label = format!("Ping: {} ms", *self.client.ping.read());
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let tick_average = self.tick_history.iter().sum::<f32>() / self.tick_history.len() as f32;
-                    let label = format!("Tick: {:.2} ms", tick_average);
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                let tick_average = self.tick_history.iter().sum::<f32>() / self.tick_history.len() as f32;
+                let label = format!("Tick: {:.2} ms", tick_average);
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    ui.style_mut().drag_value_text_style = TextStyle::Monospace;
-                    ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
+                ui.style_mut().drag_value_text_style = TextStyle::Monospace;
+                ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Objects count:").font(font.clone()).heading().color(color));
-                    if ui.add(Slider::new(&mut self.objects_count, 0..=10000).text_color(color).logarithmic(true)).changed() {
-                        if *self.client.status.read() == ConnectionStatus::Connected {
-                            self.client.send_packet(Packet::from_object(PACKET_SET_COUNT, &PacketSetCount { count: self.objects_count }));
-                        }
+                ui.add_space(10.0);
+                ui.label(RichText::new("Objects count:").font(font.clone()).heading().color(color));
+                if ui.add(Slider::new(&mut self.objects_count, 0..=10000).text_color(color).logarithmic(true)).changed() {
+                    if *self.client.status.read() == ConnectionStatus::Connected {
+                        self.client.send_packet(Packet::from_object(PACKET_SET_COUNT, &PacketSetCount { count: self.objects_count }));
                     }
                 }
-            });
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
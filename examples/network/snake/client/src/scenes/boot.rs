@@ -3,10 +3,9 @@ use capybara::anyhow::Result;
 use capybara::app::ApplicationState;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
-use capybara::egui::RawInput;
 use capybara::egui::Stroke;
 use capybara::egui::TextStyle;
 use capybara::scene::FrameCommand;
@@ -33,7 +32,7 @@ impl Scene<GlobalData> for BootScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
         if state.global.assets.load("./data/boot.zip") == AssetsLoadingStatus::Finished {
             state.ui.instantiate_assets(&state.global.assets, None);
 
@@ -54,8 +53,8 @@ impl Scene<GlobalData> for BootScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        Ok((state.ui.inner.read().run(input, |_| {}), None))
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, _context: &Context) -> Result<Option<FrameCommand>> {
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -12,11 +12,10 @@ use capybara::egui::panel::TopBottomSide;
 use capybara::egui::Align;
 use capybara::egui::Align2;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::Frame;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
 use capybara::egui::Layout;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::TopBottomPanel;
 use capybara::egui::Vec2;
@@ -83,7 +82,7 @@ impl Scene<GlobalData> for GameScene {
         Ok(None)
     }
 
-    fn frame(&mut self, mut state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, mut state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("frame");
         let now = Instant::now();
 
@@ -104,59 +103,57 @@ impl Scene<GlobalData> for GameScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("ui");
         self.debug_profiler.stop("input");
 
         let mut command = None;
-        let output = state.ui.inner.read().run(input, |context| {
-            let center = context.screen_rect().center();
-
-            if self.exit_menu_visible {
-                Window::new("Back to the menu? The game will be lost")
-                    .frame(components::frame())
-                    .movable(false)
-                    .resizable(false)
-                    .collapsible(false)
-                    .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
-                    .current_pos(center)
-                    .show(context, |ui| {
-                        ui.add_space(15.0);
-                        ui.horizontal(|ui| {
-                            if components::button_primary(ui, state.ui, state.renderer, "Yes", &mut self.play_button_state).clicked() {
-                                command = Some(FrameCommand::ChangeScene { name: "MenuScene".to_string() });
-                            }
-
-                            ui.add_space(32.0);
-
-                            if components::button_secondary(ui, state.ui, state.renderer, "No", &mut self.exit_button_state).clicked() {
-                                self.exit_menu_visible = false;
-                            }
-                        });
-                    });
-            }
-
-            if self.debug_enabled {
-                components::debug_window(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
-            }
+        let center = context.screen_rect().center();
 
-            TopBottomPanel::new(TopBottomSide::Bottom, Id::new("bottom_panel"))
-                .exact_height(30.0)
-                .frame(Frame::none())
-                .show_separator_line(false)
+        if self.exit_menu_visible {
+            Window::new("Back to the menu? The game will be lost")
+                .frame(components::frame())
+                .movable(false)
                 .resizable(false)
+                .collapsible(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .current_pos(center)
                 .show(context, |ui| {
-                    ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
-                        let text = format!("{}, ping {} ms", self.network.server_name, self.network.server_websocket.ping.read());
+                    ui.add_space(15.0);
+                    ui.horizontal(|ui| {
+                        if components::button_primary(ui, state.ui, state.renderer, "Yes", &mut self.play_button_state).clicked() {
+                            command = Some(FrameCommand::ChangeScene { name: "MenuScene".to_string() });
+                        }
+
+                        ui.add_space(32.0);
 
-                        ui.add_space(5.0);
-                        ui.label(RichText::new(text).heading().color(Color32::from_rgb(255, 255, 255)));
+                        if components::button_secondary(ui, state.ui, state.renderer, "No", &mut self.exit_button_state).clicked() {
+                            self.exit_menu_visible = false;
+                        }
                     });
                 });
-        });
+        }
+
+        if self.debug_enabled {
+            components::debug_window(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
+        }
+
+        TopBottomPanel::new(TopBottomSide::Bottom, Id::new("bottom_panel"))
+            .exact_height(30.0)
+            .frame(Frame::none())
+            .show_separator_line(false)
+            .resizable(false)
+            .show(context, |ui| {
+                ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                    let text = format!("{}, ping {} ms", self.network.server_name, self.network.server_websocket.ping.read());
+
+                    ui.add_space(5.0);
+                    ui.label(RichText::new(text).heading().color(Color32::from_rgb(255, 255, 255)));
+                });
+            });
 
         self.debug_profiler.stop("ui");
-        Ok((output, command))
+        Ok(command)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
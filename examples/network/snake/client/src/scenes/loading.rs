@@ -5,10 +5,9 @@ use capybara::app::ApplicationState;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::Align2;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::Frame;
-use capybara::egui::FullOutput;
 use capybara::egui::ProgressBar;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::Vec2;
 use capybara::egui::Window;
@@ -59,7 +58,7 @@ impl Scene<GlobalData> for LoadingScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
         if state.global.assets.load("./data/main.zip") == AssetsLoadingStatus::Finished {
             state.renderer.instantiate_assets(&state.global.assets, None);
             state.ui.instantiate_assets(&state.global.assets, None);
@@ -76,28 +75,26 @@ impl Scene<GlobalData> for LoadingScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            let center = context.screen_rect().center();
-            Window::new("Loading")
-                .frame(Frame::none())
-                .movable(false)
-                .resizable(false)
-                .collapsible(false)
-                .title_bar(false)
-                .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, -50.0))
-                .current_pos(center)
-                .default_width(300.0)
-                .show(context, |ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.label(RichText::new("Loading".to_string()).heading().color(Color32::from_rgb(255, 255, 255)));
-                        ui.add_space(15.0);
-                        ui.add(ProgressBar::new(state.global.assets.progress).fill(Color32::from_rgba_unmultiplied(200, 200, 200, 255)));
-                    });
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        let center = context.screen_rect().center();
+        Window::new("Loading")
+            .frame(Frame::none())
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .title_bar(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, -50.0))
+            .current_pos(center)
+            .default_width(300.0)
+            .show(context, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(RichText::new("Loading".to_string()).heading().color(Color32::from_rgb(255, 255, 255)));
+                    ui.add_space(15.0);
+                    ui.add(ProgressBar::new(state.global.assets.progress).fill(Color32::from_rgba_unmultiplied(200, 200, 200, 255)));
                 });
-        });
+            });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -11,11 +11,9 @@ use capybara::egui::Align2;
 use capybara::egui::Color32;
 use capybara::egui::Context;
 use capybara::egui::Frame;
-use capybara::egui::FullOutput;
 use capybara::egui::Grid;
 use capybara::egui::Id;
 use capybara::egui::Layout;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::Rounding;
 use capybara::egui::Slider;
@@ -101,45 +99,43 @@ impl Scene<GlobalData> for MenuScene {
         Ok(None)
     }
 
-    fn frame(&mut self, _state: ApplicationState<GlobalData>, _accumulator: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, _state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
         let now = Instant::now();
         self.network.process(now);
 
         Ok(None)
     }
 
-    fn ui(&mut self, mut state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
+    fn ui(&mut self, mut state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
         let mut command = None;
-        let output = state.ui.inner.clone().read().run(input, |context| {
-            TopBottomPanel::new(TopBottomSide::Top, Id::new("top_menu_panel"))
-                .exact_height(200.0)
-                .frame(Frame::none())
-                .show_separator_line(false)
-                .resizable(false)
-                .show(context, |ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.label(RichText::new("Template".to_string()).size(160.0).color(Color32::from_rgb(255, 255, 255)));
-                    })
-                });
-            TopBottomPanel::new(TopBottomSide::Bottom, Id::new("bottom_menu_panel"))
-                .exact_height(30.0)
-                .frame(Frame::none())
-                .show_separator_line(false)
-                .resizable(false)
-                .show(context, |ui| {
-                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                        ui.label(RichText::new("Template v0.1".to_string()).heading().color(Color32::from_rgb(255, 255, 255)));
-                    });
+        TopBottomPanel::new(TopBottomSide::Top, Id::new("top_menu_panel"))
+            .exact_height(200.0)
+            .frame(Frame::none())
+            .show_separator_line(false)
+            .resizable(false)
+            .show(context, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(RichText::new("Template".to_string()).size(160.0).color(Color32::from_rgb(255, 255, 255)));
+                })
+            });
+        TopBottomPanel::new(TopBottomSide::Bottom, Id::new("bottom_menu_panel"))
+            .exact_height(30.0)
+            .frame(Frame::none())
+            .show_separator_line(false)
+            .resizable(false)
+            .show(context, |ui| {
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    ui.label(RichText::new("Template v0.1".to_string()).heading().color(Color32::from_rgb(255, 255, 255)));
                 });
+            });
 
-            command = match self.sub_scene {
-                MenuSubScene::Main => self.subscene_main(&mut state, context),
-                MenuSubScene::Settings => self.subscene_settings(&mut state, context),
-                MenuSubScene::About => self.subscene_about(&mut state, context),
-            };
-        });
+        command = match self.sub_scene {
+            MenuSubScene::Main => self.subscene_main(&mut state, context),
+            MenuSubScene::Settings => self.subscene_settings(&mut state, context),
+            MenuSubScene::About => self.subscene_about(&mut state, context),
+        };
 
-        Ok((output, command))
+        Ok(command)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
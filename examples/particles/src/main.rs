@@ -5,11 +5,10 @@ use capybara::assets::loader::AssetsLoader;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::egui::Slider;
@@ -66,7 +65,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -115,51 +114,49 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let label = format!("FPS: {}", state.renderer.fps);
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let label = format!("FPS: {}", state.renderer.fps);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
-                    let label = format!("Delta: {:.2}", delta_average * 1000.0);
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+                let label = format!("Delta: {:.2}", delta_average * 1000.0);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    ui.style_mut().drag_value_text_style = TextStyle::Monospace;
-                    ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
+                ui.style_mut().drag_value_text_style = TextStyle::Monospace;
+                ui.style_mut().text_styles.get_mut(&TextStyle::Monospace).unwrap().size = 20.0;
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Amount:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.amount, 0..=1000).text_color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new("Amount:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.amount, 0..=1000).text_color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Period:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.period, 0.0..=2.0).text_color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new("Period:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.period, 0.0..=2.0).text_color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Lifetime:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.period, 0.0..=2.0).text_color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new("Lifetime:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.period, 0.0..=2.0).text_color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Emitter width:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.size.x, 0.0..=500.0).text_color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new("Emitter width:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.size.x, 0.0..=500.0).text_color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new("Emitter height:").font(font.clone()).heading().color(color));
-                    ui.add(Slider::new(&mut self.emitter.size.y, 0.0..=500.0).text_color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new("Emitter height:").font(font.clone()).heading().color(color));
+                ui.add(Slider::new(&mut self.emitter.size.y, 0.0..=500.0).text_color(color));
 
-                    ui.add_space(10.0);
-                    ui.checkbox(&mut self.debug, RichText::new("Debug mode").font(font.clone()).heading().color(color));
-                }
-            });
+                ui.add_space(10.0);
+                ui.checkbox(&mut self.debug, RichText::new("Debug mode").font(font.clone()).heading().color(color));
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -5,11 +5,10 @@ use capybara::assets::loader::AssetsLoader;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::panel::Side;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
-use capybara::egui::FullOutput;
 use capybara::egui::Id;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::fast_gpu;
@@ -36,7 +35,6 @@ struct GlobalData {
 
 #[derive(Default)]
 struct MainScene {
-    objects: Vec<Object>,
     initialized: bool,
     delta_history: VecDeque<f32>,
 
@@ -54,11 +52,6 @@ struct MainScene {
     debug: bool,
 }
 
-struct Object {
-    sprite: Sprite,
-    rigidbody: RigidBodyHandle,
-}
-
 impl Scene<GlobalData> for MainScene {
     fn activation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
         Ok(())
@@ -92,7 +85,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.delta_history.push_back(delta);
 
         if self.delta_history.len() > 100 {
@@ -105,6 +98,7 @@ impl Scene<GlobalData> for MainScene {
             state.renderer.instantiate_assets(&state.global.assets, None);
             state.ui.instantiate_assets(&state.global.assets, None);
             state.window.set_swap_interval(0);
+            state.world.pixels_per_meter = PIXELS_PER_METER;
 
             self.terrain = Sprite { size: Some(Vec2::new(state.renderer.viewport_size.x, 50.0)), ..Default::default() };
             self.terrain_collider = Some(state.physics.colliders.insert(ColliderBuilder::cuboid(100.0, 0.1).build()));
@@ -146,24 +140,15 @@ impl Scene<GlobalData> for MainScene {
                 let rigidbody_handle = state.physics.rigidbodies.insert(rigidbody);
                 state.physics.colliders.insert_with_parent(collider, rigidbody_handle, &mut state.physics.rigidbodies);
 
-                self.objects.push(Object { sprite, rigidbody: rigidbody_handle });
+                let entity = state.world.spawn();
+                state.world.insert(entity, sprite)?;
+                state.world.insert(entity, rigidbody_handle)?;
             }
 
             self.initialized = true;
         }
 
         if self.initialized {
-            let alpha = accumulator / state.physics.integration_parameters.dt;
-
-            for i in 0..self.objects.len() {
-                let object = &mut self.objects[i];
-                if let Some(interpolation_data) = state.physics.interpolation_data.get(&object.rigidbody) {
-                    self.objects[i].sprite.position = interpolation_data.get_position_interpolated(alpha) * PIXELS_PER_METER;
-                    self.objects[i].sprite.rotation = interpolation_data.get_rotation_interpolated(alpha);
-                    state.renderer.draw_sprite(&self.objects[i].sprite);
-                }
-            }
-
             if let Some(interpolation_data) = state.physics.interpolation_data.get(&self.wheel_left_rigidbody) {
                 self.wheel_left.position = interpolation_data.get_position_interpolated(alpha) * PIXELS_PER_METER;
                 self.wheel_left.rotation = interpolation_data.get_rotation_interpolated(alpha);
@@ -190,36 +175,35 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(120.0).resizable(false).show(context, |ui| {
-                if self.initialized {
-                    let font = FontId { size: 24.0, family: FontFamily::Monospace };
-                    let color = Color32::from_rgb(255, 255, 255);
-                    let label = format!("FPS: {}", state.renderer.fps);
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        SidePanel::new(Side::Left, Id::new("side_panel")).exact_width(120.0).resizable(false).show(context, |ui| {
+            if self.initialized {
+                let font = FontId { size: 24.0, family: FontFamily::Monospace };
+                let color = Color32::from_rgb(255, 255, 255);
+                let label = format!("FPS: {}", state.renderer.fps);
 
-                    let collisions = state.physics.events.collisions.read();
-                    let contacts = state.physics.events.contacts.read();
+                let collisions = state.physics.events.collisions.read();
+                let contacts = state.physics.events.contacts.read();
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
 
-                    let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
-                    let label = format!("Delta: {:.2}", delta_average * 1000.0);
+                let delta_average = self.delta_history.iter().sum::<f32>() / self.delta_history.len() as f32;
+                let label = format!("Delta: {:.2}", delta_average * 1000.0);
 
-                    ui.label(RichText::new(label).font(font.clone()).heading().color(color));
-                    ui.label(RichText::new(format!("N: {}", self.objects.len())).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(label).font(font.clone()).heading().color(color));
+                let objects_count = state.world.query::<(Sprite, RigidBodyHandle)>().len();
+                ui.label(RichText::new(format!("N: {}", objects_count)).font(font.clone()).heading().color(color));
 
-                    ui.add_space(10.0);
-                    ui.label(RichText::new(format!("Collisions: {}", collisions.len())).font(font.clone()).heading().color(color));
-                    ui.label(RichText::new(format!("Contacts: {}", contacts.len())).font(font.clone()).heading().color(color));
+                ui.add_space(10.0);
+                ui.label(RichText::new(format!("Collisions: {}", collisions.len())).font(font.clone()).heading().color(color));
+                ui.label(RichText::new(format!("Contacts: {}", contacts.len())).font(font.clone()).heading().color(color));
 
-                    ui.add_space(10.0);
-                    ui.checkbox(&mut self.debug, RichText::new("Debug mode").font(font.clone()).heading().color(color));
-                }
-            });
+                ui.add_space(10.0);
+                ui.checkbox(&mut self.debug, RichText::new("Debug mode").font(font.clone()).heading().color(color));
+            }
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -234,6 +218,7 @@ fn main() {
 fn main_internal() -> Result<()> {
     ApplicationContext::<GlobalData>::new("Physics", WindowStyle::Window { size: IVec2::new(1280, 720) }, Some(4))?
         .with_scene("MainScene", Box::<MainScene>::default())
+        .with_default_systems()
         .run("MainScene");
 
     Ok(())
@@ -10,12 +10,11 @@ use capybara::assets::AssetsLoadingStatus;
 use capybara::egui;
 use capybara::egui::Button;
 use capybara::egui::Color32;
+use capybara::egui::Context;
 use capybara::egui::FontFamily;
 use capybara::egui::FontId;
 use capybara::egui::Frame;
-use capybara::egui::FullOutput;
 use capybara::egui::Label;
-use capybara::egui::RawInput;
 use capybara::egui::RichText;
 use capybara::egui::SidePanel;
 use capybara::egui::Stroke;
@@ -189,7 +188,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("frame");
 
         if !self.initialized && state.global.assets.load("./data/data0.zip") == AssetsLoadingStatus::Finished {
@@ -237,85 +236,84 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("ui");
         self.debug_profiler.stop("input");
 
-        let output =
-            state.ui.inner.read().run(input, |context| {
-                if self.debug_enabled {
-                    self.debug_window.show(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
-                }
+        if self.debug_enabled {
+            self.debug_window.show(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
+        }
 
-                SidePanel::left("panel-left").frame(Frame::none()).resizable(false).show_separator_line(false).exact_width(200.0).show(
-                    context,
-                    |ui| {
-                        let selector_position = self.selector.position;
-
-                        ui.horizontal(|ui| {
-                            ui.add_space(10.0);
-                            ui.vertical(|ui| {
-                                ui.add_space(10.0);
-                                ui.add(Label::new(RichText::new(format!("position: {:.2} {:.2}", selector_position.x, selector_position.y))));
-
-                                if self.simulation.is_position_valid(selector_position) {
-                                    if let Some(chunk) = self.simulation.get_chunk(selector_position) {
-                                        let chunk = chunk.read();
-                                        let particle = chunk.get_particle(selector_position);
-                                        if let Some(particle) = particle {
-                                            ui.add(Label::new(format!("velocity: {:.2} {:.2}", particle.velocity.x, particle.velocity.y)));
-                                            ui.add(Label::new(format!("offset: {:.2} {:.2}", particle.offset.x, particle.offset.y)));
-                                            ui.add(Label::new(format!("hpressure: {:.2}", particle.hpressure)));
-                                        }
-                                    }
+        SidePanel::left("panel-left")
+            .frame(Frame::none())
+            .resizable(false)
+            .show_separator_line(false)
+            .exact_width(200.0)
+            .show(context, |ui| {
+                let selector_position = self.selector.position;
+
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    ui.vertical(|ui| {
+                        ui.add_space(10.0);
+                        ui.add(Label::new(RichText::new(format!("position: {:.2} {:.2}", selector_position.x, selector_position.y))));
+
+                        if self.simulation.is_position_valid(selector_position) {
+                            if let Some(chunk) = self.simulation.get_chunk(selector_position) {
+                                let chunk = chunk.read();
+                                let particle = chunk.get_particle(selector_position);
+                                if let Some(particle) = particle {
+                                    ui.add(Label::new(format!("velocity: {:.2} {:.2}", particle.velocity.x, particle.velocity.y)));
+                                    ui.add(Label::new(format!("offset: {:.2} {:.2}", particle.offset.x, particle.offset.y)));
+                                    ui.add(Label::new(format!("hpressure: {:.2}", particle.hpressure)));
                                 }
-                            });
-                        })
-                    },
-                );
-
-                SidePanel::right("panel-right").frame(Frame::none()).resizable(false).show_separator_line(false).show(context, |ui| {
-                    let size = egui::Vec2::new(100.0, 0.0);
-                    let definitions = self.simulation.definitions.read();
-
-                    for (r#type, definition) in definitions.iter().enumerate() {
-                        let selected = self.selector.particle_type == r#type;
-                        if ui.add(Button::new(&definition.name).min_size(size).selected(selected)).clicked() {
-                            self.selector.particle_type = r#type;
-                            self.selector.particle_definition = Some((*definition).clone());
-                            self.selector.update();
-                            self.rigidbody_mode = false;
+                            }
                         }
-                    }
-                    drop(definitions);
+                    });
+                })
+            });
 
-                    ui.add_space(20.0);
+        SidePanel::right("panel-right").frame(Frame::none()).resizable(false).show_separator_line(false).show(context, |ui| {
+            let size = egui::Vec2::new(100.0, 0.0);
+            let definitions = self.simulation.definitions.read();
+
+            for (r#type, definition) in definitions.iter().enumerate() {
+                let selected = self.selector.particle_type == r#type;
+                if ui.add(Button::new(&definition.name).min_size(size).selected(selected)).clicked() {
+                    self.selector.particle_type = r#type;
+                    self.selector.particle_definition = Some((*definition).clone());
+                    self.selector.update();
+                    self.rigidbody_mode = false;
+                }
+            }
+            drop(definitions);
 
-                    if ui.add(Button::new("Rigodbody").min_size(size).selected(self.rigidbody_mode)).clicked() {
-                        self.selector.particle_type = usize::MAX;
-                        self.selector.particle_definition = None;
-                        self.selector.update();
-                        self.rigidbody_mode = !self.rigidbody_mode;
-                    }
+            ui.add_space(20.0);
 
-                    ui.add_space(20.0);
+            if ui.add(Button::new("Rigodbody").min_size(size).selected(self.rigidbody_mode)).clicked() {
+                self.selector.particle_type = usize::MAX;
+                self.selector.particle_definition = None;
+                self.selector.update();
+                self.rigidbody_mode = !self.rigidbody_mode;
+            }
 
-                    if ui.add(Button::new("Load").min_size(size)).clicked() {
-                        persistence::load("saves/test.lvl", &mut self.simulation, state.renderer, state.physics);
-                    }
+            ui.add_space(20.0);
 
-                    if ui.add(Button::new("Save").min_size(size)).clicked() {
-                        persistence::save("saves/test.lvl", &mut self.simulation);
-                    }
+            if ui.add(Button::new("Load").min_size(size)).clicked() {
+                persistence::load("saves/test.lvl", &mut self.simulation, state.renderer, state.physics);
+            }
 
-                    ui.add_space(20.0);
+            if ui.add(Button::new("Save").min_size(size)).clicked() {
+                persistence::save("saves/test.lvl", &mut self.simulation);
+            }
 
-                    ui.checkbox(&mut self.force_all_chunks, "Force all");
-                });
-            });
+            ui.add_space(20.0);
+
+            ui.checkbox(&mut self.force_all_chunks, "Force all");
+        });
 
         self.debug_profiler.stop("ui");
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
@@ -10,6 +10,7 @@ use scenes::boot::BootScene;
 use scenes::game::GameScene;
 use scenes::loading::LoadingScene;
 use scenes::menu::MenuScene;
+use scenes::pause::PauseScene;
 use scenes::GlobalData;
 
 pub mod scenes;
@@ -27,6 +28,7 @@ fn main_internal() -> Result<()> {
         .with_scene("LoadingScene", Box::<LoadingScene>::default())
         .with_scene("MenuScene", Box::<MenuScene>::default())
         .with_scene("GameScene", Box::<GameScene>::default())
+        .with_scene("PauseScene", Box::<PauseScene>::default())
         .run("BootScene");
 
     Ok(())
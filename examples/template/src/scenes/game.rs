@@ -1,14 +1,8 @@
 use super::GlobalData;
-use crate::ui::components;
-use crate::ui::state::WidgetState;
 use capybara::anyhow::Result;
 use capybara::app::ApplicationState;
-use capybara::egui::Align2;
 use capybara::egui::Color32;
-use capybara::egui::FullOutput;
-use capybara::egui::RawInput;
-use capybara::egui::Vec2;
-use capybara::egui::Window;
+use capybara::egui::Context;
 use capybara::glam::Vec4;
 use capybara::scene::FrameCommand;
 use capybara::scene::Scene;
@@ -23,9 +17,7 @@ use capybara::window::Key;
 
 #[derive(Default)]
 pub struct GameScene {
-    play_button_state: WidgetState,
-    exit_button_state: WidgetState,
-    exit_menu_visible: bool,
+    pause_requested: bool,
 
     debug_enabled: bool,
     debug_window: DebugWindow,
@@ -36,7 +28,7 @@ pub struct GameScene {
 
 impl Scene<GlobalData> for GameScene {
     fn activation(&mut self, state: ApplicationState<GlobalData>) -> Result<()> {
-        self.exit_menu_visible = false;
+        self.pause_requested = false;
         self.debug_window.plot_definitions = vec![
             ProfilerPlotDefinition { name: String::from("input"), label: String::from("Input average"), color: Color32::RED },
             ProfilerPlotDefinition { name: String::from("fixed"), label: String::from("Fixed average"), color: Color32::GREEN },
@@ -57,7 +49,7 @@ impl Scene<GlobalData> for GameScene {
 
         if let InputEvent::KeyPress { key, repeat: _, modifiers } = event {
             if key == Key::Escape {
-                self.exit_menu_visible = !self.exit_menu_visible;
+                self.pause_requested = true;
             } else if key == Key::KeyD && modifiers.shift {
                 self.debug_enabled = !self.debug_enabled;
                 self.debug_profiler.enabled = !self.debug_profiler.enabled;
@@ -76,7 +68,7 @@ impl Scene<GlobalData> for GameScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, delta: f32) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("frame");
 
         if self.debug_enabled {
@@ -88,45 +80,22 @@ impl Scene<GlobalData> for GameScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
         self.debug_profiler.start("ui");
         self.debug_profiler.stop("input");
 
-        let mut command = None;
-        let output = state.ui.inner.read().run(input, |context| {
-            let center = context.screen_rect().center();
-
-            if self.exit_menu_visible {
-                Window::new("Back to the menu? The game will be lost")
-                    .frame(components::frame())
-                    .movable(false)
-                    .resizable(false)
-                    .collapsible(false)
-                    .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
-                    .current_pos(center)
-                    .show(context, |ui| {
-                        ui.add_space(15.0);
-                        ui.horizontal(|ui| {
-                            if components::button_primary(ui, state.ui, state.renderer, "Yes", &mut self.play_button_state).clicked() {
-                                command = Some(FrameCommand::ChangeScene { name: "MenuScene".to_string() });
-                            }
-
-                            ui.add_space(32.0);
-
-                            if components::button_secondary(ui, state.ui, state.renderer, "No", &mut self.exit_button_state).clicked() {
-                                self.exit_menu_visible = false;
-                            }
-                        });
-                    });
-            }
+        if self.debug_enabled {
+            self.debug_window.show(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
+        }
 
-            if self.debug_enabled {
-                self.debug_window.show(context, &mut self.debug_console, &self.debug_profiler, &mut self.debug_collector);
-            }
-        });
+        let mut command = None;
+        if self.pause_requested {
+            self.pause_requested = false;
+            command = Some(FrameCommand::PushScene { name: "PauseScene".to_string() });
+        }
 
         self.debug_profiler.stop("ui");
-        Ok((output, command))
+        Ok(command)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {
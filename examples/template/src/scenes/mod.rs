@@ -6,6 +6,7 @@ pub mod boot;
 pub mod game;
 pub mod loading;
 pub mod menu;
+pub mod pause;
 
 pub const SETTINGS_MASTER_VOLUME: &str = "MASTER_VOLUME";
 pub const SETTINGS_MUSIC_VOLUME: &str = "MUSIC_VOLUME";
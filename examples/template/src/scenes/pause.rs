@@ -0,0 +1,88 @@
+use super::GlobalData;
+use crate::ui::components;
+use crate::ui::state::WidgetState;
+use capybara::anyhow::Result;
+use capybara::app::ApplicationState;
+use capybara::egui::Align2;
+use capybara::egui::Context;
+use capybara::egui::Vec2;
+use capybara::egui::Window;
+use capybara::scene::FrameCommand;
+use capybara::scene::Scene;
+use capybara::scene::SceneFlags;
+use capybara::window::InputEvent;
+use capybara::window::Key;
+
+#[derive(Default)]
+pub struct PauseScene {
+    yes_button_state: WidgetState,
+    no_button_state: WidgetState,
+    close_requested: bool,
+}
+
+impl Scene<GlobalData> for PauseScene {
+    fn activation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn deactivation(&mut self, _state: ApplicationState<GlobalData>) -> Result<()> {
+        Ok(())
+    }
+
+    fn input(&mut self, _state: ApplicationState<GlobalData>, event: InputEvent) -> Result<()> {
+        if let InputEvent::KeyPress { key: Key::Escape, repeat: false, modifiers: _ } = event {
+            self.close_requested = true;
+        }
+
+        Ok(())
+    }
+
+    fn fixed(&mut self, _state: ApplicationState<GlobalData>) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn frame(&mut self, _state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+        Ok(None)
+    }
+
+    fn ui(&mut self, state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        let mut command = None;
+        let center = context.screen_rect().center();
+
+        Window::new("Back to the menu? The game will be lost")
+            .frame(components::frame())
+            .movable(false)
+            .resizable(false)
+            .collapsible(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .current_pos(center)
+            .show(context, |ui| {
+                ui.add_space(15.0);
+                ui.horizontal(|ui| {
+                    if components::button_primary(ui, state.ui, state.renderer, "Yes", &mut self.yes_button_state).clicked() {
+                        command = Some(FrameCommand::ChangeScene { name: "MenuScene".to_string() });
+                    }
+
+                    ui.add_space(32.0);
+
+                    if components::button_secondary(ui, state.ui, state.renderer, "No", &mut self.no_button_state).clicked() {
+                        self.close_requested = true;
+                    }
+                });
+            });
+
+        if command.is_none() && self.close_requested {
+            command = Some(FrameCommand::PopScene);
+        }
+
+        Ok(command)
+    }
+
+    fn reset(&self) -> Box<dyn Scene<GlobalData>> {
+        Box::<Self>::default()
+    }
+
+    fn flags(&self) -> SceneFlags {
+        SceneFlags { render_below: true, update_below: false, input_passthrough: false }
+    }
+}
@@ -4,8 +4,7 @@ use capybara::app::ApplicationState;
 use capybara::assets::loader::AssetsLoader;
 use capybara::assets::AssetsLoadingStatus;
 use capybara::egui::CentralPanel;
-use capybara::egui::FullOutput;
-use capybara::egui::RawInput;
+use capybara::egui::Context;
 use capybara::egui::ScrollArea;
 use capybara::fast_gpu;
 use capybara::glam::IVec2;
@@ -47,7 +46,7 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn frame(&mut self, state: ApplicationState<GlobalData>, _accumulator: f32, _delta: f32) -> Result<Option<FrameCommand>> {
+    fn frame(&mut self, state: ApplicationState<GlobalData>, _alpha: f32, _delta: f32) -> Result<Option<FrameCommand>> {
         if !self.initialized && state.global.assets.load("./data/data0.zip") == AssetsLoadingStatus::Finished {
             state.ui.instantiate_assets(&state.global.assets, None);
             self.initialized = true;
@@ -56,18 +55,16 @@ impl Scene<GlobalData> for MainScene {
         Ok(None)
     }
 
-    fn ui(&mut self, state: ApplicationState<GlobalData>, input: RawInput) -> Result<(FullOutput, Option<FrameCommand>)> {
-        let output = state.ui.inner.read().run(input, |context| {
-            CentralPanel::default().show(context, |ui| {
-                ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
-                    if self.initialized {
-                        self.test.ui(ui);
-                    }
-                });
+    fn ui(&mut self, _state: ApplicationState<GlobalData>, context: &Context) -> Result<Option<FrameCommand>> {
+        CentralPanel::default().show(context, |ui| {
+            ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
+                if self.initialized {
+                    self.test.ui(ui);
+                }
             });
         });
 
-        Ok((output, None))
+        Ok(None)
     }
 
     fn reset(&self) -> Box<dyn Scene<GlobalData>> {